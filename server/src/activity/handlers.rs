@@ -0,0 +1,87 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::activity::service;
+use crate::config::AppState;
+use crate::dto::{PageParams, Response};
+
+// Returns the changes made to a board, its columns and its tasks, most recent first
+#[axum_macros::debug_handler]
+pub async fn get_board_activity_handler(
+    path: Path<String>,
+    Query(params): Query<PageParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting activity of board {}", board_id);
+    match service::get_board_activity(&board_id, &params, state.get_activity_collection()).await {
+        Ok(page) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(page),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_board_activity_handler] Error getting activity of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Returns the changes made to a task, most recent first
+#[axum_macros::debug_handler]
+pub async fn get_task_history_handler(
+    path: Path<String>,
+    Query(params): Query<PageParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!("Getting history of task {}", task_id);
+    match service::get_task_history(&task_id, &params, state.get_activity_collection()).await {
+        Ok(page) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(page),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_task_history_handler] Error getting history of task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::activity::model::{Activity, ActivityAction, ActivityEntity, FieldChange};
+use crate::error::{ActivityRepoError, AppError, Result};
+
+pub async fn parse_activities(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<Activity>> {
+    let mut result: Vec<Activity> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_activity(&doc?)?);
+    }
+    Ok(result)
+}
+
+fn get_value(doc: &bson::document::Document, field_name: &str) -> Option<serde_json::Value> {
+    doc.get(field_name)
+        .filter(|v| !matches!(v, bson::Bson::Null))
+        .map(|v| v.clone().into_relaxed_extjson())
+}
+
+fn get_changes(doc: &bson::document::Document) -> Vec<FieldChange> {
+    doc.get_array("changes")
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_document())
+                .filter_map(|change| {
+                    Some(FieldChange {
+                        field: change.get_str("field").ok()?.to_owned(),
+                        before: get_value(change, "before"),
+                        after: get_value(change, "after"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn to_bson(value: &Option<serde_json::Value>) -> bson::Bson {
+    value
+        .as_ref()
+        .and_then(|v| bson::to_bson(v).ok())
+        .unwrap_or(bson::Bson::Null)
+}
+
+pub fn doc_to_activity(doc: &bson::document::Document) -> Result<Activity> {
+    let id = doc.get_str("id")?;
+    let board_id = doc.get_str("board_id")?;
+    let entity = doc.get_str("entity")?;
+    let entity_id = doc.get_str("entity_id")?;
+    let action = doc.get_str("action")?;
+    let actor = doc.get_str("actor").ok();
+    let created_at = doc.get_datetime("created_at")?;
+    match (
+        Uuid::from_str(id),
+        Uuid::from_str(board_id),
+        ActivityEntity::from_str(entity),
+        Uuid::from_str(entity_id),
+        ActivityAction::from_str(action),
+    ) {
+        (Ok(activity_uuid), Ok(board_uuid), Ok(entity), Ok(entity_uuid), Ok(action)) => {
+            Ok(Activity {
+                id: activity_uuid,
+                board_id: board_uuid,
+                entity,
+                entity_id: entity_uuid,
+                action,
+                actor: actor.map(|a| a.to_owned()),
+                changes: get_changes(doc),
+                created_at: chrono::DateTime::from(*created_at),
+            })
+        }
+        _ => Err(AppError::ActivityRepo(ActivityRepoError::DecodeError(
+            format!("Activity doesnt have id, board_id, entity or action {}", id),
+        ))),
+    }
+}
+
+pub fn activity_to_doc(activity: &Activity) -> bson::document::Document {
+    doc! {
+        "id" : activity.id.to_string(),
+        "board_id" : activity.board_id.to_string(),
+        "entity" : activity.entity.to_string(),
+        "entity_id" : activity.entity_id.to_string(),
+        "action" : activity.action.to_string(),
+        "actor" : activity.actor.clone(),
+        "changes" : activity.changes.iter().map(|c| doc! {
+            "field" : c.field.clone(),
+            "before" : to_bson(&c.before),
+            "after" : to_bson(&c.after),
+        }).collect::<Vec<bson::document::Document>>(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(activity.created_at)
+    }
+}
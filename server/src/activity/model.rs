@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+/// A change made to a board, column or task. Activities are only ever appended.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Activity {
+    pub id: uuid::Uuid,
+    pub board_id: uuid::Uuid,
+    pub entity: ActivityEntity,
+    pub entity_id: uuid::Uuid,
+    pub action: ActivityAction,
+    /// User who made the change, if known.
+    pub actor: Option<String>,
+    pub changes: Vec<FieldChange>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ActivityEntity {
+    Board,
+    Column,
+    Task,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ActivityAction {
+    Created,
+    Updated,
+    /// The task changed column.
+    Moved,
+    Archived,
+    Unarchived,
+    /// Moved to the trash.
+    Deleted,
+    /// Brought back from the trash.
+    Restored,
+}
+
+/// Value of a field before and after a change, missing when the field was unset.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
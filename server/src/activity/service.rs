@@ -0,0 +1,64 @@
+use bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::activity::mapper::{activity_to_doc, parse_activities};
+use crate::activity::model::Activity;
+use crate::dto::{Page, PageParams};
+use crate::error::Result;
+
+async fn get_page(
+    filter: Document,
+    params: &PageParams,
+    collection: Collection<Document>,
+) -> Result<Page<Activity>> {
+    let total = collection.count_documents(filter.clone(), None).await?;
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((params.page() - 1) * params.per_page())
+        .limit(params.per_page() as i64)
+        .build();
+    let cursor = collection.find(filter, options).await?;
+    Ok(Page {
+        items: parse_activities(cursor).await?,
+        page: params.page(),
+        per_page: params.per_page(),
+        total,
+    })
+}
+
+/// Returns the changes made to a board, its columns and its tasks, most recent first.
+pub async fn get_board_activity(
+    board_id: &String,
+    params: &PageParams,
+    collection: Collection<Document>,
+) -> Result<Page<Activity>> {
+    get_page(doc! { "board_id": board_id }, params, collection).await
+}
+
+/// Returns the changes made to a task, most recent first.
+pub async fn get_task_history(
+    task_id: &String,
+    params: &PageParams,
+    collection: Collection<Document>,
+) -> Result<Page<Activity>> {
+    let filter = doc! { "entity": "task", "entity_id": task_id };
+    get_page(filter, params, collection).await
+}
+
+/// Appends the given activities. Failures are only logged, as activities are
+/// recorded after the changes they describe succeeded.
+pub async fn record(activities: Vec<Activity>, collection: Collection<Document>) {
+    if activities.is_empty() {
+        return;
+    }
+    debug!("[record] Recording {} activities", activities.len());
+    let docs = activities
+        .iter()
+        .map(activity_to_doc)
+        .collect::<Vec<Document>>();
+    if let Err(e) = collection.insert_many(docs, None).await {
+        error!("ERROR [record] Couldn't record activities: {:?}", e);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::activity::model::{Activity, ActivityAction, ActivityEntity, FieldChange};
+use crate::board::model::Board;
+use crate::task::model::Task;
+
+/// Fields that change along with every other change and carry no information.
+const IGNORED_FIELDS: [&str; 1] = ["updated_at"];
+
+fn to_value<T: Serialize>(entity: Option<&T>) -> Value {
+    entity
+        .and_then(|e| serde_json::to_value(e).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// Compares the top level fields of two serialized entities, a missing entity
+/// or field counts as unset.
+pub fn diff_fields(before: &Value, after: &Value) -> Vec<FieldChange> {
+    let fields = |v: &Value| {
+        v.as_object()
+            .map(|o| o.keys().cloned().collect::<Vec<String>>())
+            .unwrap_or_default()
+    };
+    fields(before)
+        .into_iter()
+        .chain(fields(after))
+        .filter(|f| !IGNORED_FIELDS.contains(&f.as_str()))
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .filter_map(|field| {
+            let value = |v: &Value| v.get(&field).filter(|v| !v.is_null()).cloned();
+            let (before, after) = (value(before), value(after));
+            (before != after).then_some(FieldChange {
+                field,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+fn action_of(before: &Value, after: &Value, changes: &[FieldChange]) -> ActivityAction {
+    let changed = |field: &str| changes.iter().any(|c| c.field == field);
+    match (before.is_null(), after.is_null()) {
+        (true, _) => ActivityAction::Created,
+        (_, true) => ActivityAction::Deleted,
+        _ if changed("archived_at") => match after.get("archived_at") {
+            Some(Value::Null) | None => ActivityAction::Unarchived,
+            Some(_) => ActivityAction::Archived,
+        },
+        _ if changed("column_id") => ActivityAction::Moved,
+        _ => ActivityAction::Updated,
+    }
+}
+
+fn new_activity(
+    entity: ActivityEntity,
+    entity_id: uuid::Uuid,
+    board_id: uuid::Uuid,
+    before: Value,
+    after: Value,
+    actor: Option<&str>,
+) -> Option<Activity> {
+    let changes = diff_fields(&before, &after);
+    if changes.is_empty() {
+        return None;
+    }
+    Some(Activity {
+        id: uuid::Uuid::new_v4(),
+        board_id,
+        entity,
+        entity_id,
+        action: action_of(&before, &after, &changes),
+        actor: actor.map(|a| a.to_owned()),
+        changes,
+        created_at: Utc::now(),
+    })
+}
+
+/// Activities of the tasks that differ between `before` and `after`, tasks
+/// missing from `before` were created and tasks missing from `after` deleted.
+pub fn task_activities(before: &[Task], after: &[Task], actor: Option<&str>) -> Vec<Activity> {
+    let stored = before
+        .iter()
+        .map(|t| (t.id, t))
+        .collect::<HashMap<uuid::Uuid, &Task>>();
+    let deleted = before
+        .iter()
+        .filter(|b| after.iter().all(|a| a.id != b.id))
+        .map(|b| (Some(b), None));
+    after
+        .iter()
+        .map(|a| (stored.get(&a.id).copied(), Some(a)))
+        .chain(deleted)
+        .filter_map(|(before, after)| {
+            let task = after.or(before)?;
+            new_activity(
+                ActivityEntity::Task,
+                task.id,
+                task.board_id,
+                to_value(before),
+                to_value(after),
+                actor,
+            )
+        })
+        .collect()
+}
+
+/// Activities of a board and of its columns, the board only lists the ids of
+/// its columns as they get their own activities.
+pub fn board_activities(
+    before: Option<&Board>,
+    after: Option<&Board>,
+    actor: Option<&str>,
+) -> Vec<Activity> {
+    let Some(board) = after.or(before) else {
+        return vec![];
+    };
+    let board_value = |b: Option<&Board>| {
+        let mut value = to_value(b);
+        if let (Some(b), Some(object)) = (b, value.as_object_mut()) {
+            object.insert(
+                "columns".to_owned(),
+                b.columns.iter().map(|c| c.id.to_string()).collect(),
+            );
+        }
+        value
+    };
+    let mut activities = new_activity(
+        ActivityEntity::Board,
+        board.id,
+        board.id,
+        board_value(before),
+        board_value(after),
+        actor,
+    )
+    .into_iter()
+    .collect::<Vec<Activity>>();
+    if let (Some(before), Some(after)) = (before, after) {
+        let column_ids = before
+            .columns
+            .iter()
+            .chain(after.columns.iter())
+            .map(|c| c.id)
+            .collect::<BTreeSet<uuid::Uuid>>();
+        activities.extend(column_ids.into_iter().filter_map(|id| {
+            new_activity(
+                ActivityEntity::Column,
+                id,
+                board.id,
+                to_value(before.column(id)),
+                to_value(after.column(id)),
+                actor,
+            )
+        }));
+    }
+    activities
+}
+
+/// Marks the tasks created by `activities` as brought back from the trash.
+pub fn as_restored(activities: Vec<Activity>) -> Vec<Activity> {
+    activities
+        .into_iter()
+        .map(|a| match a.action {
+            ActivityAction::Created => Activity {
+                action: ActivityAction::Restored,
+                ..a
+            },
+            _ => a,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::activity::model::{ActivityAction, ActivityEntity};
+    use crate::activity::utils::{board_activities, diff_fields, task_activities};
+    use crate::board::model::{Board, BoardColumn};
+    use crate::fixtures::{mock_board, mock_column, mock_task};
+    use crate::task::model::Task;
+
+    #[test]
+    fn it_diffs_changed_fields_only() {
+        let before = json!({ "title": "a", "assignee": "bob", "updated_at": "x" });
+        let after = json!({ "title": "b", "description": "new", "updated_at": "y" });
+
+        let changes = diff_fields(&before, &after);
+        let fields = changes
+            .iter()
+            .map(|c| c.field.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(fields, vec!["assignee", "description", "title"]);
+        assert_eq!(changes[0].before, Some(json!("bob")));
+        assert_eq!(changes[0].after, None);
+        assert_eq!(changes[2].after, Some(json!("b")));
+    }
+
+    #[test]
+    fn it_names_the_action_of_task_changes() {
+        let board_id = Uuid::new_v4();
+        let moved = mock_task(board_id, None);
+        let renamed = mock_task(board_id, None);
+        let untouched = mock_task(board_id, None);
+        let deleted = mock_task(board_id, None);
+        let created = mock_task(board_id, None);
+        let before = vec![
+            moved.clone(),
+            renamed.clone(),
+            untouched.clone(),
+            deleted.clone(),
+        ];
+        let after = vec![
+            Task {
+                column_id: Some(Uuid::new_v4()),
+                updated_at: Some(Utc::now()),
+                ..moved.clone()
+            },
+            Task {
+                title: String::from("renamed"),
+                ..renamed.clone()
+            },
+            untouched,
+            created.clone(),
+        ];
+
+        let activities = task_activities(&before, &after, Some("alice"));
+        let actions = activities
+            .iter()
+            .map(|a| (a.entity_id, a.action))
+            .collect::<Vec<(Uuid, ActivityAction)>>();
+        assert_eq!(
+            actions,
+            vec![
+                (moved.id, ActivityAction::Moved),
+                (renamed.id, ActivityAction::Updated),
+                (created.id, ActivityAction::Created),
+                (deleted.id, ActivityAction::Deleted),
+            ]
+        );
+        assert_eq!(activities[0].changes.len(), 1);
+        assert_eq!(activities[0].actor.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn it_records_columns_separately_from_their_board() {
+        let todo = mock_column("todo", false);
+        let done = mock_column("done", false);
+        let before = mock_board(vec![todo.clone()]);
+        let after = Board {
+            columns: vec![
+                BoardColumn {
+                    archived_at: Some(Utc::now()),
+                    ..todo.clone()
+                },
+                done.clone(),
+            ],
+            ..before.clone()
+        };
+
+        let activities = board_activities(Some(&before), Some(&after), None);
+        let entries = activities
+            .iter()
+            .map(|a| (a.entity, a.action))
+            .collect::<Vec<(ActivityEntity, ActivityAction)>>();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0], (ActivityEntity::Board, ActivityAction::Updated));
+        assert_eq!(activities[0].changes[0].field, "columns");
+        assert!(entries.contains(&(ActivityEntity::Column, ActivityAction::Archived)));
+        assert!(entries.contains(&(ActivityEntity::Column, ActivityAction::Created)));
+        assert!(board_activities(Some(&before), Some(&before), None).is_empty());
+    }
+}
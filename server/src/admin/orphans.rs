@@ -0,0 +1,163 @@
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use crate::activity::service as activity_service;
+use crate::activity::utils::task_activities;
+use crate::board::model::Board;
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::error::Result;
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::task::utils::move_tasks_to_column;
+use crate::transition::service as transition_service;
+use crate::transition::utils::column_transitions;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrphanReason {
+    MissingBoard,
+    MissingColumn,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrphanTask {
+    pub task: Task,
+    pub reason: OrphanReason,
+}
+
+/// Finds the tasks whose board or column doesn't exist anymore.
+pub fn find_orphans(boards: &[Board], tasks: &[Task]) -> Vec<OrphanTask> {
+    let columns = boards
+        .iter()
+        .map(|b| (b.id, b.columns.iter().map(|c| c.id).collect()))
+        .collect::<HashMap<Uuid, HashSet<Uuid>>>();
+    tasks
+        .iter()
+        .filter_map(|task| {
+            let reason = match (columns.get(&task.board_id), task.column_id) {
+                (None, _) => OrphanReason::MissingBoard,
+                (Some(board_columns), Some(column_id)) if !board_columns.contains(&column_id) => {
+                    OrphanReason::MissingColumn
+                }
+                _ => return None,
+            };
+            Some(OrphanTask {
+                task: task.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Reports the orphaned tasks and, when `fix` is set, deletes the tasks of
+/// missing boards (with their related data) and moves the tasks of missing
+/// columns to the unassigned tasks of their board.
+pub async fn run(state: AppState, fix: bool) -> Result<()> {
+    let boards = board_service::get_all_boards(state.get_boards_collection()).await?;
+    let tasks = task_service::get_all_tasks(state.get_tasks_collection()).await?;
+    let orphans = find_orphans(&boards, &tasks);
+    for orphan in orphans.iter() {
+        println!(
+            "{:?}\ttask={}\tboard={}\tcolumn={}\t{}",
+            orphan.reason,
+            orphan.task.id,
+            orphan.task.board_id,
+            orphan
+                .task
+                .column_id
+                .map(|c| c.to_string())
+                .unwrap_or_default(),
+            orphan.task.title
+        );
+    }
+    println!("Found {} orphaned tasks", orphans.len());
+    if !fix || orphans.is_empty() {
+        return Ok(());
+    }
+
+    let missing_boards = orphans
+        .iter()
+        .filter(|o| o.reason == OrphanReason::MissingBoard)
+        .map(|o| o.task.board_id)
+        .collect::<HashSet<Uuid>>();
+    for board_id in missing_boards {
+        let deleted = board_service::delete_with_tasks(&board_id.to_string(), &state).await?;
+        let board_tasks = tasks
+            .iter()
+            .filter(|t| t.board_id == board_id)
+            .cloned()
+            .collect::<Vec<Task>>();
+        activity_service::record(
+            task_activities(&board_tasks, &[], None),
+            state.get_activity_collection(),
+        )
+        .await;
+        println!("Deleted {} tasks of missing board {}", deleted, board_id);
+    }
+
+    let mut unassigned = Vec::new();
+    for board in boards.iter() {
+        let moved = orphans
+            .iter()
+            .filter(|o| o.reason == OrphanReason::MissingColumn && o.task.board_id == board.id)
+            .map(|o| o.task.clone())
+            .collect::<Vec<Task>>();
+        if moved.is_empty() {
+            continue;
+        }
+        let board_unassigned = tasks
+            .iter()
+            .filter(|t| t.board_id == board.id && t.column_id.is_none())
+            .cloned()
+            .collect::<Vec<Task>>();
+        unassigned.extend(move_tasks_to_column(&moved, &board_unassigned, None));
+    }
+    if !unassigned.is_empty() {
+        task_service::update_many(&unassigned, state.clone()).await?;
+        let before = tasks
+            .iter()
+            .filter(|t| unassigned.iter().any(|u| u.id == t.id))
+            .cloned()
+            .collect::<Vec<Task>>();
+        activity_service::record(
+            task_activities(&before, &unassigned, None),
+            state.get_activity_collection(),
+        )
+        .await;
+        transition_service::record(
+            column_transitions(&before, &unassigned),
+            state.get_transitions_collection(),
+        )
+        .await;
+        println!("Unassigned {} tasks of missing columns", unassigned.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::admin::orphans::{find_orphans, OrphanReason};
+    use crate::fixtures::{mock_board, mock_column, mock_task};
+
+    #[test]
+    fn it_finds_tasks_of_missing_boards_and_columns() {
+        let column = mock_column("column", false);
+        let board = mock_board(vec![column.clone()]);
+        let tasks = vec![
+            mock_task(board.id, Some(column.id)),
+            mock_task(board.id, None),
+            mock_task(board.id, Some(Uuid::new_v4())),
+            mock_task(Uuid::new_v4(), None),
+        ];
+
+        let orphans = find_orphans(&[board], &tasks);
+        assert_eq!(orphans.len(), 2);
+        assert_eq!(orphans[0].task.id, tasks[2].id);
+        assert_eq!(orphans[0].reason, OrphanReason::MissingColumn);
+        assert_eq!(orphans[1].task.id, tasks[3].id);
+        assert_eq!(orphans[1].reason, OrphanReason::MissingBoard);
+    }
+}
@@ -0,0 +1,276 @@
+use std::io;
+
+use axum::body::StreamBody;
+use axum::extract::{Multipart, Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use futures::TryStreamExt;
+use tracing::{debug, error};
+
+use crate::attachment::model::Attachment;
+use crate::attachment::service;
+use crate::attachment::store::ByteStream;
+use crate::attachment::utils::{
+    content_disposition, is_allowed_type, is_size_limit_error, limit_size,
+};
+use crate::config::AppState;
+use crate::dto::Response;
+use crate::error::{AppError, AttachmentRepoError};
+use crate::task::service as task_service;
+use crate::user::extractor::CurrentUser;
+
+// Returns the attachments of a task
+#[axum_macros::debug_handler]
+pub async fn get_attachments_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!("Getting attachments of task {}", task_id);
+    match service::get_attachments(&task_id, state.get_attachments_collection()).await {
+        Ok(attachments) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(attachments),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_attachments_handler] Error getting attachments of task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Uploads the `file` field of a multipart request as attachment of a task
+#[axum_macros::debug_handler]
+pub async fn attachment_upload_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!(
+        "[attachment_upload_handler] Uploading attachment to task {}",
+        task_id
+    );
+
+    async fn upload(
+        task_id: &String,
+        uploaded_by: Option<String>,
+        multipart: &mut Multipart,
+        state: AppState,
+    ) -> Result<Attachment, AppError> {
+        let task = task_service::get_task(task_id, state.get_tasks_collection()).await?;
+        let invalid = |msg: String| AttachmentRepoError::InvalidAttachment(msg);
+        let field = loop {
+            match multipart
+                .next_field()
+                .await
+                .map_err(|e| invalid(e.to_string()))?
+            {
+                Some(field) if field.name() == Some("file") => break field,
+                Some(_) => continue,
+                None => return Err(invalid(String::from("missing file field")).into()),
+            }
+        };
+        let filename = field.file_name().unwrap_or("attachment").to_owned();
+        let content_type = field
+            .content_type()
+            .map(|ct| ct.to_owned())
+            .filter(|ct| ct != "application/octet-stream")
+            .unwrap_or_else(|| {
+                mime_guess::from_path(&filename)
+                    .first_or_octet_stream()
+                    .to_string()
+            });
+        let limits = &state.attachment_limits;
+        if !is_allowed_type(&content_type, &limits.allowed_types) {
+            return Err(AttachmentRepoError::UnsupportedType(content_type).into());
+        }
+
+        let attachment = Attachment::new(&task, filename, content_type, uploaded_by);
+        let data: ByteStream = Box::pin(field.map_err(io::Error::other));
+        let size = state
+            .blob_store
+            .put(
+                &attachment.storage_key,
+                &attachment.content_type,
+                limit_size(data, limits.max_bytes),
+            )
+            .await
+            .map_err(|e| match is_size_limit_error(&e) {
+                true => AttachmentRepoError::TooLarge(limits.max_bytes),
+                false => AttachmentRepoError::Storage(e.to_string()),
+            })?;
+        let attachment = Attachment { size, ..attachment };
+        service::create(&attachment, state.get_attachments_collection()).await?;
+        Ok(attachment)
+    }
+
+    match upload(&task_id, user.map(|u| u.0), &mut multipart, state).await {
+        Ok(attachment) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(attachment),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[attachment_upload_handler] Error uploading attachment to task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Streams the content of an attachment
+#[axum_macros::debug_handler]
+pub async fn attachment_download_handler(
+    Path((task_id, attachment_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    debug!(
+        "[attachment_download_handler] Downloading attachment {} of task {}",
+        attachment_id, task_id
+    );
+
+    async fn open(
+        task_id: &String,
+        attachment_id: &String,
+        state: AppState,
+    ) -> Result<(Attachment, ByteStream<'static>), AppError> {
+        let attachment =
+            service::get_attachment(task_id, attachment_id, state.get_attachments_collection())
+                .await?;
+        let data = state
+            .blob_store
+            .get(&attachment.storage_key)
+            .await
+            .map_err(|e| AttachmentRepoError::Storage(e.to_string()))?;
+        Ok((attachment, data))
+    }
+
+    match open(&task_id, &attachment_id, state).await {
+        Ok((attachment, data)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, attachment.content_type.clone()),
+                // browsers must not guess a type that runs scripts out of the content
+                (header::X_CONTENT_TYPE_OPTIONS, String::from("nosniff")),
+                (
+                    header::CONTENT_DISPOSITION,
+                    content_disposition(&attachment.filename),
+                ),
+            ],
+            StreamBody::new(data),
+        )
+            .into_response(),
+        Err(e) => {
+            let msg = format!(
+                "[attachment_download_handler] Error downloading attachment {}: {:?}",
+                attachment_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::NOT_FOUND,
+                Json(Response::<()> {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+// Deletes an attachment and its content
+#[axum_macros::debug_handler]
+pub async fn attachment_delete_handler(
+    Path((task_id, attachment_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    debug!(
+        "[attachment_delete_handler] Deleting attachment {} of task {}",
+        attachment_id, task_id
+    );
+
+    async fn delete(
+        task_id: &String,
+        attachment_id: &String,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        let attachment =
+            service::get_attachment(task_id, attachment_id, state.get_attachments_collection())
+                .await?;
+        service::delete(
+            &attachment,
+            state.get_attachments_collection(),
+            state.blob_store.as_ref(),
+        )
+        .await
+    }
+
+    match delete(&task_id, &attachment_id, state).await {
+        Ok(_) => {
+            let msg = format!("Deleted attachment with id {}", attachment_id);
+            debug!("[attachment_delete_handler] {}", msg);
+            (
+                StatusCode::OK,
+                Json(Response {
+                    success: true,
+                    data: Some(msg),
+                    error_message: None,
+                    warnings: None,
+                }),
+            )
+        }
+        Err(e) => {
+            let msg = format!("Error in deleting attachment {}: {}", attachment_id, e);
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::attachment::model::Attachment;
+use crate::error::{AppError, AttachmentRepoError, Result};
+
+pub async fn parse_attachments(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<Attachment>> {
+    let mut result: Vec<Attachment> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_attachment(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_attachment(doc: &bson::document::Document) -> Result<Attachment> {
+    let id = doc.get_str("id")?;
+    let task_id = doc.get_str("task_id")?;
+    let board_id = doc.get_str("board_id")?;
+    let filename = doc.get_str("filename")?;
+    let content_type = doc.get_str("content_type")?;
+    let size = doc.get_i64("size")?;
+    let storage_key = doc.get_str("storage_key")?;
+    let uploaded_by = doc.get_str("uploaded_by").ok();
+    let created_at = doc.get_datetime("created_at")?;
+    match (
+        Uuid::from_str(id),
+        Uuid::from_str(task_id),
+        Uuid::from_str(board_id),
+    ) {
+        (Ok(attachment_uuid), Ok(task_uuid), Ok(board_uuid)) => Ok(Attachment {
+            id: attachment_uuid,
+            task_id: task_uuid,
+            board_id: board_uuid,
+            filename: filename.to_owned(),
+            content_type: content_type.to_owned(),
+            size: size as u64,
+            storage_key: storage_key.to_owned(),
+            uploaded_by: uploaded_by.map(|u| u.to_owned()),
+            created_at: chrono::DateTime::from(*created_at),
+        }),
+        _ => Err(AppError::AttachmentRepo(AttachmentRepoError::DecodeError(
+            format!("Attachment doesnt have id, task_id or board_id {}", id),
+        ))),
+    }
+}
+
+pub fn attachment_to_doc(attachment: &Attachment) -> bson::document::Document {
+    doc! {
+        "id" : attachment.id.to_string(),
+        "task_id" : attachment.task_id.to_string(),
+        "board_id" : attachment.board_id.to_string(),
+        "filename" : attachment.filename.clone(),
+        "content_type" : attachment.content_type.clone(),
+        "size" : attachment.size as i64,
+        "storage_key" : attachment.storage_key.clone(),
+        "uploaded_by" : attachment.uploaded_by.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(attachment.created_at)
+    }
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::task::model::Task;
+
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Attachment {
+    pub id: uuid::Uuid,
+    pub task_id: uuid::Uuid,
+    pub board_id: uuid::Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size: u64,
+    /// Key of the content in the blob store.
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub uploaded_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    pub fn new(
+        task: &Task,
+        filename: String,
+        content_type: String,
+        uploaded_by: Option<String>,
+    ) -> Self {
+        let id = uuid::Uuid::new_v4();
+        Self {
+            id,
+            task_id: task.id,
+            board_id: task.board_id,
+            filename,
+            content_type,
+            size: 0,
+            storage_key: format!("{}/{}/{}", task.board_id, task.id, id),
+            uploaded_by,
+            created_at: Utc::now(),
+        }
+    }
+}
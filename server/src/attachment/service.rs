@@ -0,0 +1,116 @@
+use bson::{doc, Document};
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::attachment::mapper::{attachment_to_doc, doc_to_attachment, parse_attachments};
+use crate::attachment::model::Attachment;
+use crate::attachment::store::BlobStore;
+use crate::error::{AppError, AttachmentRepoError, Result};
+
+pub async fn get_attachments(
+    task_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Attachment>> {
+    let filter = doc! { "task_id": task_id };
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
+        debug!("ERROR [get_attachments] {:?}", _e);
+        AttachmentRepoError::NotFound
+    })?;
+    parse_attachments(cursor).await
+}
+
+pub async fn get_attachments_of_tasks(
+    task_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<Vec<Attachment>> {
+    let ids = task_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    let filter = doc! { "task_id": { "$in": ids } };
+    let cursor = collection.find(filter, None).await?;
+    parse_attachments(cursor).await
+}
+
+pub async fn get_attachments_of_board(
+    board_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Attachment>> {
+    let filter = doc! { "board_id": board_id };
+    let cursor = collection.find(filter, None).await?;
+    parse_attachments(cursor).await
+}
+
+pub async fn get_attachment(
+    task_id: &String,
+    attachment_id: &String,
+    collection: Collection<Document>,
+) -> Result<Attachment> {
+    let filter = doc! { "id": attachment_id, "task_id": task_id };
+    let attachment_opt = collection
+        .find_one(filter, None)
+        .await
+        .map_err(|e| {
+            debug!(
+                "Error while getting attachment with id {}: {}",
+                attachment_id, e
+            );
+            AppError::MongoError(e)
+        })?
+        .and_then(|doc| doc_to_attachment(&doc).ok());
+
+    match attachment_opt {
+        Some(attachment) => Ok(attachment),
+        None => Err(AppError::AttachmentRepo(AttachmentRepoError::NotFound)),
+    }
+}
+
+pub async fn create(
+    attachment: &Attachment,
+    collection: Collection<Document>,
+) -> Result<&Attachment> {
+    debug!(
+        "[create_attachment] Creating attachment with id={}",
+        &attachment.id
+    );
+    collection
+        .insert_one(attachment_to_doc(attachment), None)
+        .await
+        .map_err(|_e| {
+            error!("ERROR [create_attachment] {:?}", _e);
+            AttachmentRepoError::InvalidAttachment(_e.to_string())
+        })?;
+    Ok(attachment)
+}
+
+/// Deletes the attachment along with its content.
+pub async fn delete(
+    attachment: &Attachment,
+    collection: Collection<Document>,
+    store: &dyn BlobStore,
+) -> Result<()> {
+    debug!(
+        "[delete_attachment] Deleting attachment with id={}",
+        attachment.id
+    );
+    store
+        .delete(&attachment.storage_key)
+        .await
+        .map_err(|e| AttachmentRepoError::Storage(e.to_string()))?;
+    let filter = doc! { "id": attachment.id.to_string() };
+    collection.delete_one(filter, None).await?;
+    Ok(())
+}
+
+/// Removes the content of attachments whose records were deleted already,
+/// failures are only logged as the records cannot be restored anyway.
+pub async fn delete_contents(attachments: &[Attachment], store: &dyn BlobStore) {
+    for attachment in attachments {
+        if let Err(e) = store.delete(&attachment.storage_key).await {
+            error!(
+                "ERROR [delete_contents] Couldn't delete content of attachment {}: {:?}",
+                attachment.id, e
+            );
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+
+use crate::attachment::store::{BlobStore, ByteStream};
+
+/// Keeps blobs as files under a root folder, keys being relative paths.
+#[derive(Debug, Clone)]
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_of(&self, key: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(key);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid blob key {}", key),
+            ));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalStore {
+    async fn put(
+        &self,
+        key: &str,
+        _content_type: &str,
+        mut data: ByteStream<'_>,
+    ) -> io::Result<u64> {
+        let path = self.path_of(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let mut file = fs::File::create(&path).await?;
+        let mut written = 0u64;
+        while let Some(chunk) = data.next().await {
+            match chunk {
+                Ok(chunk) => {
+                    file.write_all(&chunk).await?;
+                    written += chunk.len() as u64;
+                }
+                Err(e) => {
+                    // don't leave partial uploads around
+                    drop(file);
+                    fs::remove_file(&path).await.ok();
+                    return Err(e);
+                }
+            }
+        }
+        file.flush().await?;
+        Ok(written)
+    }
+
+    async fn get(&self, key: &str) -> io::Result<ByteStream<'static>> {
+        let file = fs::File::open(self.path_of(key)?).await?;
+        Ok(Box::pin(ReaderStream::new(file)))
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path_of(key)?).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Bytes;
+    use futures::{stream, StreamExt};
+
+    use crate::attachment::store::local::LocalStore;
+    use crate::attachment::store::BlobStore;
+
+    #[tokio::test]
+    async fn it_stores_reads_and_deletes_blobs() {
+        let root = std::env::temp_dir().join(format!("blobs-{}", uuid::Uuid::new_v4()));
+        let store = LocalStore::new(&root);
+        let chunks = vec![Ok(Bytes::from("hello ")), Ok(Bytes::from("world"))];
+
+        let written = store
+            .put("task/blob", "text/plain", Box::pin(stream::iter(chunks)))
+            .await
+            .unwrap();
+        assert_eq!(written, 11);
+
+        let mut content = Vec::new();
+        let mut data = store.get("task/blob").await.unwrap();
+        while let Some(chunk) = data.next().await {
+            content.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(content, b"hello world");
+
+        store.delete("task/blob").await.unwrap();
+        assert!(store.get("task/blob").await.is_err());
+        assert!(store.get("../outside").await.is_err());
+        std::fs::remove_dir_all(root).ok();
+    }
+}
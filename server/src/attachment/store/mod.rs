@@ -0,0 +1,37 @@
+use std::fmt::Debug;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use futures::Stream;
+
+use crate::attachment::store::local::LocalStore;
+use crate::attachment::store::s3::S3Store;
+use crate::EnvVars;
+
+pub mod local;
+pub mod s3;
+
+pub type ByteStream<'a> = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + 'a>>;
+
+/// Storage for the content of attachments, addressed by key.
+#[async_trait]
+pub trait BlobStore: Debug + Send + Sync {
+    /// Stores the content of `data` under `key`, returning the number of bytes written.
+    async fn put(&self, key: &str, content_type: &str, data: ByteStream<'_>) -> io::Result<u64>;
+
+    async fn get(&self, key: &str) -> io::Result<ByteStream<'static>>;
+
+    /// Removes the blob, deleting a missing blob is not an error.
+    async fn delete(&self, key: &str) -> io::Result<()>;
+}
+
+/// Builds the store selected by the `BLOB_STORE` environment variable (`local` or `s3`).
+pub fn from_env(env_vars: &EnvVars) -> Arc<dyn BlobStore> {
+    match env_vars.blob_store.as_str() {
+        "s3" => Arc::new(S3Store::new(env_vars.s3.clone())),
+        _ => Arc::new(LocalStore::new(env_vars.blob_store_path.clone())),
+    }
+}
@@ -0,0 +1,492 @@
+use std::io;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use hmac::{Hmac, Mac};
+use reqwest::{Method, StatusCode, Url};
+use sha2::{Digest, Sha256};
+
+use crate::attachment::store::{BlobStore, ByteStream};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Size of the parts of multipart uploads, the smallest S3 accepts. Only one
+/// part of an upload is held in memory at a time.
+const PART_SIZE: usize = 5 * 1024 * 1024;
+
+#[derive(Clone, Debug, Default)]
+pub struct S3Config {
+    /// Base url of the service, e.g. `https://s3.eu-west-1.amazonaws.com` or `http://localhost:9000`.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// Stores blobs in a bucket of an S3-compatible service (AWS, MinIO...),
+/// using path-style urls and V4 request signing.
+#[derive(Clone, Debug)]
+pub struct S3Store {
+    config: S3Config,
+    client: reqwest::Client,
+}
+
+impl S3Store {
+    pub fn new(config: S3Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn url_of(&self, key: &str, query: &[(&str, &str)]) -> io::Result<Url> {
+        let encoded_key = key
+            .split('/')
+            .map(uri_encode)
+            .collect::<Vec<String>>()
+            .join("/");
+        let mut url = Url::parse(&format!(
+            "{}/{}/{}",
+            self.config.endpoint.trim_end_matches('/'),
+            self.config.bucket,
+            encoded_key
+        ))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        content_type: Option<&str>,
+        body: Vec<u8>,
+    ) -> io::Result<reqwest::Response> {
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let headers = sign(&self.config, &method, &url, &payload_hash, Utc::now());
+        let mut request = self.client.request(method, url);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        if let Some(content_type) = content_type {
+            request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+        }
+        let response = request.body(body).send().await.map_err(io::Error::other)?;
+        match response.status().is_success() {
+            true => Ok(response),
+            false => Err(status_error(&response)),
+        }
+    }
+
+    /// Uploads the parts of a multipart upload, starting with `first`,
+    /// and completes it. Returns the size of the content.
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        first: Vec<u8>,
+        data: &mut ByteStream<'_>,
+        buffer: &mut Vec<u8>,
+    ) -> io::Result<u64> {
+        let mut size = 0u64;
+        let mut etags = Vec::new();
+        let mut part = first;
+        while !part.is_empty() {
+            size += part.len() as u64;
+            let part_number = (etags.len() + 1).to_string();
+            let url = self.url_of(
+                key,
+                &[("partNumber", &part_number), ("uploadId", upload_id)],
+            )?;
+            let response = self.send(Method::PUT, url, None, part).await?;
+            let etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .ok_or_else(|| io::Error::other("s3 part upload returned no etag"))?;
+            etags.push(etag.to_owned());
+            part = next_part(data, buffer).await?;
+        }
+        let url = self.url_of(key, &[("uploadId", upload_id)])?;
+        let body = complete_multipart_upload_body(&etags);
+        self.send(
+            Method::POST,
+            url,
+            Some("application/xml"),
+            body.into_bytes(),
+        )
+        .await?;
+        Ok(size)
+    }
+}
+
+/// Reads the next part of an upload out of `data`, `buffer` keeping what was
+/// read past the part. The part is empty once all the content was read.
+async fn next_part(data: &mut ByteStream<'_>, buffer: &mut Vec<u8>) -> io::Result<Vec<u8>> {
+    while buffer.len() < PART_SIZE {
+        match data.next().await {
+            Some(chunk) => buffer.extend_from_slice(&chunk?),
+            None => break,
+        }
+    }
+    let rest = buffer.split_off(buffer.len().min(PART_SIZE));
+    Ok(std::mem::replace(buffer, rest))
+}
+
+fn complete_multipart_upload_body(etags: &[String]) -> String {
+    let parts = etags
+        .iter()
+        .enumerate()
+        .map(|(i, etag)| {
+            format!(
+                "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                i + 1,
+                etag.replace('&', "&amp;").replace('"', "&quot;")
+            )
+        })
+        .collect::<String>();
+    format!(
+        "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+        parts
+    )
+}
+
+/// Extracts the upload id from the response to a CreateMultipartUpload request.
+fn upload_id_of(xml: &str) -> Option<&str> {
+    let start = xml.find("<UploadId>")? + "<UploadId>".len();
+    let end = start + xml[start..].find("</UploadId>")?;
+    Some(&xml[start..end])
+}
+
+fn status_error(response: &reqwest::Response) -> io::Error {
+    let kind = match response.status() {
+        StatusCode::NOT_FOUND => io::ErrorKind::NotFound,
+        StatusCode::FORBIDDEN => io::ErrorKind::PermissionDenied,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(
+        kind,
+        format!("s3 request failed with status {}", response.status()),
+    )
+}
+
+#[async_trait]
+impl BlobStore for S3Store {
+    async fn put(
+        &self,
+        key: &str,
+        content_type: &str,
+        mut data: ByteStream<'_>,
+    ) -> io::Result<u64> {
+        // the content is streamed part by part, unless it fits in a single one
+        let mut buffer = Vec::new();
+        let first = next_part(&mut data, &mut buffer).await?;
+        if first.len() < PART_SIZE {
+            let size = first.len() as u64;
+            self.send(
+                Method::PUT,
+                self.url_of(key, &[])?,
+                Some(content_type),
+                first,
+            )
+            .await?;
+            return Ok(size);
+        }
+
+        let url = self.url_of(key, &[("uploads", "")])?;
+        let response = self
+            .send(Method::POST, url, Some(content_type), Vec::new())
+            .await?;
+        let xml = response.text().await.map_err(io::Error::other)?;
+        let upload_id = upload_id_of(&xml)
+            .ok_or_else(|| io::Error::other("s3 multipart upload returned no upload id"))?
+            .to_owned();
+        match self
+            .upload_parts(key, &upload_id, first, &mut data, &mut buffer)
+            .await
+        {
+            Ok(size) => Ok(size),
+            Err(e) => {
+                // don't leave the uploaded parts around
+                if let Ok(url) = self.url_of(key, &[("uploadId", &upload_id)]) {
+                    self.send(Method::DELETE, url, None, Vec::new()).await.ok();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn get(&self, key: &str) -> io::Result<ByteStream<'static>> {
+        let response = self
+            .send(Method::GET, self.url_of(key, &[])?, None, Vec::new())
+            .await?;
+        Ok(Box::pin(response.bytes_stream().map_err(io::Error::other)))
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match self
+            .send(Method::DELETE, self.url_of(key, &[])?, None, Vec::new())
+            .await
+        {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the headers authenticating a request with AWS Signature Version 4.
+fn sign(
+    config: &S3Config,
+    method: &Method,
+    url: &Url,
+    payload_hash: &str,
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_owned(),
+    };
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method.as_str(),
+        url.path(),
+        canonical_query(url),
+        host,
+        payload_hash,
+        amz_date,
+        signed_headers,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = signing_key(&config.secret_key, &date, &config.region, "s3");
+    let signature = hex::encode(hmac(&key, string_to_sign.as_bytes()));
+    vec![
+        ("x-amz-date", amz_date),
+        ("x-amz-content-sha256", payload_hash.to_owned()),
+        (
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                config.access_key, scope, signed_headers, signature
+            ),
+        ),
+    ]
+}
+
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let k_region = hmac(&k_date, region.as_bytes());
+    let k_service = hmac(&k_region, service.as_bytes());
+    hmac(&k_service, b"aws4_request")
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encodes a path segment as required by the S3 canonical request.
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Sorts and encodes the query parameters as required by the S3 canonical request.
+fn canonical_query(url: &Url) -> String {
+    let mut pairs = url
+        .query_pairs()
+        .map(|(name, value)| format!("{}={}", uri_encode(&name), uri_encode(&value)))
+        .collect::<Vec<String>>();
+    pairs.sort();
+    pairs.join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+
+    use axum::body::Bytes;
+    use axum::extract::{DefaultBodyLimit, Path, Query, State};
+    use axum::http::{header, HeaderMap, StatusCode};
+    use axum::routing::put;
+    use axum::Router;
+    use futures::{stream, StreamExt};
+
+    use crate::attachment::store::s3::{signing_key, S3Config, S3Store};
+    use crate::attachment::store::BlobStore;
+
+    #[derive(Clone, Default)]
+    struct Stub {
+        objects: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+        /// Parts of the pending multipart uploads, by upload id.
+        uploads: Arc<Mutex<HashMap<String, Vec<Vec<u8>>>>>,
+    }
+
+    /// Minimal stand-in for an S3-compatible service, keeping objects in memory.
+    async fn start_stub() -> (SocketAddr, Stub) {
+        let stub = Stub::default();
+        let app = Router::new()
+            .route(
+                "/:bucket/*key",
+                put(
+                    |Path((_, key)): Path<(String, String)>,
+                     Query(query): Query<HashMap<String, String>>,
+                     State(stub): State<Stub>,
+                     headers: HeaderMap,
+                     body: Bytes| async move {
+                        if !headers.contains_key("authorization") {
+                            return Err(StatusCode::FORBIDDEN);
+                        }
+                        match query.get("uploadId") {
+                            Some(upload_id) => {
+                                let mut uploads = stub.uploads.lock().unwrap();
+                                let parts = uploads.get_mut(upload_id).ok_or(StatusCode::NOT_FOUND)?;
+                                parts.push(body.to_vec());
+                                Ok([(header::ETAG, format!("\"part-{}\"", parts.len()))])
+                            }
+                            None => {
+                                stub.objects.lock().unwrap().insert(key, body.to_vec());
+                                Ok([(header::ETAG, String::from("\"object\""))])
+                            }
+                        }
+                    },
+                )
+                .post(
+                    |Path((_, key)): Path<(String, String)>,
+                     Query(query): Query<HashMap<String, String>>,
+                     State(stub): State<Stub>| async move {
+                        if query.contains_key("uploads") {
+                            stub.uploads.lock().unwrap().insert(String::from("upload-1"), Vec::new());
+                            return Ok::<String, StatusCode>(String::from(
+                                "<InitiateMultipartUploadResult><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>",
+                            ));
+                        }
+                        let upload_id = query.get("uploadId").ok_or(StatusCode::BAD_REQUEST)?;
+                        let parts = stub.uploads.lock().unwrap().remove(upload_id).ok_or(StatusCode::NOT_FOUND)?;
+                        stub.objects.lock().unwrap().insert(key, parts.concat());
+                        Ok(String::from("<CompleteMultipartUploadResult/>"))
+                    },
+                )
+                .get(
+                    |Path((_, key)): Path<(String, String)>, State(stub): State<Stub>| async move {
+                        match stub.objects.lock().unwrap().get(&key) {
+                            Some(content) => Ok(content.clone()),
+                            None => Err(StatusCode::NOT_FOUND),
+                        }
+                    },
+                )
+                .delete(
+                    |Path((_, key)): Path<(String, String)>, State(stub): State<Stub>| async move {
+                        stub.objects.lock().unwrap().remove(&key);
+                        StatusCode::NO_CONTENT
+                    },
+                ),
+            )
+            // parts are larger than the default limit of request bodies
+            .layer(DefaultBodyLimit::disable())
+            .with_state(stub.clone());
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        (addr, stub)
+    }
+
+    fn store_of(addr: SocketAddr) -> S3Store {
+        S3Store::new(S3Config {
+            endpoint: format!("http://{}", addr),
+            bucket: String::from("attachments"),
+            region: String::from("us-east-1"),
+            access_key: String::from("minio"),
+            secret_key: String::from("minio-secret"),
+        })
+    }
+
+    #[tokio::test]
+    async fn it_stores_blobs_in_s3_compatible_service() {
+        let (addr, stub) = start_stub().await;
+        let store = store_of(addr);
+
+        let chunks = vec![Ok(Bytes::from("hello ")), Ok(Bytes::from("s3"))];
+        let written = store
+            .put("task/blob", "text/plain", Box::pin(stream::iter(chunks)))
+            .await
+            .unwrap();
+        assert_eq!(written, 8);
+        assert!(stub.objects.lock().unwrap().contains_key("task/blob"));
+
+        let mut content = Vec::new();
+        let mut data = store.get("task/blob").await.unwrap();
+        while let Some(chunk) = data.next().await {
+            content.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(content, b"hello s3");
+
+        store.delete("task/blob").await.unwrap();
+        assert!(store.get("task/blob").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_streams_large_blobs_in_parts() {
+        let (addr, stub) = start_stub().await;
+        let store = store_of(addr);
+
+        let chunk = Bytes::from(vec![7u8; 1024 * 1024]);
+        let chunks = (0..6)
+            .map(|_| Ok(chunk.clone()))
+            .chain(std::iter::once(Ok(Bytes::from("end"))));
+        let written = store
+            .put(
+                "task/large",
+                "application/pdf",
+                Box::pin(stream::iter(chunks)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(written, 6 * 1024 * 1024 + 3);
+        let objects = stub.objects.lock().unwrap();
+        let content = objects.get("task/large").unwrap();
+        assert_eq!(content.len(), 6 * 1024 * 1024 + 3);
+        assert!(content.ends_with(b"end"));
+        assert!(stub.uploads.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_derives_the_signing_key() {
+        // example from the AWS Signature Version 4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use futures::StreamExt;
+
+use crate::attachment::store::ByteStream;
+
+/// Error yielded by `limit_size` once the content goes over the limit.
+#[derive(Debug)]
+pub struct SizeLimitExceeded(pub u64);
+
+impl fmt::Display for SizeLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "content exceeds the limit of {} bytes", self.0)
+    }
+}
+
+impl Error for SizeLimitExceeded {}
+
+/// Passes the chunks of `data` through, failing as soon as more than `max_bytes` went by.
+pub fn limit_size(data: ByteStream<'_>, max_bytes: u64) -> ByteStream<'_> {
+    Box::pin(data.scan(0u64, move |total, chunk| {
+        let item = match chunk {
+            Ok(chunk) => {
+                *total += chunk.len() as u64;
+                if *total > max_bytes {
+                    Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        SizeLimitExceeded(max_bytes),
+                    ))
+                } else {
+                    Ok(chunk)
+                }
+            }
+            Err(e) => Err(e),
+        };
+        futures::future::ready(Some(item))
+    }))
+}
+
+pub fn is_size_limit_error(error: &io::Error) -> bool {
+    error
+        .get_ref()
+        .map(|inner| inner.is::<SizeLimitExceeded>())
+        .unwrap_or(false)
+}
+
+/// Checks `content_type` against the allowed types, which can end with a `/*` wildcard.
+pub fn is_allowed_type(content_type: &str, allowed_types: &[String]) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    allowed_types.iter().any(|allowed| {
+        let allowed = allowed.trim().to_lowercase();
+        match allowed.strip_suffix("/*") {
+            Some(prefix) => essence.starts_with(&format!("{}/", prefix)),
+            None => allowed == "*/*" || allowed == essence,
+        }
+    })
+}
+
+pub fn content_disposition(filename: &str) -> String {
+    let safe_name = filename
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect::<String>();
+    format!("attachment; filename=\"{}\"", safe_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use axum::body::Bytes;
+    use futures::{stream, StreamExt};
+
+    use crate::attachment::utils::{
+        content_disposition, is_allowed_type, is_size_limit_error, limit_size,
+    };
+
+    #[test]
+    fn it_matches_allowed_types() {
+        let allowed = vec![String::from("image/*"), String::from("application/pdf")];
+        assert!(is_allowed_type("image/png", &allowed));
+        assert!(is_allowed_type("application/pdf; charset=binary", &allowed));
+        assert!(!is_allowed_type("application/x-msdownload", &allowed));
+        assert_eq!(
+            content_disposition("a \"quoted\".pdf"),
+            "attachment; filename=\"a quoted.pdf\""
+        );
+    }
+
+    #[tokio::test]
+    async fn it_stops_streams_over_the_limit() {
+        let chunks: Vec<io::Result<Bytes>> = vec![Ok(Bytes::from("1234")), Ok(Bytes::from("5678"))];
+        let mut limited = limit_size(Box::pin(stream::iter(chunks)), 6);
+        assert!(limited.next().await.unwrap().is_ok());
+        let error = limited.next().await.unwrap().unwrap_err();
+        assert!(is_size_limit_error(&error));
+    }
+}
@@ -0,0 +1,254 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::automation::model::AutomationRule;
+use crate::automation::service;
+use crate::automation::utils::validate_rule;
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::dto::{CreateAutomationRuleRequest, PageParams, Response, UpdateAutomationRuleRequest};
+use crate::error::AppError;
+use crate::user::extractor::CurrentUser;
+
+// Returns the automation rules of a board, in the order they run
+#[axum_macros::debug_handler]
+pub async fn get_automation_rules_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting automation rules of board {}", board_id);
+    match service::get_rules(&board_id, state.get_automations_collection()).await {
+        Ok(rules) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(rules),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_automation_rules_handler] Error getting automation rules of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Returns what the automation rules of a board did, most recent first
+#[axum_macros::debug_handler]
+pub async fn get_automation_runs_handler(
+    path: Path<String>,
+    Query(params): Query<PageParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting automation runs of board {}", board_id);
+    match service::get_runs(&board_id, &params, state.get_automation_runs_collection()).await {
+        Ok(page) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(page),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_automation_runs_handler] Error getting automation runs of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Creates a new automation rule on a board
+#[axum_macros::debug_handler]
+pub async fn automation_rule_create_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CreateAutomationRuleRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[automation_rule_create_handler] Creating automation rule {} on board {}",
+        req.name, board_id
+    );
+
+    async fn validate_and_create(
+        board_id: &String,
+        req: CreateAutomationRuleRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<AutomationRule, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let rule = AutomationRule::from_request(board.id, req, actor);
+        validate_rule(&rule, &board)?;
+        service::create(&rule, state.get_automations_collection()).await?;
+        Ok(rule)
+    }
+
+    match validate_and_create(&board_id, req, user.map(|u| u.0), state).await {
+        Ok(rule) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(rule),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[automation_rule_create_handler] Error creating automation rule on board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Updates an automation rule, e.g. to disable it
+#[axum_macros::debug_handler]
+pub async fn automation_rule_update_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    Json(req): Json<UpdateAutomationRuleRequest>,
+) -> impl IntoResponse {
+    let rule_id = path.0;
+    debug!(
+        "[automation_rule_update_handler] Updating automation rule {}",
+        rule_id
+    );
+
+    async fn validate_and_update(
+        rule_id: &String,
+        req: UpdateAutomationRuleRequest,
+        state: AppState,
+    ) -> Result<AutomationRule, AppError> {
+        let rule = service::get_rule(rule_id, state.get_automations_collection())
+            .await?
+            .with_update_request(req);
+        let board =
+            board_service::get_board(&rule.board_id.to_string(), state.get_boards_collection())
+                .await?;
+        validate_rule(&rule, &board)?;
+        service::update(&rule, state.get_automations_collection()).await?;
+        Ok(rule)
+    }
+
+    match validate_and_update(&rule_id, req, state).await {
+        Ok(rule) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(rule),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[automation_rule_update_handler] Error updating automation rule {}: {:?}",
+                rule_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes an automation rule, its runs stay in the run log of the board
+#[axum_macros::debug_handler]
+pub async fn automation_rule_delete_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let rule_id = path.0;
+    debug!(
+        "[automation_rule_delete_handler] Deleting automation rule {}",
+        rule_id
+    );
+
+    async fn delete(rule_id: &String, state: AppState) -> Result<(), AppError> {
+        let rule = service::get_rule(rule_id, state.get_automations_collection()).await?;
+        service::delete(&rule.id.to_string(), state.get_automations_collection()).await
+    }
+
+    match delete(&rule_id, state).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(rule_id),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[automation_rule_delete_handler] Error deleting automation rule {}: {:?}",
+                rule_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+use crate::automation::service;
+use crate::config::AppState;
+
+const SCAN_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Runs the idle rules of the boards, once at startup and every hour after.
+pub fn spawn_scanner(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCAN_INTERVAL);
+        loop {
+            interval.tick().await;
+            match service::scan_idle(&state).await {
+                Ok(changed) => debug!("[spawn_scanner] Idle rules changed {} tasks", changed),
+                Err(e) => error!("ERROR [spawn_scanner] Couldn't run the idle rules: {:?}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::automation::model::{AutomationRule, AutomationRun, RunStatus};
+use crate::error::{AppError, AutomationRepoError, Result};
+
+pub async fn parse_rules(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<AutomationRule>> {
+    let mut result: Vec<AutomationRule> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_rule(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub async fn parse_runs(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<AutomationRun>> {
+    let mut result: Vec<AutomationRun> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_run(&doc?)?);
+    }
+    Ok(result)
+}
+
+fn decode_error(msg: String) -> AppError {
+    AppError::AutomationRepo(AutomationRepoError::DecodeError(msg))
+}
+
+pub fn doc_to_rule(doc: &bson::document::Document) -> Result<AutomationRule> {
+    let id = doc.get_str("id")?;
+    let board_id = doc.get_str("board_id")?;
+    let name = doc.get_str("name")?;
+    let created_at = doc.get_datetime("created_at")?;
+    let decode = |field: &str| {
+        doc.get(field)
+            .cloned()
+            .ok_or_else(|| decode_error(format!("Automation rule {} has no {}", id, field)))
+    };
+    let trigger = bson::from_bson(decode("trigger")?).map_err(|e| decode_error(e.to_string()))?;
+    let actions = bson::from_bson(decode("actions")?).map_err(|e| decode_error(e.to_string()))?;
+    let conditions =
+        bson::from_bson(decode("conditions")?).map_err(|e| decode_error(e.to_string()))?;
+    match (Uuid::from_str(id), Uuid::from_str(board_id)) {
+        (Ok(rule_uuid), Ok(board_uuid)) => Ok(AutomationRule {
+            id: rule_uuid,
+            board_id: board_uuid,
+            name: name.to_owned(),
+            enabled: doc.get_bool("enabled").unwrap_or(true),
+            trigger,
+            conditions,
+            actions,
+            created_by: doc.get_str("created_by").ok().map(|c| c.to_owned()),
+            created_at: chrono::DateTime::from(*created_at),
+            updated_at: doc
+                .get_datetime("updated_at")
+                .ok()
+                .map(|v| chrono::DateTime::from(*v)),
+        }),
+        _ => Err(decode_error(format!(
+            "Automation rule doesnt have id or board_id {}",
+            id
+        ))),
+    }
+}
+
+pub fn rule_to_doc(rule: &AutomationRule) -> bson::document::Document {
+    doc! {
+        "id" : rule.id.to_string(),
+        "board_id" : rule.board_id.to_string(),
+        "name" : rule.name.clone(),
+        "enabled" : rule.enabled,
+        "trigger" : bson::to_bson(&rule.trigger).ok(),
+        "conditions" : bson::to_bson(&rule.conditions).ok(),
+        "actions" : bson::to_bson(&rule.actions).ok(),
+        "created_by" : rule.created_by.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(rule.created_at),
+        "updated_at" : rule.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    }
+}
+
+pub fn doc_to_run(doc: &bson::document::Document) -> Result<AutomationRun> {
+    let id = doc.get_str("id")?;
+    let status = doc.get_str("status")?;
+    let created_at = doc.get_datetime("created_at")?;
+    let uuid = |field: &str| -> Result<Uuid> {
+        Uuid::from_str(doc.get_str(field)?)
+            .map_err(|_| decode_error(format!("Automation run {} has an invalid {}", id, field)))
+    };
+    Ok(AutomationRun {
+        id: uuid("id")?,
+        rule_id: uuid("rule_id")?,
+        rule_name: doc.get_str("rule_name")?.to_owned(),
+        board_id: uuid("board_id")?,
+        task_id: uuid("task_id")?,
+        status: RunStatus::from_str(status).map_err(|e| decode_error(e.to_string()))?,
+        depth: doc.get_i32("depth").unwrap_or_default() as u32,
+        created_at: chrono::DateTime::from(*created_at),
+    })
+}
+
+pub fn run_to_doc(run: &AutomationRun) -> bson::document::Document {
+    doc! {
+        "id" : run.id.to_string(),
+        "rule_id" : run.rule_id.to_string(),
+        "rule_name" : run.rule_name.clone(),
+        "board_id" : run.board_id.to_string(),
+        "task_id" : run.task_id.to_string(),
+        "status" : run.status.to_string(),
+        "depth" : run.depth as i32,
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(run.created_at),
+    }
+}
@@ -0,0 +1,6 @@
+pub mod handlers;
+pub mod jobs;
+pub mod mapper;
+pub mod model;
+pub mod service;
+pub mod utils;
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+use crate::custom_field::model::CustomFieldValue;
+use crate::dto::{CreateAutomationRuleRequest, UpdateAutomationRuleRequest};
+
+/// "When `trigger`, if `conditions`, then `actions`" rule of a board, run by
+/// the server on the tasks of the board.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AutomationRule {
+    pub id: uuid::Uuid,
+    pub board_id: uuid::Uuid,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: Trigger,
+    /// All have to hold for the actions to run.
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    /// Applied in order.
+    pub actions: Vec<Action>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "when", rename_all = "snake_case")]
+pub enum Trigger {
+    Created,
+    /// A task entering the given column, any column when none is given.
+    Moved {
+        column_id: Option<uuid::Uuid>,
+    },
+    /// A task left unchanged for the given number of days, checked by the
+    /// periodic scanner.
+    Idle {
+        days: u32,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "if", rename_all = "snake_case")]
+pub enum Condition {
+    InColumn {
+        column_id: uuid::Uuid,
+    },
+    /// Value of a custom field, matched like the `field.<field_id>` filters:
+    /// for multi-select fields, e.g. labels, one of the options.
+    FieldIs {
+        field_id: uuid::Uuid,
+        value: String,
+    },
+    FieldEmpty {
+        field_id: uuid::Uuid,
+    },
+    /// `null` for unassigned tasks.
+    AssigneeIs {
+        assignee: Option<String>,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "then", rename_all = "snake_case")]
+pub enum Action {
+    SetField {
+        field_id: uuid::Uuid,
+        value: CustomFieldValue,
+    },
+    /// Sets a date field to the day the rule runs, e.g. a completion date.
+    SetToday {
+        field_id: uuid::Uuid,
+    },
+    ClearField {
+        field_id: uuid::Uuid,
+    },
+    /// Adds an option to a multi-select field, e.g. a label.
+    AddOption {
+        field_id: uuid::Uuid,
+        option: String,
+    },
+    RemoveOption {
+        field_id: uuid::Uuid,
+        option: String,
+    },
+    /// `null` unassigns the task.
+    Assign {
+        assignee: Option<String>,
+    },
+    /// Moves the task to the top or the bottom of its swimlane in a column.
+    MoveTo {
+        column_id: uuid::Uuid,
+        #[serde(default)]
+        position: Position,
+    },
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Position {
+    Top,
+    #[default]
+    Bottom,
+}
+
+/// Run of a rule on a task, kept in the run log of the board.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AutomationRun {
+    pub id: uuid::Uuid,
+    pub rule_id: uuid::Uuid,
+    pub rule_name: String,
+    pub board_id: uuid::Uuid,
+    pub task_id: uuid::Uuid,
+    pub status: RunStatus,
+    /// Number of rule runs that led to this one, 0 when it reacts to a change
+    /// made by a user or to an idle task.
+    pub depth: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RunStatus {
+    Applied,
+    /// Not applied by the loop protection: the rule already changed the task
+    /// in the same chain of runs, or the chain got too long.
+    Stopped,
+}
+
+impl AutomationRule {
+    pub fn from_request(
+        board_id: uuid::Uuid,
+        r: CreateAutomationRuleRequest,
+        creator: Option<String>,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            board_id,
+            name: r.name.trim().to_owned(),
+            enabled: r.enabled.unwrap_or(true),
+            trigger: r.trigger,
+            conditions: r.conditions.unwrap_or_default(),
+            actions: r.actions,
+            created_by: creator,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    pub fn with_update_request(self, r: UpdateAutomationRuleRequest) -> Self {
+        Self {
+            name: r.name.map(|n| n.trim().to_owned()).unwrap_or(self.name),
+            enabled: r.enabled.unwrap_or(self.enabled),
+            trigger: r.trigger.unwrap_or(self.trigger),
+            conditions: r.conditions.unwrap_or(self.conditions),
+            actions: r.actions.unwrap_or(self.actions),
+            updated_at: Some(Utc::now()),
+            ..self
+        }
+    }
+}
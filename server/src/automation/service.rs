@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use bson::{doc, Document};
+use chrono::Utc;
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::activity::service as activity_service;
+use crate::activity::utils::task_activities;
+use crate::automation::mapper::{doc_to_rule, parse_rules, parse_runs, rule_to_doc, run_to_doc};
+use crate::automation::model::{AutomationRule, AutomationRun};
+use crate::automation::utils::{run_rules, TaskEvent};
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::dto::{Page, PageParams};
+use crate::error::{AppError, AutomationRepoError, Result};
+use crate::notification::service as notification_service;
+use crate::notification::utils::task_notifications;
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::task::utils::without_archived;
+use crate::transition::service as transition_service;
+use crate::transition::utils::column_transitions;
+
+/// Actor of the activities recorded for the changes made by rules.
+const AUTOMATION_ACTOR: &str = "automation";
+
+/// Returns the automation rules of a board, in the order they run.
+pub async fn get_rules(
+    board_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<AutomationRule>> {
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .build();
+    let cursor = collection
+        .find(doc! { "board_id": board_id }, options)
+        .await?;
+    parse_rules(cursor).await
+}
+
+pub async fn get_rule(
+    rule_id: &String,
+    collection: Collection<Document>,
+) -> Result<AutomationRule> {
+    let rule_opt = collection
+        .find_one(doc! { "id": rule_id }, None)
+        .await
+        .map_err(|e| {
+            debug!(
+                "Error while getting automation rule with id {}: {}",
+                rule_id, e
+            );
+            AppError::MongoError(e)
+        })?;
+    match rule_opt {
+        Some(doc) => doc_to_rule(&doc),
+        None => Err(AppError::AutomationRepo(AutomationRepoError::NotFound)),
+    }
+}
+
+pub async fn create(
+    rule: &AutomationRule,
+    collection: Collection<Document>,
+) -> Result<&AutomationRule> {
+    debug!(
+        "[create_rule] Creating automation rule with id={}",
+        &rule.id
+    );
+    collection.insert_one(rule_to_doc(rule), None).await?;
+    Ok(rule)
+}
+
+pub async fn update(
+    rule: &AutomationRule,
+    collection: Collection<Document>,
+) -> Result<&AutomationRule> {
+    debug!(
+        "[update_rule] Updating automation rule with id={}",
+        &rule.id
+    );
+    let filter = doc! { "id": rule.id.to_string() };
+    let updates = doc! { "$set": rule_to_doc(rule) };
+    collection.update_one(filter, updates, None).await?;
+    Ok(rule)
+}
+
+pub async fn delete(rule_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!("[delete_rule] Deleting automation rule with id={}", rule_id);
+    collection.delete_one(doc! { "id": rule_id }, None).await?;
+    Ok(())
+}
+
+/// Deletes the rules or the runs of a board, depending on the collection.
+pub async fn delete_of_board(board_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!(
+        "[delete_of_board] Deleting automations of board {}",
+        board_id
+    );
+    collection
+        .delete_many(doc! { "board_id": board_id }, None)
+        .await?;
+    Ok(())
+}
+
+/// Returns the run log of a board, most recent first.
+pub async fn get_runs(
+    board_id: &String,
+    params: &PageParams,
+    collection: Collection<Document>,
+) -> Result<Page<AutomationRun>> {
+    let filter = doc! { "board_id": board_id };
+    let total = collection.count_documents(filter.clone(), None).await?;
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .skip((params.page() - 1) * params.per_page())
+        .limit(params.per_page() as i64)
+        .build();
+    let cursor = collection.find(filter, options).await?;
+    Ok(Page {
+        items: parse_runs(cursor).await?,
+        page: params.page(),
+        per_page: params.per_page(),
+        total,
+    })
+}
+
+/// Appends the given runs to the run log, failures are only logged.
+async fn record_runs(runs: Vec<AutomationRun>, collection: Collection<Document>) {
+    if runs.is_empty() {
+        return;
+    }
+    debug!("[record_runs] Recording {} automation runs", runs.len());
+    let docs = runs.iter().map(run_to_doc).collect::<Vec<Document>>();
+    if let Err(e) = collection.insert_many(docs, None).await {
+        error!(
+            "ERROR [record_runs] Couldn't record automation runs: {:?}",
+            e
+        );
+    }
+}
+
+async fn run(board_id: uuid::Uuid, events: Vec<TaskEvent>, state: &AppState) -> Result<Vec<Task>> {
+    let board_id = board_id.to_string();
+    let rules = get_rules(&board_id, state.get_automations_collection())
+        .await?
+        .into_iter()
+        .filter(|r| r.enabled)
+        .collect::<Vec<AutomationRule>>();
+    if rules.is_empty() || events.is_empty() {
+        return Ok(Vec::new());
+    }
+    let board = board_service::get_board(&board_id, state.get_boards_collection()).await?;
+    if board.archived_at.is_some() {
+        return Ok(Vec::new());
+    }
+    let board_tasks = without_archived(
+        task_service::get_tasks(&board_id, state.get_tasks_collection()).await?,
+        &board.archived_column_ids(),
+    );
+    let (changed, runs) = run_rules(&rules, &board, &board_tasks, events, Utc::now());
+    if !changed.is_empty() {
+        task_service::update_many(&changed, state.clone()).await?;
+        let before = board_tasks
+            .into_iter()
+            .filter(|t| changed.iter().any(|c| c.id == t.id))
+            .collect::<Vec<Task>>();
+        activity_service::record(
+            task_activities(&before, &changed, Some(AUTOMATION_ACTOR)),
+            state.get_activity_collection(),
+        )
+        .await;
+        transition_service::record(
+            column_transitions(&before, &changed),
+            state.get_transitions_collection(),
+        )
+        .await;
+        let notifications = changed
+            .iter()
+            .flat_map(|t| {
+                task_notifications(
+                    before.iter().find(|b| b.id == t.id),
+                    t,
+                    Some(AUTOMATION_ACTOR),
+                )
+            })
+            .collect();
+        notification_service::notify(notifications, state.get_notifications_collection()).await;
+    }
+    record_runs(runs, state.get_automation_runs_collection()).await;
+    Ok(changed)
+}
+
+/// Runs the rules of a board on stored changes of its tasks, returns the tasks
+/// the rules changed. Failures are only logged, as rules run after the changes
+/// they react to succeeded.
+pub async fn react(board_id: uuid::Uuid, events: Vec<TaskEvent>, state: &AppState) -> Vec<Task> {
+    match run(board_id, events, state).await {
+        Ok(changed) => changed,
+        Err(e) => {
+            error!(
+                "ERROR [react] Couldn't run the automation rules of board {}: {:?}",
+                board_id, e
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Runs the rules of their boards on updated tasks, `stored` holding the tasks
+/// before the update. Returns the tasks the rules changed.
+pub async fn react_to_updates(stored: &[Task], updated: &[Task], state: &AppState) -> Vec<Task> {
+    let mut events: HashMap<uuid::Uuid, Vec<TaskEvent>> = HashMap::new();
+    for after in updated.iter() {
+        if let Some(before) = stored.iter().find(|s| s.id == after.id) {
+            events
+                .entry(after.board_id)
+                .or_default()
+                .push(TaskEvent::Updated {
+                    before: Box::new(before.clone()),
+                    after: Box::new(after.clone()),
+                });
+        }
+    }
+    let mut changed = Vec::new();
+    for (board_id, events) in events {
+        changed.extend(react(board_id, events, state).await);
+    }
+    changed
+}
+
+/// Runs the idle rules on the tasks of their boards, returns how many tasks
+/// they changed.
+pub async fn scan_idle(state: &AppState) -> Result<usize> {
+    let filter = doc! { "enabled": true, "trigger.when": "idle" };
+    let board_ids = state
+        .get_automations_collection()
+        .distinct("board_id", filter, None)
+        .await?;
+    let mut changed = 0;
+    for board_id in board_ids.iter().filter_map(|b| b.as_str()) {
+        let Ok(board_uuid) = uuid::Uuid::parse_str(board_id) else {
+            continue;
+        };
+        let events = task_service::get_tasks(&board_id.to_owned(), state.get_tasks_collection())
+            .await?
+            .into_iter()
+            .filter(|t| t.archived_at.is_none())
+            .map(TaskEvent::Idle)
+            .collect();
+        changed += react(board_uuid, events, state).await.len();
+    }
+    Ok(changed)
+}
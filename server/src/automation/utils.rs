@@ -0,0 +1,555 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::automation::model::{
+    Action, AutomationRule, AutomationRun, Condition, Position, RunStatus, Trigger,
+};
+use crate::board::model::Board;
+use crate::custom_field::model::{CustomField, CustomFieldKind, CustomFieldValue};
+use crate::custom_field::utils::{matches, validate_values};
+use crate::error::{AutomationRepoError, Result};
+use crate::swimlane::utils::place_in_swimlane;
+use crate::task::model::Task;
+use crate::task::utils::{move_tasks_to_column, unlink_from_column};
+
+/// Longest chain of rules reacting to the changes of other rules.
+pub const MAX_CHAIN_DEPTH: u32 = 5;
+
+/// Change of a task the rules of its board react to.
+#[derive(Clone, Debug)]
+pub enum TaskEvent {
+    Created(Task),
+    Updated {
+        before: Box<Task>,
+        after: Box<Task>,
+    },
+    /// Raised by the scanner for the tasks of the boards with idle rules.
+    Idle(Task),
+}
+
+impl TaskEvent {
+    fn task_id(&self) -> Uuid {
+        match self {
+            TaskEvent::Created(task) | TaskEvent::Idle(task) => task.id,
+            TaskEvent::Updated { after, .. } => after.id,
+        }
+    }
+}
+
+fn board_field<'a>(
+    board: &'a Board,
+    field_id: Uuid,
+    kinds: &[CustomFieldKind],
+) -> Result<&'a CustomField> {
+    let field = board
+        .custom_fields
+        .iter()
+        .find(|f| f.id == field_id)
+        .ok_or_else(|| {
+            AutomationRepoError::InvalidRule(format!("unknown custom field {}", field_id))
+        })?;
+    if !kinds.is_empty() && !kinds.contains(&field.kind) {
+        return Err(AutomationRepoError::InvalidRule(format!(
+            "custom field {} of kind {} cannot be used this way",
+            field.name, field.kind
+        ))
+        .into());
+    }
+    Ok(field)
+}
+
+fn board_column(board: &Board, column_id: Uuid) -> Result<()> {
+    match board.column(column_id) {
+        Some(_) => Ok(()),
+        None => {
+            Err(AutomationRepoError::InvalidRule(format!("unknown column {}", column_id)).into())
+        }
+    }
+}
+
+pub fn validate_rule(rule: &AutomationRule, board: &Board) -> Result<()> {
+    let invalid = |msg: &str| -> Result<()> {
+        Err(AutomationRepoError::InvalidRule(String::from(msg)).into())
+    };
+    if rule.name.is_empty() {
+        return invalid("name cannot be empty");
+    }
+    if rule.actions.is_empty() {
+        return invalid("a rule needs at least one action");
+    }
+    match rule.trigger {
+        Trigger::Moved {
+            column_id: Some(column_id),
+        } => board_column(board, column_id)?,
+        Trigger::Idle { days: 0 } => return invalid("idle days have to be at least 1"),
+        _ => {}
+    }
+    for condition in rule.conditions.iter() {
+        match condition {
+            Condition::InColumn { column_id } => board_column(board, *column_id)?,
+            Condition::FieldIs { field_id, .. } | Condition::FieldEmpty { field_id } => {
+                board_field(board, *field_id, &[])?;
+            }
+            Condition::AssigneeIs { .. } => {}
+        }
+    }
+    for action in rule.actions.iter() {
+        match action {
+            Action::SetField { field_id, value } => {
+                board_field(board, *field_id, &[])?;
+                validate_values(
+                    &BTreeMap::from([(field_id.to_string(), value.clone())]),
+                    None,
+                    &board.custom_fields,
+                )?;
+            }
+            Action::SetToday { field_id } => {
+                board_field(board, *field_id, &[CustomFieldKind::Date])?;
+            }
+            Action::ClearField { field_id } => {
+                board_field(board, *field_id, &[])?;
+            }
+            Action::AddOption { field_id, option } | Action::RemoveOption { field_id, option } => {
+                let field = board_field(board, *field_id, &[CustomFieldKind::MultiSelect])?;
+                if !field.options.contains(option) {
+                    return Err(AutomationRepoError::InvalidRule(format!(
+                        "{} is not an option of custom field {}",
+                        option, field.name
+                    ))
+                    .into());
+                }
+            }
+            Action::Assign {
+                assignee: Some(assignee),
+            } if assignee.trim().is_empty() => return invalid("assignee cannot be empty"),
+            Action::Assign { .. } => {}
+            Action::MoveTo { column_id, .. } => board_column(board, *column_id)?,
+        }
+    }
+    Ok(())
+}
+
+fn is_triggered(trigger: &Trigger, event: &TaskEvent, now: DateTime<Utc>) -> bool {
+    match (trigger, event) {
+        (Trigger::Created, TaskEvent::Created(_)) => true,
+        (Trigger::Moved { column_id }, TaskEvent::Updated { before, after }) => {
+            before.column_id != after.column_id
+                && after.column_id.is_some()
+                && column_id.is_none_or(|c| after.column_id == Some(c))
+        }
+        (Trigger::Idle { days }, TaskEvent::Idle(task)) => {
+            now - task.updated_at.unwrap_or(task.created_at) >= Duration::days(*days as i64)
+        }
+        _ => false,
+    }
+}
+
+fn holds(condition: &Condition, task: &Task) -> bool {
+    match condition {
+        Condition::InColumn { column_id } => task.column_id == Some(*column_id),
+        Condition::FieldIs { field_id, value } => {
+            matches(task.custom_fields.get(&field_id.to_string()), value)
+        }
+        Condition::FieldEmpty { field_id } => match task.custom_fields.get(&field_id.to_string()) {
+            Some(CustomFieldValue::Text(text)) => text.is_empty(),
+            Some(CustomFieldValue::Options(options)) => options.is_empty(),
+            Some(CustomFieldValue::Number(_)) => false,
+            None => true,
+        },
+        Condition::AssigneeIs { assignee } => task.assignee == *assignee,
+    }
+}
+
+/// Applies the actions of a rule to a task. Returns the task first, followed
+/// by the tasks whose order changed along with it. Actions on fields or
+/// columns removed from the board since the rule was saved are skipped.
+fn apply(
+    rule: &AutomationRule,
+    task: &Task,
+    board: &Board,
+    board_tasks: &[Task],
+    now: DateTime<Utc>,
+) -> Vec<Task> {
+    let mut changed = task.clone();
+    let mut position = None;
+    let has_field = |field_id: &Uuid| board.custom_fields.iter().any(|f| f.id == *field_id);
+    for action in rule.actions.iter() {
+        match action {
+            Action::SetField { field_id, value } if has_field(field_id) => {
+                changed
+                    .custom_fields
+                    .insert(field_id.to_string(), value.clone());
+            }
+            Action::SetToday { field_id } if has_field(field_id) => {
+                changed.custom_fields.insert(
+                    field_id.to_string(),
+                    CustomFieldValue::Text(now.format("%Y-%m-%d").to_string()),
+                );
+            }
+            Action::ClearField { field_id } => {
+                changed.custom_fields.remove(&field_id.to_string());
+            }
+            Action::AddOption { field_id, option } if has_field(field_id) => {
+                let mut options = match changed.custom_fields.remove(&field_id.to_string()) {
+                    Some(CustomFieldValue::Options(options)) => options,
+                    _ => Vec::new(),
+                };
+                if !options.contains(option) {
+                    options.push(option.clone());
+                }
+                changed
+                    .custom_fields
+                    .insert(field_id.to_string(), CustomFieldValue::Options(options));
+            }
+            Action::RemoveOption { field_id, option } => {
+                if let Some(CustomFieldValue::Options(options)) =
+                    changed.custom_fields.get_mut(&field_id.to_string())
+                {
+                    options.retain(|o| o != option);
+                }
+            }
+            Action::Assign { assignee } => changed.assignee = assignee.clone(),
+            Action::MoveTo {
+                column_id,
+                position: to,
+            } if board.column(*column_id).is_some() => {
+                changed.column_id = Some(*column_id);
+                position = Some(*to);
+            }
+            _ => {}
+        }
+    }
+    if let Ok(placed) = place_in_swimlane(changed.clone(), board, board_tasks) {
+        changed.swimlane_id = placed.swimlane_id;
+    }
+    if position.is_none()
+        && changed.column_id == task.column_id
+        && changed.swimlane_id == task.swimlane_id
+    {
+        return vec![changed];
+    }
+
+    let mut updated = unlink_from_column(task, board_tasks);
+    let cell = board_tasks
+        .iter()
+        .filter(|t| {
+            t.id != task.id
+                && t.column_id == changed.column_id
+                && t.swimlane_id == changed.swimlane_id
+        })
+        .map(|t| updated.iter().find(|u| u.id == t.id).unwrap_or(t).clone())
+        .collect::<Vec<Task>>();
+    updated[0] = match position.unwrap_or_default() {
+        Position::Top => {
+            if let Some(first) = cell.iter().find(|t| t.above_task_id.is_none()) {
+                updated.retain(|u| u.id != first.id);
+                updated.push(first.with_above_task(Some(changed.id)));
+            }
+            changed.with_above_task(None)
+        }
+        Position::Bottom => {
+            move_tasks_to_column(&[changed.with_above_task(None)], &cell, changed.column_id)
+                .remove(0)
+        }
+    };
+    updated
+}
+
+/// Runs the rules of a board on changes of its tasks, then on the changes the
+/// rules make in turn. A rule changes a task at most once in such a chain and
+/// chains stop after `MAX_CHAIN_DEPTH` runs, so that rules undoing each other
+/// cannot loop. Returns the tasks the rules changed and the runs to log.
+pub fn run_rules(
+    rules: &[AutomationRule],
+    board: &Board,
+    board_tasks: &[Task],
+    events: Vec<TaskEvent>,
+    now: DateTime<Utc>,
+) -> (Vec<Task>, Vec<AutomationRun>) {
+    let mut tasks = board_tasks.to_vec();
+    let mut queue = events
+        .into_iter()
+        .map(|e| (e, 0))
+        .collect::<VecDeque<(TaskEvent, u32)>>();
+    let mut applied = HashSet::new();
+    let mut runs = Vec::new();
+    while let Some((event, depth)) = queue.pop_front() {
+        for rule in rules
+            .iter()
+            .filter(|r| r.enabled && is_triggered(&r.trigger, &event, now))
+        {
+            let Some(task) = tasks.iter().find(|t| t.id == event.task_id()).cloned() else {
+                break;
+            };
+            if !rule.conditions.iter().all(|c| holds(c, &task)) {
+                continue;
+            }
+            let run = |status: RunStatus| AutomationRun {
+                id: Uuid::new_v4(),
+                rule_id: rule.id,
+                rule_name: rule.name.clone(),
+                board_id: board.id,
+                task_id: task.id,
+                status,
+                depth,
+                created_at: now,
+            };
+            if depth >= MAX_CHAIN_DEPTH || applied.contains(&(rule.id, task.id)) {
+                runs.push(run(RunStatus::Stopped));
+                continue;
+            }
+            let updated = apply(rule, &task, board, &tasks, now);
+            if updated[0] == task {
+                continue;
+            }
+            applied.insert((rule.id, task.id));
+            runs.push(run(RunStatus::Applied));
+            let after = Task {
+                updated_at: Some(now),
+                ..updated[0].clone()
+            };
+            for changed in std::iter::once(after.clone()).chain(updated.into_iter().skip(1)) {
+                if let Some(t) = tasks.iter_mut().find(|t| t.id == changed.id) {
+                    *t = changed;
+                }
+            }
+            queue.push_back((
+                TaskEvent::Updated {
+                    before: Box::new(task),
+                    after: Box::new(after),
+                },
+                depth + 1,
+            ));
+        }
+    }
+    let changed = tasks
+        .into_iter()
+        .filter(|t| board_tasks.iter().find(|b| b.id == t.id) != Some(t))
+        .collect();
+    (changed, runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use uuid::Uuid;
+
+    use crate::automation::mapper::{doc_to_rule, rule_to_doc};
+    use crate::automation::model::{
+        Action, AutomationRule, Condition, Position, RunStatus, Trigger,
+    };
+    use crate::automation::utils::{run_rules, validate_rule, TaskEvent, MAX_CHAIN_DEPTH};
+    use crate::board::model::Board;
+    use crate::custom_field::model::{CustomFieldKind, CustomFieldValue};
+    use crate::fixtures::{mock_board, mock_column, mock_field, mock_task};
+    use crate::task::model::Task;
+
+    fn board_with_labels() -> Board {
+        Board {
+            custom_fields: vec![
+                mock_field("Labels", CustomFieldKind::MultiSelect, &["bug", "stale"]),
+                mock_field("Completed", CustomFieldKind::Date, &[]),
+            ],
+            ..mock_board(vec![
+                mock_column("Triage", false),
+                mock_column("Doing", false),
+                mock_column("Done", false),
+            ])
+        }
+    }
+
+    fn task_in(board: &Board, column: usize, above: Option<&Task>) -> Task {
+        Task {
+            above_task_id: above.map(|t| t.id),
+            assignee: Some(String::from("alice")),
+            ..mock_task(board.id, Some(board.columns[column].id))
+        }
+    }
+
+    fn mock_rule(
+        board: &Board,
+        trigger: Trigger,
+        conditions: Vec<Condition>,
+        actions: Vec<Action>,
+    ) -> AutomationRule {
+        AutomationRule {
+            id: Uuid::new_v4(),
+            board_id: board.id,
+            name: String::from("mock-rule"),
+            enabled: true,
+            trigger,
+            conditions,
+            actions,
+            created_by: None,
+            created_at: DateTime::<Utc>::default(),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn it_runs_rules_on_task_changes() {
+        let board = board_with_labels();
+        let (labels, completed) = (board.custom_fields[0].id, board.custom_fields[1].id);
+        let (triage, done) = (board.columns[0].id, board.columns[2].id);
+        let now = Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 0).unwrap();
+        let finish = mock_rule(
+            &board,
+            Trigger::Moved {
+                column_id: Some(done),
+            },
+            Vec::new(),
+            vec![
+                Action::SetToday {
+                    field_id: completed,
+                },
+                Action::Assign { assignee: None },
+            ],
+        );
+        let triage_bugs = mock_rule(
+            &board,
+            Trigger::Created,
+            vec![Condition::FieldIs {
+                field_id: labels,
+                value: String::from("bug"),
+            }],
+            vec![Action::MoveTo {
+                column_id: triage,
+                position: Position::Top,
+            }],
+        );
+        assert!(validate_rule(&finish, &board).is_ok());
+        assert!(validate_rule(&triage_bugs, &board).is_ok());
+        let rules = vec![finish, triage_bugs];
+
+        let first = task_in(&board, 0, None);
+        let second = task_in(&board, 0, Some(&first));
+        let before = task_in(&board, 1, None);
+        let moved = Task {
+            column_id: Some(done),
+            ..before.clone()
+        };
+        let tasks = vec![first.clone(), second.clone(), moved.clone()];
+        let event = TaskEvent::Updated {
+            before: Box::new(before),
+            after: Box::new(moved.clone()),
+        };
+        let (changed, runs) = run_rules(&rules, &board, &tasks, vec![event], now);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].assignee, None);
+        assert_eq!(
+            changed[0].custom_fields.get(&completed.to_string()),
+            Some(&CustomFieldValue::Text(String::from("2023-03-06")))
+        );
+        assert_eq!(changed[0].updated_at, Some(now));
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, RunStatus::Applied);
+
+        let bug = Task {
+            custom_fields: [(
+                labels.to_string(),
+                CustomFieldValue::Options(vec![String::from("bug")]),
+            )]
+            .into(),
+            ..task_in(&board, 1, None)
+        };
+        let tasks = vec![first.clone(), second, bug.clone()];
+        let (changed, _) = run_rules(&rules, &board, &tasks, vec![TaskEvent::Created(bug)], now);
+        let changed_task = |task: &Task| changed.iter().find(|t| t.id == task.id).cloned();
+        let bug = changed.iter().find(|t| t.above_task_id.is_none()).unwrap();
+        assert_eq!(bug.column_id, Some(triage));
+        assert_eq!(changed_task(&first).unwrap().above_task_id, Some(bug.id));
+    }
+
+    #[test]
+    fn it_stops_rules_undoing_each_other() {
+        let board = board_with_labels();
+        let (doing, done) = (board.columns[1].id, board.columns[2].id);
+        let now = Utc::now();
+        let move_rule = |from: Uuid, to: Uuid| {
+            mock_rule(
+                &board,
+                Trigger::Moved {
+                    column_id: Some(from),
+                },
+                Vec::new(),
+                vec![Action::MoveTo {
+                    column_id: to,
+                    position: Position::Bottom,
+                }],
+            )
+        };
+        let rules = vec![move_rule(done, doing), move_rule(doing, done)];
+        let before = task_in(&board, 0, None);
+        let after = Task {
+            column_id: Some(done),
+            ..before.clone()
+        };
+        let event = TaskEvent::Updated {
+            before: Box::new(before),
+            after: Box::new(after.clone()),
+        };
+
+        let (changed, runs) = run_rules(&rules, &board, &[after], vec![event], now);
+        assert_eq!(changed[0].column_id, Some(done));
+        assert_eq!(
+            runs.iter().map(|r| r.status).collect::<Vec<RunStatus>>(),
+            vec![RunStatus::Applied, RunStatus::Applied, RunStatus::Stopped]
+        );
+        assert!(runs.iter().all(|r| r.depth < MAX_CHAIN_DEPTH));
+    }
+
+    #[test]
+    fn it_labels_idle_tasks() {
+        let board = board_with_labels();
+        let labels = board.custom_fields[0].id;
+        let now = Utc::now();
+        let rule = mock_rule(
+            &board,
+            Trigger::Idle { days: 14 },
+            vec![Condition::InColumn {
+                column_id: board.columns[1].id,
+            }],
+            vec![Action::AddOption {
+                field_id: labels,
+                option: String::from("stale"),
+            }],
+        );
+        let idle = Task {
+            updated_at: Some(now - Duration::days(15)),
+            ..task_in(&board, 1, None)
+        };
+        let recent = Task {
+            updated_at: Some(now - Duration::days(3)),
+            ..task_in(&board, 1, Some(&idle))
+        };
+        let tasks = vec![idle.clone(), recent.clone()];
+        let events = tasks.iter().cloned().map(TaskEvent::Idle).collect();
+
+        let (changed, runs) = run_rules(std::slice::from_ref(&rule), &board, &tasks, events, now);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].id, idle.id);
+        assert_eq!(
+            changed[0].custom_fields.get(&labels.to_string()),
+            Some(&CustomFieldValue::Options(vec![String::from("stale")]))
+        );
+        assert_eq!(runs.len(), 1);
+
+        // a rule whose actions change nothing leaves no run
+        let (changed, runs) = run_rules(
+            std::slice::from_ref(&rule),
+            &board,
+            &changed,
+            vec![TaskEvent::Idle(Task {
+                updated_at: idle.updated_at,
+                ..changed[0].clone()
+            })],
+            now,
+        );
+        assert!(changed.is_empty());
+        assert!(runs.is_empty());
+
+        assert_eq!(doc_to_rule(&rule_to_doc(&rule)).unwrap(), rule);
+    }
+}
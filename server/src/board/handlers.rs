@@ -1,35 +1,62 @@
 // use axum::body::Body;
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
+use chrono::Utc;
 use tracing::{debug, error};
 
-use crate::board::model::Board;
+use crate::activity::service as activity_service;
+use crate::activity::utils::{board_activities, task_activities};
+use crate::automation::service as automation_service;
+use crate::board::model::{Board, BoardColumn};
 use crate::board::service as board_service;
-use crate::board::utils::{create_and_add_column_to, filter_column_from};
+use crate::board::utils::{
+    check_wip_limits, clone_board, create_and_add_column_to, filter_column_from, get_move_target,
+    reorder_columns, validate_column, CloneOptions,
+};
 use crate::config::AppState;
 use crate::dto::{
-    CreateBoardColumnRequest, CreateBoardColumnResponse, CreateBoardRequest, Response,
-    UpdateBoardRequest,
+    ArchivedParams, CloneBoardRequest, CreateBoardColumnRequest, CreateBoardColumnResponse,
+    CreateBoardRequest, DeleteColumnParams, ReorderColumnsRequest, Response, UpdateBoardRequest,
+    UpdateColumnRequest,
 };
-use crate::error::{AppError, TaskRepoError};
+use crate::error::{AppError, BoardRepoError, TaskRepoError};
 use crate::task::model::Task;
 use crate::task::service as task_service;
-use crate::task::utils::map_task_db_to_linked;
+use crate::task::utils::{map_task_db_to_linked, move_tasks_to_column};
+use crate::template::service as template_service;
+use crate::transition::service as transition_service;
+use crate::transition::utils::column_transitions;
+use crate::trash::service as trash_service;
+use crate::user::extractor::CurrentUser;
 
 // Returns all tasks
 #[axum_macros::debug_handler]
-pub async fn get_boards_handler(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn get_boards_handler(
+    Query(params): Query<ArchivedParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
     debug!("Getting all boards");
-    match board_service::get_all_boards(state.get_boards_collection()).await {
+    let include_archived = params.include_archived.unwrap_or(false);
+    match board_service::get_all_boards(state.get_boards_collection())
+        .await
+        .map(|boards| match include_archived {
+            true => boards,
+            false => boards
+                .into_iter()
+                .filter(|b| b.archived_at.is_none())
+                .map(|b| b.without_archived_columns())
+                .collect(),
+        }) {
         Ok(boards) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
                 data: Some(boards),
                 error_message: None,
+                warnings: None,
             }),
         ),
         Err(e) => {
@@ -44,6 +71,7 @@ pub async fn get_boards_handler(State(state): State<AppState>) -> impl IntoRespo
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -53,17 +81,25 @@ pub async fn get_boards_handler(State(state): State<AppState>) -> impl IntoRespo
 #[axum_macros::debug_handler]
 pub async fn get_board_handler(
     path: Path<String>,
+    Query(params): Query<ArchivedParams>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     let board_id = path.0;
     debug!("Getting board with id {}", board_id);
-    match board_service::get_board(&board_id, state.get_boards_collection()).await {
+    let include_archived = params.include_archived.unwrap_or(false);
+    match board_service::get_board(&board_id, state.get_boards_collection())
+        .await
+        .map(|board| match include_archived {
+            true => board,
+            false => board.without_archived_columns(),
+        }) {
         Ok(board) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
                 data: Some(board),
                 error_message: None,
+                warnings: None,
             }),
         ),
         Err(e) => {
@@ -79,6 +115,7 @@ pub async fn get_board_handler(
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -88,35 +125,146 @@ pub async fn get_board_handler(
 #[axum_macros::debug_handler]
 pub async fn board_create_handler(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
     Json(req): Json<CreateBoardRequest>,
 ) -> impl IntoResponse {
     debug!(
         "[task_board_handler] Creating board with title ({})",
         req.title
     );
+    let template_id = req.template_id.clone();
     let board = Board::from_create_request(req);
-    match board_service::create(&board, state.get_boards_collection()).await {
-        Ok(_) => (
+    let board_id = board.id;
+
+    async fn create(
+        board: Board,
+        template_id: Option<String>,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Board, AppError> {
+        let board = match template_id {
+            Some(template_id) => {
+                let template =
+                    template_service::get_template(&template_id, state.get_templates_collection())
+                        .await?;
+                let options = CloneOptions {
+                    custom_fields: true,
+                    settings: true,
+                    ..Default::default()
+                };
+                clone_board(&template.layout(), &[], board, options).0
+            }
+            None => board,
+        };
+        board_service::create(&board, state.get_boards_collection()).await?;
+        activity_service::record(
+            board_activities(None, Some(&board), actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        Ok(board)
+    }
+
+    match create(board, template_id, user.map(|u| u.0), state).await {
+        Ok(board) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
                 data: Some(board),
                 error_message: None,
+                warnings: None,
             }),
         ),
         Err(e) => {
             let msg = format!(
                 "[task_board_handler] Error creating board ({}) : {:?}",
-                board.id,
+                board_id,
                 e.to_string()
             );
             error!("{}", msg);
             (
-                StatusCode::BAD_REQUEST,
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Copies the columns and swimlanes of a board into a new board, along with its
+// tasks, custom fields and settings when asked to
+#[axum_macros::debug_handler]
+pub async fn board_clone_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CloneBoardRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[board_clone_handler] Cloning board {}: {:?}",
+        board_id, req
+    );
+
+    async fn clone(
+        board_id: &String,
+        req: CloneBoardRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Board, AppError> {
+        let source = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let options = CloneOptions {
+            tasks: req.include_tasks.unwrap_or(false),
+            custom_fields: req.include_custom_fields.unwrap_or(false),
+            settings: req.include_settings.unwrap_or(false),
+        };
+        let tasks = match options.tasks {
+            true => task_service::get_tasks(board_id, state.get_tasks_collection()).await?,
+            false => Vec::new(),
+        };
+        let target = Board::from_create_request(CreateBoardRequest {
+            title: req
+                .title
+                .unwrap_or_else(|| format!("{} (copy)", source.title)),
+            description: source.description.clone(),
+            template_id: None,
+        });
+        let (board, tasks) = clone_board(&source, &tasks, target, options);
+        board_service::create_with_tasks(&board, &tasks, &state).await?;
+        let mut activities = board_activities(None, Some(&board), actor.as_deref());
+        activities.extend(task_activities(&[], &tasks, actor.as_deref()));
+        activity_service::record(activities, state.get_activity_collection()).await;
+        Ok(board)
+    }
+
+    match clone(&board_id, req, user.map(|u| u.0), state).await {
+        Ok(board) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(board),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[board_clone_handler] Error cloning board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
                 Json(Response {
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -127,6 +275,7 @@ pub async fn board_create_handler(
 pub async fn board_create_column_handler(
     path: Path<String>,
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
     Json(req): Json<CreateBoardColumnRequest>,
 ) -> impl IntoResponse {
     let board_id = path.0;
@@ -138,24 +287,52 @@ pub async fn board_create_column_handler(
     async fn match_and_update(
         board_id: &String,
         req: &CreateBoardColumnRequest,
+        actor: Option<String>,
         state: AppState,
-    ) -> Result<CreateBoardColumnResponse, AppError> {
-        let board = board_service::get_board(&board_id, state.get_boards_collection()).await?;
-        let (updated_board, new_column) = create_and_add_column_to(board, req.clone()).await?;
-        board_service::update(&updated_board, state.get_boards_collection()).await?;
-        let mapped = req
+    ) -> Result<(CreateBoardColumnResponse, Vec<String>), AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let (updated_board, new_column) =
+            create_and_add_column_to(board.clone(), req.clone()).await?;
+        let mut mapped = req
             .items
             .iter()
             .map(|task| Task {
-                column_id: Some(new_column.id.clone()),
+                column_id: Some(new_column.id),
                 ..task.clone()
             })
             .collect::<Vec<Task>>();
-        if let Ok(_) = task_service::update_many(&mapped.clone(), state).await {
-            Ok(CreateBoardColumnResponse {
-                column: new_column,
-                items: map_task_db_to_linked(mapped.clone()),
-            })
+        let warnings = check_wip_limits(&updated_board, &req.items, &mapped)?;
+        let ids = mapped.iter().map(|t| t.id).collect::<Vec<uuid::Uuid>>();
+        let stored = task_service::get_tasks_by_ids(&ids, state.get_tasks_collection()).await?;
+        board_service::update(&updated_board, state.get_boards_collection()).await?;
+        if task_service::update_many(&mapped, state.clone())
+            .await
+            .is_ok()
+        {
+            let mut activities =
+                board_activities(Some(&board), Some(&updated_board), actor.as_deref());
+            activities.extend(task_activities(&stored, &mapped, actor.as_deref()));
+            activity_service::record(activities, state.get_activity_collection()).await;
+            transition_service::record(
+                column_transitions(&stored, &mapped),
+                state.get_transitions_collection(),
+            )
+            .await;
+            for changed in automation_service::react_to_updates(&stored, &mapped, &state).await {
+                if let Some(task) = mapped.iter_mut().find(|t| t.id == changed.id) {
+                    *task = changed;
+                }
+            }
+            Ok((
+                CreateBoardColumnResponse {
+                    column: new_column,
+                    items: map_task_db_to_linked(
+                        mapped.clone(),
+                        std::slice::from_ref(&updated_board),
+                    ),
+                },
+                warnings,
+            ))
         } else {
             Err(AppError::TaskRepo(TaskRepoError::TransactionError(
                 String::from("Coudnt complete update of tasks"),
@@ -163,13 +340,14 @@ pub async fn board_create_column_handler(
         }
     }
 
-    match match_and_update(&board_id, &req, state).await {
-        Ok(data) => (
+    match match_and_update(&board_id, &req, user.map(|u| u.0), state).await {
+        Ok((data, warnings)) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
                 data: Some(data),
                 error_message: None,
+                warnings: (!warnings.is_empty()).then_some(warnings),
             }),
         ),
         Err(e) => {
@@ -181,21 +359,25 @@ pub async fn board_create_column_handler(
             );
             error!("{}", msg);
             (
-                StatusCode::BAD_REQUEST,
+                e.status_or(StatusCode::BAD_REQUEST),
                 Json(Response {
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
     }
 }
 
+// Deletes a column, its tasks are moved to the column given as `move_tasks_to`
 #[axum_macros::debug_handler]
 pub async fn board_delete_column_handler(
     Path((board_id, column_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+    Query(params): Query<DeleteColumnParams>,
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
 ) -> impl IntoResponse {
     debug!(
         "[board_delete_column_handler] Deleting board column with id ({:?}) for board ({:?})",
@@ -205,42 +387,77 @@ pub async fn board_delete_column_handler(
     async fn match_and_update(
         board_id: &String,
         column_id: &uuid::Uuid,
+        move_tasks_to: Option<String>,
+        deleted_by: Option<String>,
         state: AppState,
-    ) -> Result<Board, AppError> {
-        let board = board_service::get_board(&board_id, state.get_boards_collection()).await?;
-        let filtered_board = filter_column_from(board, column_id.clone());
-        let updated_board =
-            board_service::update(&filtered_board, state.get_boards_collection()).await?;
-        debug!("Correctly updated board {:?}", filtered_board);
-        if let Ok(_) = task_service::delete_tasks_of_column(
-            &column_id.to_string(),
-            state.get_tasks_collection(),
+    ) -> Result<(Board, Vec<String>), AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        board.column(*column_id).ok_or(BoardRepoError::NotFound)?;
+        let board_tasks = task_service::get_tasks(board_id, state.get_tasks_collection()).await?;
+        let column_tasks = board_tasks
+            .iter()
+            .filter(|t| t.column_id == Some(*column_id))
+            .cloned()
+            .collect::<Vec<Task>>();
+        let filtered_board = filter_column_from(board.clone(), *column_id);
+        let target = match move_tasks_to {
+            Some(target) => get_move_target(&target, &filtered_board)?,
+            None if column_tasks.is_empty() => None,
+            None => {
+                return Err(AppError::Conflict(format!(
+                    "column {} still holds {} tasks, use move_tasks_to to keep them",
+                    column_id,
+                    column_tasks.len()
+                )))
+            }
+        };
+        let target_tasks = board_tasks
+            .iter()
+            .filter(|t| t.column_id == target)
+            .cloned()
+            .collect::<Vec<Task>>();
+        let moved = move_tasks_to_column(&column_tasks, &target_tasks, target);
+        let after = board_tasks
+            .iter()
+            .filter(|t| t.column_id != Some(*column_id))
+            .chain(moved.iter())
+            .cloned()
+            .collect::<Vec<Task>>();
+        let warnings = check_wip_limits(&filtered_board, &board_tasks, &after)?;
+        let updated_board = trash_service::trash_column(
+            &board,
+            *column_id,
+            &column_tasks,
+            &moved,
+            deleted_by,
+            &state,
         )
-        .await
-        {
-            debug!(
-                "Correctly deleted tasks after deletion of column board with id {:?}",
-                column_id
-            );
-            Ok(updated_board)
-        } else {
-            debug!(
-                "Error int deleting tasks after deletion of column board with id {:?}",
-                column_id
-            );
-            Err(AppError::TaskRepo(TaskRepoError::TransactionError(
-                String::from("Couldnt complete delete of column tasks"),
-            )))
-        }
+        .await?;
+        automation_service::react_to_updates(&column_tasks, &moved, &state).await;
+        debug!(
+            "Moved {} tasks after deletion of column board with id {:?}",
+            moved.len(),
+            column_id
+        );
+        Ok((updated_board, warnings))
     }
 
-    match match_and_update(&board_id.to_string(), &column_id, state).await {
-        Ok(data) => (
+    match match_and_update(
+        &board_id.to_string(),
+        &column_id,
+        params.move_tasks_to,
+        user.map(|u| u.0),
+        state,
+    )
+    .await
+    {
+        Ok((data, warnings)) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
                 data: Some(data),
                 error_message: None,
+                warnings: (!warnings.is_empty()).then_some(warnings),
             }),
         ),
         Err(e) => {
@@ -252,11 +469,12 @@ pub async fn board_delete_column_handler(
             );
             error!("{}", msg);
             (
-                StatusCode::BAD_REQUEST,
+                e.status_or(StatusCode::BAD_REQUEST),
                 Json(Response {
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -266,18 +484,32 @@ pub async fn board_delete_column_handler(
 // Updates existing task
 pub async fn board_update_handler(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
     Json(req): Json<UpdateBoardRequest>,
 ) -> impl IntoResponse {
     let board_id = req.id.to_string();
     debug!("[board_update_handler] Updating board {}", board_id);
-    let board = Board::from_update_request(req);
-    match board_service::update(&board, state.get_boards_collection()).await {
+
+    async fn update(
+        req: UpdateBoardRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Board, AppError> {
+        let stored =
+            board_service::get_board(&req.id.to_string(), state.get_boards_collection()).await?;
+        let board = Board::from_update_request(req, &stored);
+        board_service::update(&board, state.get_boards_collection()).await?;
+        record_change(stored, actor, &state).await
+    }
+
+    match update(req, user.map(|u| u.0), state).await {
         Ok(b) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
                 data: Some(b),
                 error_message: None,
+                warnings: None,
             }),
         ),
         Err(e) => {
@@ -293,6 +525,7 @@ pub async fn board_update_handler(
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -304,12 +537,16 @@ pub async fn board_update_handler(
 pub async fn board_delete_handler(
     path: Path<String>,
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
 ) -> impl IntoResponse {
     let board_id = path.0;
-    debug!("[task_delete_handler] Deleting board {}", board_id);
-    match board_service::delete(&board_id, state.get_boards_collection()).await {
-        Ok(_) => {
-            let msg = format!("Deleted board with id {}", board_id);
+    debug!("[board_delete_handler] Moving board {} to trash", board_id);
+    match trash_service::trash_board(&board_id, user.map(|u| u.0), &state).await {
+        Ok(item) => {
+            let msg = format!(
+                "Moved board with id {} and its {} tasks to trash item {}",
+                board_id, item.task_count, item.id
+            );
             debug!("[board_delete_handler] {}", msg);
             (
                 StatusCode::OK,
@@ -317,18 +554,322 @@ pub async fn board_delete_handler(
                     success: true,
                     data: Some(msg),
                     error_message: None,
+                    warnings: None,
                 }),
             )
         }
         Err(e) => {
-            let msg = format!("Error in deleting board {}: {}", board_id, e.to_string());
+            let msg = format!("Error in deleting board {}: {}", board_id, e);
             error!("{}", msg);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(Response {
-                    success: true,
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Changes the name, colour, description, done flag or WIP limit of a column
+#[axum_macros::debug_handler]
+pub async fn board_update_column_handler(
+    Path((board_id, column_id)): Path<(String, uuid::Uuid)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<UpdateColumnRequest>,
+) -> impl IntoResponse {
+    debug!(
+        "[board_update_column_handler] Updating column {} of board {}",
+        column_id, board_id
+    );
+
+    async fn validate_and_update(
+        board_id: &String,
+        column_id: uuid::Uuid,
+        req: UpdateColumnRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<BoardColumn, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let column = board
+            .column(column_id)
+            .cloned()
+            .ok_or(BoardRepoError::NotFound)?
+            .with_update_request(req);
+        validate_column(&column)?;
+        board_service::update_column(board_id, &column, state.get_boards_collection()).await?;
+        record_change(board, actor, &state).await?;
+        Ok(column)
+    }
+
+    match validate_and_update(&board_id, column_id, req, user.map(|u| u.0), state).await {
+        Ok(column) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(column),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[board_update_column_handler] Error updating column {} of board {}: {:?}",
+                column_id,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Changes the order of some columns of a board, returns the columns in their new order
+#[axum_macros::debug_handler]
+pub async fn board_reorder_columns_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<ReorderColumnsRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[board_reorder_columns_handler] Reordering columns {:?} of board {}",
+        req.column_ids, board_id
+    );
+
+    async fn reorder(
+        board_id: &String,
+        req: &ReorderColumnsRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Vec<BoardColumn>, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let columns = reorder_columns(&board.columns, &req.column_ids)?;
+        board_service::replace_columns(&board, &columns, state.get_boards_collection()).await?;
+        record_change(board, actor, &state).await?;
+        Ok(columns)
+    }
+
+    match reorder(&board_id, &req, user.map(|u| u.0), state).await {
+        Ok(columns) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(columns),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[board_reorder_columns_handler] Error reordering columns of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+/// Records the change made to a board by an update of the store, returns the
+/// updated board.
+async fn record_change(
+    before: Board,
+    actor: Option<String>,
+    state: &AppState,
+) -> Result<Board, AppError> {
+    let after =
+        board_service::get_board(&before.id.to_string(), state.get_boards_collection()).await?;
+    activity_service::record(
+        board_activities(Some(&before), Some(&after), actor.as_deref()),
+        state.get_activity_collection(),
+    )
+    .await;
+    Ok(after)
+}
+
+// Archives a board, archived boards are hidden from the boards list unless asked for
+#[axum_macros::debug_handler]
+pub async fn board_archive_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    set_board_archived(path.0, true, user.map(|u| u.0), state).await
+}
+
+// Restores an archived board
+#[axum_macros::debug_handler]
+pub async fn board_unarchive_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    set_board_archived(path.0, false, user.map(|u| u.0), state).await
+}
+
+async fn set_board_archived(
+    board_id: String,
+    archived: bool,
+    actor: Option<String>,
+    state: AppState,
+) -> (StatusCode, Json<Response<Board>>) {
+    debug!(
+        "[set_board_archived] Board {} archived: {}",
+        board_id, archived
+    );
+
+    async fn update(
+        board_id: &String,
+        archived: bool,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Board, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        if board.archived_at.is_some() == archived {
+            return Ok(board);
+        }
+        let archived_at = archived.then(Utc::now);
+        board_service::set_archived(board_id, archived_at, state.get_boards_collection()).await?;
+        record_change(board, actor, &state).await
+    }
+
+    match update(&board_id, archived, actor, state).await {
+        Ok(board) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(board),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[set_board_archived] Error archiving ({}) board {}: {:?}",
+                archived,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Archives a column, hiding it and its tasks unless asked for
+#[axum_macros::debug_handler]
+pub async fn board_archive_column_handler(
+    Path((board_id, column_id)): Path<(String, uuid::Uuid)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    set_column_archived(board_id, column_id, true, user.map(|u| u.0), state).await
+}
+
+// Restores an archived column along with its tasks
+#[axum_macros::debug_handler]
+pub async fn board_unarchive_column_handler(
+    Path((board_id, column_id)): Path<(String, uuid::Uuid)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    set_column_archived(board_id, column_id, false, user.map(|u| u.0), state).await
+}
+
+async fn set_column_archived(
+    board_id: String,
+    column_id: uuid::Uuid,
+    archived: bool,
+    actor: Option<String>,
+    state: AppState,
+) -> (StatusCode, Json<Response<BoardColumn>>) {
+    debug!(
+        "[set_column_archived] Column {} of board {} archived: {}",
+        column_id, board_id, archived
+    );
+
+    async fn update(
+        board_id: &String,
+        column_id: uuid::Uuid,
+        archived: bool,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<BoardColumn, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let column = board
+            .column(column_id)
+            .cloned()
+            .ok_or(BoardRepoError::NotFound)?;
+        if column.archived_at.is_some() == archived {
+            return Ok(column);
+        }
+        let column = BoardColumn {
+            archived_at: archived.then(Utc::now),
+            ..column
+        };
+        board_service::update_column(board_id, &column, state.get_boards_collection()).await?;
+        record_change(board, actor, &state).await?;
+        Ok(column)
+    }
+
+    match update(&board_id, column_id, archived, actor, state).await {
+        Ok(column) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(column),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[set_column_archived] Error archiving ({}) column {} of board {}: {:?}",
+                archived,
+                column_id,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
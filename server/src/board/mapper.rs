@@ -7,9 +7,12 @@ use mongodb::bson::doc;
 use tokio_stream::StreamExt;
 use uuid::Uuid;
 
-use crate::board::model::{Board, BoardColumn};
+use crate::board::model::{Board, BoardColumn, BoardSettings, WipMode};
+use crate::custom_field::mapper::{custom_field_to_doc, get_custom_fields};
 use crate::error::AppError;
 use crate::error::{BoardRepoError, Result};
+use crate::swimlane::mapper::{get_swimlanes, swimlane_to_doc};
+use crate::util::get_optional_uuid;
 
 pub async fn parse_boards(
     mut cursor: mongodb::Cursor<bson::document::Document>,
@@ -37,6 +40,13 @@ pub fn doc_to_board(doc: &bson::document::Document) -> Result<Board> {
             title: title.to_owned(),
             description: description.map(|d| d.to_string()),
             columns,
+            custom_fields: get_custom_fields(doc),
+            swimlanes: get_swimlanes(doc),
+            settings: get_board_settings(doc),
+            archived_at: doc
+                .get_datetime("archived_at")
+                .ok()
+                .map(|v| chrono::DateTime::from(*v)),
             created_at: chrono::DateTime::from(created_at),
             updated_at,
         }),
@@ -47,9 +57,13 @@ pub fn doc_to_board(doc: &bson::document::Document) -> Result<Board> {
     }
 }
 
-fn doc_to_board_column(doc: &bson::document::Document) -> Result<BoardColumn> {
+pub fn doc_to_board_column(doc: &bson::document::Document) -> Result<BoardColumn> {
     let id = doc.get_str("id")?;
     let name = doc.get_str("name")?;
+    let colour = doc.get_str("colour").ok().map(|c| c.to_owned());
+    let description = doc.get_str("description").ok().map(|d| d.to_owned());
+    let done = doc.get_bool("done").unwrap_or(false);
+    let wip_limit = doc.get_i64("wip_limit").ok().map(|l| l as u32);
     let created_at = bson::DateTime::from_ref(doc.get_datetime("created_at")?);
     let updated_at = doc
         .get_datetime("updated_at")
@@ -59,6 +73,14 @@ fn doc_to_board_column(doc: &bson::document::Document) -> Result<BoardColumn> {
         Ok(board_stage_id) => Ok(BoardColumn {
             id: board_stage_id,
             name: name.to_owned(),
+            colour,
+            description,
+            done,
+            wip_limit,
+            archived_at: doc
+                .get_datetime("archived_at")
+                .ok()
+                .map(|v| chrono::DateTime::from(*v)),
             created_at: chrono::DateTime::from(created_at),
             updated_at,
         }),
@@ -69,24 +91,29 @@ fn doc_to_board_column(doc: &bson::document::Document) -> Result<BoardColumn> {
     }
 }
 
-fn map_columns_to_docs(board_columns: &Vec<BoardColumn>) -> Vec<bson::document::Document> {
-    return board_columns
-        .into_iter()
+pub fn map_columns_to_docs(board_columns: &[BoardColumn]) -> Vec<bson::document::Document> {
+    board_columns
+        .iter()
         .map(|stage| doc! {
             "id" : stage.id.to_string(),
             "name" : stage.name.clone(),
+            "colour" : stage.colour.clone(),
+            "description" : stage.description.clone(),
+            "done" : stage.done,
+            "wip_limit" : stage.wip_limit.map(i64::from),
             "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(stage.created_at),
-            "updated_at" : stage.updated_at.map(|v| <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(v)).clone(),
+            "updated_at" : stage.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+            "archived_at" : stage.archived_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
             })
         .clone()
-        .collect();
+        .collect()
 }
 
-fn get_board_columns(doc: &bson::document::Document) -> Vec<BoardColumn> {
+pub fn get_board_columns(doc: &bson::document::Document) -> Vec<BoardColumn> {
     doc.get_array("columns")
         .ok()
         .unwrap_or(&Vec::new())
-        .into_iter()
+        .iter()
         .map(|entry| {
             entry
                 .as_document()
@@ -96,13 +123,44 @@ fn get_board_columns(doc: &bson::document::Document) -> Vec<BoardColumn> {
         .collect()
 }
 
+pub fn get_board_settings(doc: &bson::document::Document) -> BoardSettings {
+    doc.get_document("settings")
+        .map(|settings| BoardSettings {
+            wip_mode: settings
+                .get_str("wip_mode")
+                .ok()
+                .and_then(|m| WipMode::from_str(m).ok())
+                .unwrap_or_default(),
+            started_column_id: get_optional_uuid(settings, "started_column_id"),
+            done_column_id: get_optional_uuid(settings, "done_column_id"),
+            swimlanes_by: settings
+                .get_document("swimlanes_by")
+                .ok()
+                .and_then(|s| bson::from_document(s.clone()).ok()),
+        })
+        .unwrap_or_default()
+}
+
+pub fn settings_to_doc(settings: &BoardSettings) -> bson::document::Document {
+    doc! {
+        "wip_mode" : settings.wip_mode.to_string(),
+        "started_column_id" : settings.started_column_id.map(|c| c.to_string()),
+        "done_column_id" : settings.done_column_id.map(|c| c.to_string()),
+        "swimlanes_by" : settings.swimlanes_by.as_ref().and_then(|s| bson::to_document(s).ok()),
+    }
+}
+
 pub fn board_to_doc(board: &Board) -> bson::document::Document {
     doc! {
         "id" : board.id.clone().to_string(),
         "title" : board.title.clone(),
         "description" : board.description.clone(),
         "columns" : map_columns_to_docs(&board.columns),
+        "settings" : settings_to_doc(&board.settings),
+        "custom_fields" : board.custom_fields.iter().map(custom_field_to_doc).collect::<Vec<bson::document::Document>>(),
+        "swimlanes" : board.swimlanes.iter().map(swimlane_to_doc).collect::<Vec<bson::document::Document>>(),
         "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(board.created_at),
-        "updated_at" : board.updated_at.map(|v| <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(v)).clone()
+        "updated_at" : board.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+        "archived_at" : board.archived_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into)
     }
 }
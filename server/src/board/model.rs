@@ -1,8 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
 
-use crate::dto::{CreateBoardRequest, UpdateBoardRequest, UpdateBoardRequestColumn};
+use crate::custom_field::model::CustomField;
+use crate::dto::{
+    CreateBoardRequest, UpdateBoardRequest, UpdateBoardRequestColumn, UpdateColumnRequest,
+};
+use crate::swimlane::model::{Swimlane, SwimlaneSource};
 
 #[skip_serializing_none]
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -11,8 +16,18 @@ pub struct Board {
     pub title: String,
     pub description: Option<String>,
     pub columns: Vec<BoardColumn>,
+    /// Managed through the custom fields endpoints.
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    /// Ordered rows of the board, managed through the swimlanes endpoints.
+    #[serde(default)]
+    pub swimlanes: Vec<Swimlane>,
+    #[serde(default)]
+    pub settings: BoardSettings,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// Set while the board is archived, managed through the archive endpoints.
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 #[skip_serializing_none]
@@ -20,8 +35,62 @@ pub struct Board {
 pub struct BoardColumn {
     pub id: uuid::Uuid,
     pub name: String,
+    /// Hex colour, e.g. `#1e90ff`.
+    pub colour: Option<String>,
+    pub description: Option<String>,
+    /// Tasks sitting in a done column count as completed.
+    #[serde(default)]
+    pub done: bool,
+    /// Maximum number of tasks the column should hold.
+    pub wip_limit: Option<u32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// The tasks of an archived column are hidden along with it.
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct BoardSettings {
+    #[serde(default)]
+    pub wip_mode: WipMode,
+    /// Work on a task starts when it enters this column or a later one, the
+    /// second column of the board by default.
+    pub started_column_id: Option<uuid::Uuid>,
+    /// A task is finished when it enters this column or a later one, the first
+    /// done column of the board by default.
+    pub done_column_id: Option<uuid::Uuid>,
+    /// Set when the swimlanes were derived from the tasks, new and updated
+    /// tasks then go to the lane matching their value.
+    pub swimlanes_by: Option<SwimlaneSource>,
+}
+
+/// What happens when a task would exceed the WIP limit of the column it enters.
+#[derive(
+    Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, Display, EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WipMode {
+    /// The change is applied and reported with a warning.
+    #[default]
+    Warn,
+    /// The change is refused.
+    Block,
+}
+
+impl BoardColumn {
+    pub fn with_update_request(self, r: UpdateColumnRequest) -> Self {
+        Self {
+            name: r.name.map(|n| n.trim().to_owned()).unwrap_or(self.name),
+            colour: r.colour.unwrap_or(self.colour),
+            description: r.description.unwrap_or(self.description),
+            done: r.done.unwrap_or(self.done),
+            wip_limit: r.wip_limit.unwrap_or(self.wip_limit),
+            updated_at: Some(Utc::now()),
+            ..self
+        }
+    }
 }
 
 impl Board {
@@ -31,43 +100,118 @@ impl Board {
             title: r.title,
             description: r.description,
             columns: Vec::<BoardColumn>::new(),
+            custom_fields: Vec::new(),
+            swimlanes: Vec::new(),
+            settings: BoardSettings::default(),
             created_at: Utc::now(),
             updated_at: None,
+            archived_at: None,
         }
     }
 
-    pub fn from_update_request(r: UpdateBoardRequest) -> Self {
+    /// Builds the board described by an update request, the fields missing from
+    /// the request keep their stored value, as do the archived columns.
+    pub fn from_update_request(r: UpdateBoardRequest, stored: &Board) -> Self {
         let current_time = Utc::now();
 
         fn map_columns_with_id(
             columns: Vec<UpdateBoardRequestColumn>,
+            stored: &[BoardColumn],
             current_time: DateTime<Utc>,
         ) -> Vec<BoardColumn> {
-            columns
+            let mut mapped = columns
                 .iter()
                 .map(|c| BoardColumn {
                     id: c.id.unwrap_or(uuid::Uuid::new_v4()),
                     name: c.name.clone(),
+                    colour: c.colour.clone(),
+                    description: c.description.clone(),
+                    done: c.done.unwrap_or(false),
+                    wip_limit: c.wip_limit,
                     created_at: c.created_at.unwrap_or(current_time),
                     updated_at: c.updated_at,
+                    archived_at: stored
+                        .iter()
+                        .find(|s| Some(s.id) == c.id)
+                        .and_then(|s| s.archived_at),
                 })
-                .collect::<Vec<BoardColumn>>()
+                .collect::<Vec<BoardColumn>>();
+            // archived columns are hidden from the boards clients read, and so
+            // from the boards they send back
+            let hidden = stored
+                .iter()
+                .filter(|s| s.archived_at.is_some() && !mapped.iter().any(|c| c.id == s.id))
+                .cloned()
+                .collect::<Vec<BoardColumn>>();
+            mapped.extend(hidden);
+            mapped
         }
 
         Self {
             id: r.id,
             title: r.title,
             description: r.description,
-            columns: map_columns_with_id(r.columns, current_time),
+            columns: map_columns_with_id(r.columns, &stored.columns, current_time),
+            custom_fields: stored.custom_fields.clone(),
+            swimlanes: stored.swimlanes.clone(),
+            settings: r.settings.unwrap_or_else(|| stored.settings.clone()),
             created_at: r.created_at,
             updated_at: Some(current_time),
+            archived_at: stored.archived_at,
         }
     }
 
+    pub fn done_column_ids(&self) -> Vec<uuid::Uuid> {
+        self.columns
+            .iter()
+            .filter(|c| c.done)
+            .map(|c| c.id)
+            .collect()
+    }
+
+    pub fn column(&self, column_id: uuid::Uuid) -> Option<&BoardColumn> {
+        self.columns.iter().find(|c| c.id == column_id)
+    }
+
     pub fn with_updated_columns(self, new_columns: Vec<BoardColumn>) -> Self {
         Self {
             columns: new_columns,
             ..self
         }
     }
+
+    /// Hides the archived columns.
+    pub fn without_archived_columns(self) -> Self {
+        Self {
+            columns: self
+                .columns
+                .into_iter()
+                .filter(|c| c.archived_at.is_none())
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn archived_column_ids(&self) -> Vec<uuid::Uuid> {
+        self.columns
+            .iter()
+            .filter(|c| c.archived_at.is_some())
+            .map(|c| c.id)
+            .collect()
+    }
+
+    pub fn with_custom_fields(self, custom_fields: Vec<CustomField>) -> Self {
+        Self {
+            custom_fields,
+            ..self
+        }
+    }
+
+    pub fn with_swimlanes(self, swimlanes: Vec<Swimlane>) -> Self {
+        Self { swimlanes, ..self }
+    }
+
+    pub fn swimlane(&self, swimlane_id: uuid::Uuid) -> Option<&Swimlane> {
+        self.swimlanes.iter().find(|l| l.id == swimlane_id)
+    }
 }
@@ -1,21 +1,56 @@
+use std::collections::HashSet;
+
 use bson::{doc, Document};
 use chrono::Utc;
 use mongodb::Collection;
 use tracing::{debug, error};
 
-use crate::board::mapper::{board_to_doc, parse_boards};
-use crate::board::model::Board;
+use crate::attachment::service as attachment_service;
+use crate::board::mapper::{board_to_doc, map_columns_to_docs, parse_boards};
+use crate::board::model::{Board, BoardColumn};
+use crate::config::AppState;
+use crate::db::run_transaction;
 use crate::error::AppError;
 use crate::error::{BoardRepoError, Result, TaskRepoError};
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::task::utils::task_to_doc;
 
 use super::mapper::doc_to_board;
 
 pub async fn get_all_boards(collection: Collection<Document>) -> Result<Vec<Board>> {
-    let mut cursor = collection.find(None, None).await.map_err(|_e| {
+    let cursor = collection.find(None, None).await.map_err(|_e| {
         debug!("ERROR [get_tasks] {:?}", _e);
-        return BoardRepoError::NotFound;
+        BoardRepoError::NotFound
     })?;
-    return parse_boards(cursor).await;
+    parse_boards(cursor).await
+}
+
+pub async fn get_boards_by_ids(
+    board_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<Vec<Board>> {
+    if board_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ids = board_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    let cursor = collection.find(doc! { "id": { "$in": ids } }, None).await?;
+    parse_boards(cursor).await
+}
+
+/// Returns the ids of the done columns of the given boards.
+pub async fn get_done_column_ids(
+    board_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<HashSet<uuid::Uuid>> {
+    Ok(get_boards_by_ids(board_ids, collection)
+        .await?
+        .iter()
+        .flat_map(|b| b.done_column_ids())
+        .collect())
 }
 
 pub async fn create(board: &Board, collection: Collection<Document>) -> Result<&Board> {
@@ -23,11 +58,40 @@ pub async fn create(board: &Board, collection: Collection<Document>) -> Result<&
     let doc = board_to_doc(board);
     collection.insert_one(doc, None).await.map_err(|_e| {
         error!("ERROR [create_board] {:?}", _e);
-        return BoardRepoError::InvalidBoard(_e.to_string());
+        BoardRepoError::InvalidBoard(_e.to_string())
     })?;
     Ok(board)
 }
 
+/// Creates a board along with its tasks in a single transaction, so that a
+/// failure doesn't leave the board without some of its tasks.
+pub async fn create_with_tasks(board: &Board, tasks: &[Task], state: &AppState) -> Result<()> {
+    debug!(
+        "[create_board] Creating board with id={} and {} tasks",
+        &board.id,
+        tasks.len()
+    );
+    let board_doc = board_to_doc(board);
+    let task_docs = tasks.iter().map(task_to_doc).collect::<Vec<Document>>();
+    run_transaction(&state.client, |session| {
+        let (board_doc, task_docs, state) = (board_doc.clone(), task_docs.clone(), state.clone());
+        Box::pin(async move {
+            state
+                .get_boards_collection()
+                .insert_one_with_session(board_doc, None, session)
+                .await?;
+            if !task_docs.is_empty() {
+                state
+                    .get_tasks_collection()
+                    .insert_many_with_session(task_docs, None, session)
+                    .await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
 pub async fn get_board(board_id: &String, collection: Collection<Document>) -> Result<Board> {
     let filter = doc! { "id": board_id };
     let board_opt = collection
@@ -68,27 +132,167 @@ pub async fn update(board: &Board, collection: Collection<Document>) -> Result<B
         .update_one(filter, updates, None)
         .await
         .map(|res| {
-            return if res.modified_count == 1 {
+            if res.modified_count == 1 {
                 Ok(())
             } else {
                 Err(AppError::BoardRepo(BoardRepoError::InvalidBoard(format!(
                     "modified_count = {:?} on {}",
                     res.modified_count, res.matched_count
                 ))))
-            };
+            }
         })
         .map_err(|_e| {
             debug!("ERROR [update_board] {:?}", _e);
-            return AppError::MongoError(_e);
+            AppError::MongoError(_e)
         })?
-        .expect(format!("Coudn't update board {}", board_id).as_str());
+        .unwrap_or_else(|_| panic!("Coudn't update board {}", board_id));
 
     Ok(updated)
 }
 
-pub async fn delete(board_id: &String, collection: Collection<Document>) -> Result<()> {
-    debug!("[delete_board] Deleting board with id={}", board_id);
+/// Replaces a single column of a board, leaving the others untouched.
+pub async fn update_column(
+    board_id: &String,
+    column: &BoardColumn,
+    collection: Collection<Document>,
+) -> Result<()> {
+    debug!(
+        "[update_column] Updating column {} of board {}",
+        column.id, board_id
+    );
+    let filter = doc! { "id": board_id, "columns.id": column.id.to_string() };
+    let column_doc = map_columns_to_docs(std::slice::from_ref(column)).remove(0);
+    let updates = doc! { "$set": {
+        "columns.$": column_doc,
+        "updated_at": <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(Utc::now()),
+    } };
+    let res = collection
+        .update_one(filter, updates, None)
+        .await
+        .map_err(|_e| {
+            debug!("ERROR [update_column] {:?}", _e);
+            AppError::MongoError(_e)
+        })?;
+    match res.matched_count {
+        1 => Ok(()),
+        _ => Err(BoardRepoError::NotFound.into()),
+    }
+}
+
+pub async fn set_archived(
+    board_id: &String,
+    archived_at: Option<chrono::DateTime<Utc>>,
+    collection: Collection<Document>,
+) -> Result<()> {
+    debug!(
+        "[set_archived] Setting archived_at of board {} to {:?}",
+        board_id, archived_at
+    );
     let filter = doc! { "id": board_id };
-    collection.delete_one(filter, None).await?;
-    Ok(())
+    let updates = doc! { "$set": {
+        "archived_at": archived_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    } };
+    match collection
+        .update_one(filter, updates, None)
+        .await?
+        .matched_count
+    {
+        1 => Ok(()),
+        _ => Err(BoardRepoError::NotFound.into()),
+    }
+}
+
+/// Replaces the columns of a board as long as nobody changed the board since
+/// it was read as `previous`, going by its `updated_at`.
+pub async fn replace_columns(
+    previous: &Board,
+    columns: &[BoardColumn],
+    collection: Collection<Document>,
+) -> Result<()> {
+    let board_id = previous.id.to_string();
+    debug!("[replace_columns] Replacing columns of board {}", board_id);
+    // boards that were never updated have no (or a null) updated_at, both match null
+    let filter = doc! {
+        "id": &board_id,
+        "updated_at": previous.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    };
+    let updates = doc! { "$set": {
+        "columns": map_columns_to_docs(columns),
+        "updated_at": <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(Utc::now()),
+    } };
+    let res = collection
+        .update_one(filter, updates, None)
+        .await
+        .map_err(|_e| {
+            debug!("ERROR [replace_columns] {:?}", _e);
+            AppError::MongoError(_e)
+        })?;
+    match res.matched_count {
+        1 => Ok(()),
+        _ => Err(AppError::Conflict(format!(
+            "columns of board {} changed in the meantime",
+            board_id
+        ))),
+    }
+}
+
+/// Deletes a board along with its tasks and everything attached to them
+/// (comments, attachments, notifications) in a single transaction, and removes
+/// the deleted tasks from the dependencies of tasks of other boards.
+/// Attachment contents are removed from the blob store once committed.
+/// Returns the number of deleted tasks.
+pub async fn delete_with_tasks(board_id: &String, state: &AppState) -> Result<usize> {
+    debug!(
+        "[delete_board] Deleting board with id={} and its tasks",
+        board_id
+    );
+    let task_ids = task_service::get_tasks(board_id, state.get_tasks_collection())
+        .await?
+        .iter()
+        .map(|t| t.id.to_string())
+        .collect::<Vec<String>>();
+    let attachments =
+        attachment_service::get_attachments_of_board(board_id, state.get_attachments_collection())
+            .await?;
+    run_transaction(&state.client, |session| {
+        let (board_id, task_ids, state) = (board_id.clone(), task_ids.clone(), state.clone());
+        Box::pin(async move {
+            let by_board = doc! { "board_id": &board_id };
+            state
+                .get_boards_collection()
+                .delete_one_with_session(doc! { "id": &board_id }, None, session)
+                .await?;
+            state
+                .get_tasks_collection()
+                .delete_many_with_session(by_board.clone(), None, session)
+                .await?;
+            state
+                .get_tasks_collection()
+                .update_many_with_session(
+                    doc! { "blocked_by": { "$in": task_ids.clone() } },
+                    doc! { "$pull": { "blocked_by": { "$in": task_ids } } },
+                    None,
+                    session,
+                )
+                .await?;
+            for collection in [
+                state.get_comments_collection(),
+                state.get_attachments_collection(),
+                state.get_notifications_collection(),
+                state.get_sprints_collection(),
+                state.get_task_templates_collection(),
+                state.get_automations_collection(),
+                state.get_automation_runs_collection(),
+                state.get_chat_webhooks_collection(),
+            ] {
+                collection
+                    .delete_many_with_session(by_board.clone(), None, session)
+                    .await?;
+            }
+            Ok(())
+        })
+    })
+    .await?;
+    attachment_service::delete_contents(&attachments, state.blob_store.as_ref()).await;
+    Ok(task_ids.len())
 }
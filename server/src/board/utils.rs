@@ -1,8 +1,13 @@
+use std::collections::HashMap;
+
 use chrono::Utc;
 
-use crate::board::model::{Board, BoardColumn};
+use crate::board::model::{Board, BoardColumn, BoardSettings, WipMode};
+use crate::custom_field::model::CustomField;
 use crate::dto::CreateBoardColumnRequest;
-use crate::error::Result;
+use crate::error::{AppError, BoardRepoError, Result};
+use crate::swimlane::model::{Swimlane, SwimlaneSource};
+use crate::task::model::Task;
 
 pub async fn create_and_add_column_to(
     board: Board,
@@ -13,8 +18,13 @@ pub async fn create_and_add_column_to(
     let new_column = BoardColumn {
         id: column_id,
         name: request.title,
+        colour: None,
+        description: None,
+        done: false,
+        wip_limit: request.wip_limit,
         created_at: now,
         updated_at: Some(now),
+        archived_at: None,
     };
 
     let updated_columns = merge_columns(&board.columns, new_column.clone(), request.was_unassigned);
@@ -22,14 +32,12 @@ pub async fn create_and_add_column_to(
 }
 
 fn merge_columns(
-    items: &Vec<BoardColumn>,
+    items: &[BoardColumn],
     new_column: BoardColumn,
     was_unassigned: Option<bool>,
 ) -> Vec<BoardColumn> {
-    fn add_all_to(all_columns: &mut Vec<BoardColumn>, items: &Vec<BoardColumn>) {
-        items
-            .into_iter()
-            .for_each(|col| all_columns.push(col.clone()));
+    fn add_all_to(all_columns: &mut Vec<BoardColumn>, items: &[BoardColumn]) {
+        items.iter().for_each(|col| all_columns.push(col.clone()));
     }
 
     let mut all_columns = Vec::new();
@@ -52,53 +60,275 @@ pub fn filter_column_from(board: Board, column_id: uuid::Uuid) -> Board {
             .clone()
             .columns
             .into_iter()
-            .filter(|c| c.id.clone() != column_id)
+            .filter(|c| c.id != column_id)
             .collect(),
         ..board
     }
 }
 
+/// Columns of a board whose task count grows over their WIP limit with a
+/// change, along with their task count after it. Archived tasks don't count.
+pub fn columns_over_wip_limit<'a>(
+    board: &'a Board,
+    before: &[Task],
+    after: &[Task],
+) -> Vec<(&'a BoardColumn, usize)> {
+    let count = |tasks: &[Task], column_id: uuid::Uuid| {
+        tasks
+            .iter()
+            .filter(|t| t.column_id == Some(column_id) && t.archived_at.is_none())
+            .count()
+    };
+    board
+        .columns
+        .iter()
+        .filter_map(|c| {
+            let limit = c.wip_limit? as usize;
+            let (count_before, count_after) = (count(before, c.id), count(after, c.id));
+            (count_after > limit && count_after > count_before).then_some((c, count_after))
+        })
+        .collect()
+}
+
+/// Checks the WIP limits of the columns of a board against the tasks of the
+/// board before and after a change. Only the columns whose task count grows
+/// over their limit are reported: as warnings when the board is in `warn` mode,
+/// as a `Conflict` error when it is in `block` mode.
+pub fn check_wip_limits(board: &Board, before: &[Task], after: &[Task]) -> Result<Vec<String>> {
+    let violations = columns_over_wip_limit(board, before, after)
+        .into_iter()
+        .map(|(c, count)| {
+            format!(
+                "column {} is over its WIP limit ({}/{})",
+                c.name,
+                count,
+                c.wip_limit.unwrap_or_default()
+            )
+        })
+        .collect::<Vec<String>>();
+    match board.settings.wip_mode {
+        WipMode::Block if !violations.is_empty() => Err(AppError::Conflict(violations.join(", "))),
+        _ => Ok(violations),
+    }
+}
+
+pub fn validate_column(column: &BoardColumn) -> Result<()> {
+    if column.name.is_empty() {
+        return Err(
+            BoardRepoError::InvalidBoard(String::from("column name cannot be empty")).into(),
+        );
+    }
+    match &column.colour {
+        Some(colour) if !is_hex_colour(colour) => Err(BoardRepoError::InvalidBoard(format!(
+            "column colour {} is not a hex colour",
+            colour
+        ))
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+fn is_hex_colour(colour: &str) -> bool {
+    colour
+        .strip_prefix('#')
+        .map(|hex| (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .unwrap_or(false)
+}
+
+/// Puts the given columns in the given order, in the places they currently
+/// occupy. Columns that are not listed keep their place.
+pub fn reorder_columns(
+    columns: &[BoardColumn],
+    column_ids: &[uuid::Uuid],
+) -> Result<Vec<BoardColumn>> {
+    reorder_by_id(columns, column_ids, |c| c.id, "column")
+}
+
+/// Puts the items with the given ids in the given order, in the places they
+/// currently occupy. Items that are not listed keep their place.
+pub fn reorder_by_id<T: Clone>(
+    items: &[T],
+    ids: &[uuid::Uuid],
+    id_of: impl Fn(&T) -> uuid::Uuid,
+    kind: &str,
+) -> Result<Vec<T>> {
+    let invalid = |msg: String| -> AppError { BoardRepoError::InvalidBoard(msg).into() };
+    let mut slots = Vec::new();
+    for (i, id) in ids.iter().enumerate() {
+        if ids[..i].contains(id) {
+            return Err(invalid(format!("{} {} is listed twice", kind, id)));
+        }
+        match items.iter().position(|item| id_of(item) == *id) {
+            Some(slot) => slots.push(slot),
+            None => return Err(invalid(format!("unknown {} {}", kind, id))),
+        }
+    }
+    let mut reordered = items.to_vec();
+    let mut sorted_slots = slots.clone();
+    sorted_slots.sort_unstable();
+    for (slot, from) in sorted_slots.into_iter().zip(slots) {
+        reordered[slot] = items[from].clone();
+    }
+    Ok(reordered)
+}
+
+/// Value of `move_tasks_to` designating the tasks without column.
+pub const UNASSIGNED: &str = "unassigned";
+
+/// Resolves the column tasks are moved to, `None` being the unassigned tasks.
+pub fn get_move_target(target: &str, board: &Board) -> Result<Option<uuid::Uuid>> {
+    if target == UNASSIGNED {
+        return Ok(None);
+    }
+    uuid::Uuid::parse_str(target)
+        .ok()
+        .and_then(|column_id| board.column(column_id))
+        .map(|c| Some(c.id))
+        .ok_or_else(|| BoardRepoError::InvalidBoard(format!("unknown column {}", target)).into())
+}
+
+/// Parts of a board copied along with its columns and swimlanes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CloneOptions {
+    pub tasks: bool,
+    pub custom_fields: bool,
+    pub settings: bool,
+}
+
+/// Copies the layout of a board, and optionally its tasks, into the target
+/// board under new ids. Archived tasks are left behind, the others keep their
+/// order.
+pub fn clone_board(
+    source: &Board,
+    tasks: &[Task],
+    target: Board,
+    options: CloneOptions,
+) -> (Board, Vec<Task>) {
+    let now = Utc::now();
+    let mut ids: HashMap<uuid::Uuid, uuid::Uuid> = HashMap::new();
+    let mut renew = |id: uuid::Uuid| *ids.entry(id).or_insert_with(uuid::Uuid::new_v4);
+
+    let columns = source
+        .columns
+        .iter()
+        .map(|c| BoardColumn {
+            id: renew(c.id),
+            created_at: now,
+            updated_at: None,
+            ..c.clone()
+        })
+        .collect::<Vec<BoardColumn>>();
+    let custom_fields = match options.custom_fields {
+        true => source
+            .custom_fields
+            .iter()
+            .map(|f| CustomField {
+                id: renew(f.id),
+                created_at: now,
+                updated_at: None,
+                ..f.clone()
+            })
+            .collect(),
+        false => Vec::new(),
+    };
+    let swimlanes = source
+        .swimlanes
+        .iter()
+        .map(|l| Swimlane {
+            id: renew(l.id),
+            created_at: now,
+            updated_at: None,
+            ..l.clone()
+        })
+        .collect::<Vec<Swimlane>>();
+    let cloned = match options.tasks {
+        true => tasks
+            .iter()
+            .filter(|t| t.archived_at.is_none())
+            .map(|t| (renew(t.id), t))
+            .collect(),
+        false => Vec::new(),
+    };
+
+    let remap = |id: Option<uuid::Uuid>| id.and_then(|id| ids.get(&id).copied());
+    let settings = match options.settings {
+        true => BoardSettings {
+            wip_mode: source.settings.wip_mode,
+            started_column_id: remap(source.settings.started_column_id),
+            done_column_id: remap(source.settings.done_column_id),
+            swimlanes_by: match &source.settings.swimlanes_by {
+                Some(SwimlaneSource::CustomField { field_id }) => {
+                    remap(Some(*field_id)).map(|field_id| SwimlaneSource::CustomField { field_id })
+                }
+                Some(SwimlaneSource::Priority) if !options.custom_fields => None,
+                swimlanes_by => swimlanes_by.clone(),
+            },
+        },
+        false => BoardSettings::default(),
+    };
+    let tasks = cloned
+        .into_iter()
+        .map(|(id, t)| Task {
+            id,
+            column_id: remap(t.column_id),
+            swimlane_id: remap(t.swimlane_id),
+            above_task_id: remap(t.above_task_id),
+            parent_task_id: remap(t.parent_task_id),
+            blocked_by: t
+                .blocked_by
+                .iter()
+                .filter_map(|b| remap(Some(*b)))
+                .collect(),
+            custom_fields: t
+                .custom_fields
+                .iter()
+                .filter_map(|(field_id, value)| {
+                    let field_id = remap(uuid::Uuid::parse_str(field_id).ok())?;
+                    Some((field_id.to_string(), value.clone()))
+                })
+                .collect(),
+            sprint_id: None,
+            board_id: target.id,
+            created_at: now,
+            updated_at: None,
+            ..t.clone()
+        })
+        .collect();
+    (
+        Board {
+            columns,
+            custom_fields,
+            swimlanes,
+            settings,
+            ..target
+        },
+        tasks,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
-    use futures::SinkExt;
     use itertools::assert_equal;
+    use serde_json::json;
     use uuid::Uuid;
 
-    use crate::board::model::{Board, BoardColumn};
-    use crate::board::utils::merge_columns;
-    use crate::dto::CreateBoardColumnRequest;
+    use crate::board::model::{Board, BoardColumn, WipMode};
+    use crate::board::utils::{
+        check_wip_limits, clone_board, merge_columns, reorder_columns, CloneOptions,
+    };
+    use crate::dto::{UpdateBoardRequest, UpdateColumnRequest};
+    use crate::fixtures::{mock_board, mock_column, mock_task};
+    use crate::task::model::Task;
 
     #[test]
     fn it_maps_new_column_in_correct_order() {
-        let existing_columns: Vec<BoardColumn> = vec![
-            BoardColumn {
-                id: Uuid::new_v4(),
-                name: String::from("column-1"),
-                created_at: chrono::DateTime::<Utc>::default(),
-                updated_at: None,
-            },
-            BoardColumn {
-                id: Uuid::new_v4(),
-                name: String::from("column-2"),
-                created_at: chrono::DateTime::<Utc>::default(),
-                updated_at: None,
-            },
+        let existing_columns = vec![
+            mock_column("column-1", false),
+            mock_column("column-2", false),
         ];
-        let board = Board {
-            id: Uuid::new_v4(),
-            title: String::from("mock-board"),
-            description: None,
-            columns: existing_columns.clone(),
-            created_at: chrono::DateTime::<Utc>::default(),
-            updated_at: None,
-        };
-        let new_column = BoardColumn {
-            id: Uuid::new_v4(),
-            name: String::from("new-column"),
-            created_at: chrono::DateTime::<Utc>::default(),
-            updated_at: None,
-        };
+        let board = mock_board(existing_columns.clone());
+        let new_column = mock_column("new-column", false);
 
         let mut expected_front: Vec<BoardColumn> = Vec::new();
         expected_front.push(new_column.clone());
@@ -118,4 +348,165 @@ mod tests {
         let actual = merge_columns(&board.columns.clone(), new_column.clone(), Some(false));
         assert_equal(expected_back, actual);
     }
+
+    #[test]
+    fn it_checks_wip_limits_of_columns_tasks_enter() {
+        let todo = mock_column("todo", false);
+        let doing = BoardColumn {
+            wip_limit: Some(1),
+            ..mock_column("doing", false)
+        };
+        let mut board = mock_board(vec![todo.clone(), doing.clone()]);
+        let task = |column_id: Uuid| mock_task(board.id, Some(column_id));
+        let first = task(doing.id);
+        let second = task(todo.id);
+        let before = vec![first.clone(), second.clone()];
+        let moved = vec![
+            first.clone(),
+            Task {
+                column_id: Some(doing.id),
+                ..second.clone()
+            },
+        ];
+
+        assert!(check_wip_limits(&board, &[], &before).unwrap().is_empty());
+        assert_eq!(check_wip_limits(&board, &before, &moved).unwrap().len(), 1);
+        // leaving a column over its limit is always allowed
+        assert!(check_wip_limits(&board, &moved, &before)
+            .unwrap()
+            .is_empty());
+
+        board.settings.wip_mode = WipMode::Block;
+        assert!(check_wip_limits(&board, &before, &moved).is_err());
+        assert!(check_wip_limits(&board, &moved, &moved).is_ok());
+
+        // archived tasks are hidden from the board and don't take a slot
+        let archived_first = vec![
+            Task {
+                archived_at: Some(Utc::now()),
+                ..first.clone()
+            },
+            second.clone(),
+        ];
+        let moved_in = vec![
+            archived_first[0].clone(),
+            Task {
+                column_id: Some(doing.id),
+                ..second
+            },
+        ];
+        assert!(check_wip_limits(&board, &archived_first, &moved_in).is_ok());
+    }
+
+    #[test]
+    fn it_clears_column_fields_set_to_null() {
+        let column = BoardColumn {
+            colour: Some(String::from("#ff0000")),
+            description: Some(String::from("in progress")),
+            wip_limit: Some(3),
+            ..mock_column("doing", false)
+        };
+        let request = |json| serde_json::from_value::<UpdateColumnRequest>(json).unwrap();
+
+        let renamed = column
+            .clone()
+            .with_update_request(request(json!({ "name": "wip" })));
+        assert_eq!(renamed.name, "wip");
+        assert_eq!(renamed.colour, column.colour);
+        assert_eq!(renamed.wip_limit, Some(3));
+
+        let cleared = column.with_update_request(request(
+            json!({ "colour": null, "description": null, "wip_limit": null }),
+        ));
+        assert_eq!(cleared.name, "doing");
+        assert_eq!(cleared.colour, None);
+        assert_eq!(cleared.description, None);
+        assert_eq!(cleared.wip_limit, None);
+    }
+
+    #[test]
+    fn it_keeps_archived_columns_of_updated_boards() {
+        let archived = BoardColumn {
+            archived_at: Some(Utc::now()),
+            ..mock_column("old", false)
+        };
+        let stored = mock_board(vec![mock_column("todo", false), archived.clone()]);
+        // clients send back the board they read, without its archived columns
+        let read = serde_json::to_value(stored.clone().without_archived_columns()).unwrap();
+        let mut request = serde_json::from_value::<UpdateBoardRequest>(read).unwrap();
+        request.title = String::from("renamed");
+
+        let updated = Board::from_update_request(request, &stored);
+        assert_eq!(updated.title, "renamed");
+        assert_eq!(updated.columns.len(), 2);
+        assert_eq!(updated.columns[0].id, stored.columns[0].id);
+        assert_eq!(updated.columns[1], archived);
+    }
+
+    #[test]
+    fn it_reorders_only_listed_columns() {
+        let columns = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| mock_column(name, false))
+            .collect::<Vec<BoardColumn>>();
+        let names = |columns: Vec<BoardColumn>| {
+            columns
+                .into_iter()
+                .map(|c| c.name)
+                .collect::<Vec<String>>()
+                .join("")
+        };
+
+        let swapped = reorder_columns(&columns, &[columns[2].id, columns[0].id]).unwrap();
+        assert_eq!(names(swapped), "cbad");
+        let ids = columns.iter().rev().map(|c| c.id).collect::<Vec<Uuid>>();
+        assert_eq!(names(reorder_columns(&columns, &ids).unwrap()), "dcba");
+        assert!(reorder_columns(&columns, &[columns[0].id, columns[0].id]).is_err());
+        assert!(reorder_columns(&columns, &[Uuid::new_v4()]).is_err());
+    }
+
+    #[test]
+    fn it_clones_boards_under_new_ids() {
+        let column = mock_column("todo", false);
+        let source = mock_board(vec![column.clone()]);
+        let task = |above: Option<&Task>| Task {
+            above_task_id: above.map(|t| t.id),
+            sprint_id: Some(Uuid::new_v4()),
+            ..mock_task(source.id, Some(column.id))
+        };
+        let first = task(None);
+        let second = Task {
+            blocked_by: vec![first.id],
+            ..task(Some(&first))
+        };
+        let archived = Task {
+            archived_at: Some(Utc::now()),
+            ..task(None)
+        };
+        let tasks = vec![first.clone(), second.clone(), archived];
+        let target = mock_board(Vec::new());
+        let options = CloneOptions {
+            tasks: true,
+            ..Default::default()
+        };
+
+        let (cloned, cloned_tasks) = clone_board(&source, &tasks, target.clone(), options);
+        assert_eq!(cloned.id, target.id);
+        assert_eq!(cloned.columns.len(), 1);
+        assert_ne!(cloned.columns[0].id, column.id);
+        assert_eq!(cloned_tasks.len(), 2);
+        let (first, second) = (&cloned_tasks[0], &cloned_tasks[1]);
+        assert!(cloned_tasks
+            .iter()
+            .all(|t| t.id != tasks[0].id && t.id != tasks[1].id));
+        assert!(cloned_tasks
+            .iter()
+            .all(|t| t.board_id == target.id && t.sprint_id.is_none()));
+        assert_eq!(first.column_id, Some(cloned.columns[0].id));
+        assert_eq!(second.above_task_id, Some(first.id));
+        assert_eq!(second.blocked_by, vec![first.id]);
+
+        let (_, without_tasks) = clone_board(&source, &tasks, target, CloneOptions::default());
+        assert!(without_tasks.is_empty());
+    }
 }
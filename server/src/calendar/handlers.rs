@@ -0,0 +1,166 @@
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response as HttpResponse};
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::board::service as board_service;
+use crate::calendar::model::CalendarFeed;
+use crate::calendar::service;
+use crate::calendar::utils::to_icalendar;
+use crate::config::AppState;
+use crate::dto::Response;
+use crate::error::AppError;
+use crate::task::service as task_service;
+use crate::user::extractor::CurrentUser;
+
+const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+fn calendar_response(
+    result: Result<String, AppError>,
+    msg: impl FnOnce(&AppError) -> String,
+) -> HttpResponse {
+    match result {
+        Ok(calendar) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE)],
+            calendar,
+        )
+            .into_response(),
+        Err(e) => {
+            let msg = msg(&e);
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(Response::<()> {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+// Returns the due dates of the tasks of a board as an iCalendar feed
+#[axum_macros::debug_handler]
+pub async fn get_board_calendar_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting calendar of board {}", board_id);
+
+    async fn render(board_id: &String, state: AppState) -> Result<String, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let tasks = task_service::get_tasks(board_id, state.get_tasks_collection()).await?;
+        Ok(to_icalendar(
+            &board.title,
+            std::slice::from_ref(&board),
+            &tasks,
+            &state.public_url,
+        ))
+    }
+
+    calendar_response(render(&board_id, state).await, |e| {
+        format!(
+            "[get_board_calendar_handler] Error getting calendar of board {}: {:?}",
+            board_id,
+            e.to_string()
+        )
+    })
+}
+
+// Returns the due dates of the tasks assigned to the owner of a calendar token
+// as an iCalendar feed
+#[axum_macros::debug_handler]
+pub async fn get_user_calendar_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let token = path.0;
+    debug!("Getting calendar of a calendar token");
+
+    async fn render(token: &String, state: AppState) -> Result<String, AppError> {
+        let token = service::get_by_token(token, state.get_calendar_tokens_collection()).await?;
+        let boards = board_service::get_all_boards(state.get_boards_collection()).await?;
+        let tasks =
+            task_service::get_tasks_of_assignee(&token.user, state.get_tasks_collection()).await?;
+        Ok(to_icalendar(
+            &format!("Tasks of {}", token.user),
+            &boards,
+            &tasks,
+            &state.public_url,
+        ))
+    }
+
+    calendar_response(render(&token, state).await, |e| {
+        format!(
+            "[get_user_calendar_handler] Error getting calendar of a calendar token: {:?}",
+            e.to_string()
+        )
+    })
+}
+
+fn feed_response(
+    user: &String,
+    result: Result<CalendarFeed, AppError>,
+) -> (StatusCode, Json<Response<CalendarFeed>>) {
+    match result {
+        Ok(feed) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(feed),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[calendar_feed] Error getting calendar feed of {}: {:?}",
+                user,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Returns the URL of the calendar feed of the current user
+#[axum_macros::debug_handler]
+pub async fn get_calendar_feed_handler(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    debug!("Getting calendar feed of {}", user);
+    let result = service::get_or_create_token(&user, state.get_calendar_tokens_collection())
+        .await
+        .map(CalendarFeed::from);
+    feed_response(&user, result)
+}
+
+// Replaces the secret in the URL of the calendar feed of the current user,
+// e.g. after it leaked
+#[axum_macros::debug_handler]
+pub async fn calendar_feed_reset_handler(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    debug!("Resetting calendar feed of {}", user);
+    let result = service::reset_token(&user, state.get_calendar_tokens_collection())
+        .await
+        .map(CalendarFeed::from);
+    feed_response(&user, result)
+}
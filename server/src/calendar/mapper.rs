@@ -0,0 +1,22 @@
+use chrono::Utc;
+use mongodb::bson::doc;
+
+use crate::calendar::model::CalendarToken;
+use crate::error::Result;
+
+pub fn doc_to_token(doc: &bson::document::Document) -> Result<CalendarToken> {
+    let created_at = doc.get_datetime("created_at")?;
+    Ok(CalendarToken {
+        user: doc.get_str("user")?.to_owned(),
+        token: doc.get_str("token")?.to_owned(),
+        created_at: chrono::DateTime::from(*created_at),
+    })
+}
+
+pub fn token_to_doc(token: &CalendarToken) -> bson::document::Document {
+    doc! {
+        "user" : token.user.clone(),
+        "token" : token.token.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(token.created_at),
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Secret of the calendar feed of a user, part of its URL since calendar
+/// clients can't send headers.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CalendarToken {
+    pub user: String,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Calendar feed of the tasks assigned to a user.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CalendarFeed {
+    pub user: String,
+    /// Path of the feed on the API, e.g. `/api/calendar/<token>/calendar.ics`.
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CalendarToken {
+    pub fn new(user: &str) -> Self {
+        Self {
+            user: user.to_owned(),
+            token: format!(
+                "{}{}",
+                uuid::Uuid::new_v4().simple(),
+                uuid::Uuid::new_v4().simple()
+            ),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl From<CalendarToken> for CalendarFeed {
+    fn from(token: CalendarToken) -> Self {
+        Self {
+            user: token.user,
+            path: format!("/api/calendar/{}/calendar.ics", token.token),
+            created_at: token.created_at,
+        }
+    }
+}
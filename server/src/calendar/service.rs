@@ -0,0 +1,41 @@
+use bson::{doc, Document};
+use mongodb::options::ReplaceOptions;
+use mongodb::Collection;
+use tracing::debug;
+
+use crate::calendar::mapper::{doc_to_token, token_to_doc};
+use crate::calendar::model::CalendarToken;
+use crate::error::{AppError, CalendarRepoError, Result};
+
+/// Returns the calendar token of a user, creating it the first time.
+pub async fn get_or_create_token(
+    user: &String,
+    collection: Collection<Document>,
+) -> Result<CalendarToken> {
+    match collection.find_one(doc! { "user": user }, None).await? {
+        Some(doc) => doc_to_token(&doc),
+        None => reset_token(user, collection).await,
+    }
+}
+
+/// Replaces the calendar token of a user, the feed URL using the previous one
+/// stops working.
+pub async fn reset_token(user: &String, collection: Collection<Document>) -> Result<CalendarToken> {
+    debug!("[reset_token] Creating calendar token of {}", user);
+    let token = CalendarToken::new(user);
+    let options = ReplaceOptions::builder().upsert(true).build();
+    collection
+        .replace_one(doc! { "user": user }, token_to_doc(&token), options)
+        .await?;
+    Ok(token)
+}
+
+pub async fn get_by_token(
+    token: &String,
+    collection: Collection<Document>,
+) -> Result<CalendarToken> {
+    match collection.find_one(doc! { "token": token }, None).await? {
+        Some(doc) => doc_to_token(&doc),
+        None => Err(AppError::CalendarRepo(CalendarRepoError::NotFound)),
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::board::model::Board;
+use crate::reminder::utils::{due_date, due_date_field};
+use crate::task::model::Task;
+
+const PRODUCT_ID: &str = "-//rust-kanban-board//calendar//EN";
+/// Domain part of the UIDs of the entries, kept constant for clients to
+/// recognise entries across refreshes.
+const UID_DOMAIN: &str = "kanban-board";
+/// Longest content line allowed before folding, in octets.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Escapes a TEXT value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Folds a content line into lines of at most 75 octets, without splitting
+/// characters, each followed by CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn date_value(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn date_time_value(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn entries(task: &Task, board: &Board, due: NaiveDate, base_url: &str) -> Vec<String> {
+    let modified = date_time_value(task.updated_at.unwrap_or(task.created_at));
+    let link = format!("{}/boards/{}", base_url.trim_end_matches('/'), board.id);
+    let description = match &task.description {
+        Some(description) => format!("{}\n\n{}", description, link),
+        None => link.clone(),
+    };
+    let done = task
+        .column_id
+        .is_some_and(|c| board.done_column_ids().contains(&c));
+    let common = [
+        format!("DTSTAMP:{}", modified),
+        format!("LAST-MODIFIED:{}", modified),
+        format!("SUMMARY:{}", escape(&task.title)),
+        format!("DESCRIPTION:{}", escape(&description)),
+        format!("CATEGORIES:{}", escape(&board.title)),
+        format!("URL:{}", link),
+    ];
+    let mut lines = vec![
+        String::from("BEGIN:VEVENT"),
+        format!("UID:{}-due@{}", task.id, UID_DOMAIN),
+        format!("DTSTART;VALUE=DATE:{}", date_value(due)),
+        format!("DTEND;VALUE=DATE:{}", date_value(due + Duration::days(1))),
+        String::from("TRANSP:TRANSPARENT"),
+    ];
+    lines.extend(common.iter().cloned());
+    lines.push(String::from("END:VEVENT"));
+    lines.push(String::from("BEGIN:VTODO"));
+    lines.push(format!("UID:{}-todo@{}", task.id, UID_DOMAIN));
+    lines.push(format!("DUE;VALUE=DATE:{}", date_value(due)));
+    lines.push(match done {
+        true => String::from("STATUS:COMPLETED"),
+        false => String::from("STATUS:NEEDS-ACTION"),
+    });
+    lines.extend(common);
+    lines.push(String::from("END:VTODO"));
+    lines
+}
+
+/// iCalendar feed of the due dates of tasks, read from the due date field of
+/// their board. Each task with a due date gets an all-day VEVENT and a VTODO
+/// whose UIDs derive from its id, so that calendar clients update them.
+pub fn to_icalendar(name: &str, boards: &[Board], tasks: &[Task], base_url: &str) -> String {
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        format!("PRODID:{}", PRODUCT_ID),
+        String::from("CALSCALE:GREGORIAN"),
+        String::from("METHOD:PUBLISH"),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for task in tasks.iter().filter(|t| t.archived_at.is_none()) {
+        let Some(board) = boards
+            .iter()
+            .find(|b| b.id == task.board_id && b.archived_at.is_none())
+        else {
+            continue;
+        };
+        if let Some(due) = due_date_field(board).and_then(|f| due_date(task, f)) {
+            lines.extend(entries(task, board, due, base_url));
+        }
+    }
+    lines.push(String::from("END:VCALENDAR"));
+    lines.iter().map(|l| fold(l)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::board::model::Board;
+    use crate::calendar::utils::to_icalendar;
+    use crate::custom_field::model::{CustomFieldKind, CustomFieldValue};
+    use crate::fixtures::{mock_board, mock_column, mock_field, mock_task};
+    use crate::task::model::Task;
+
+    fn board_with_due_date() -> Board {
+        Board {
+            title: String::from("Web, mobile"),
+            custom_fields: vec![mock_field("Due", CustomFieldKind::Date, &[])],
+            ..mock_board(vec![
+                mock_column("mock-column", false),
+                mock_column("mock-column", true),
+            ])
+        }
+    }
+
+    fn done_task(board: &Board, title: &str, due: Option<&str>) -> Task {
+        Task {
+            title: String::from(title),
+            custom_fields: due
+                .map(|due| {
+                    [(
+                        board.custom_fields[0].id.to_string(),
+                        CustomFieldValue::Text(String::from(due)),
+                    )]
+                    .into()
+                })
+                .unwrap_or_default(),
+            created_at: Utc.with_ymd_and_hms(2023, 3, 1, 9, 30, 0).unwrap(),
+            ..mock_task(board.id, Some(board.columns[1].id))
+        }
+    }
+
+    #[test]
+    fn it_renders_task_due_dates_as_icalendar() {
+        let board = board_with_due_date();
+        let task = Task {
+            description: Some(String::from("Check the release notes;\nthen tag")),
+            ..done_task(&board, &"Release ".repeat(12), Some("2023-03-07"))
+        };
+        let tasks = vec![task.clone(), done_task(&board, "Someday", None)];
+
+        let calendar = to_icalendar(
+            "Web",
+            std::slice::from_ref(&board),
+            &tasks,
+            "http://localhost:3000",
+        );
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert!(calendar.lines().all(|l| l.len() <= 76));
+        let unfolded = calendar.replace("\r\n ", "");
+        assert_eq!(unfolded.matches("BEGIN:VEVENT").count(), 1);
+        assert_eq!(unfolded.matches("BEGIN:VTODO").count(), 1);
+        assert!(unfolded.contains(&format!("UID:{}-due@kanban-board\r\n", task.id)));
+        assert!(unfolded.contains(&format!("UID:{}-todo@kanban-board\r\n", task.id)));
+        assert!(unfolded.contains("DTSTART;VALUE=DATE:20230307\r\nDTEND;VALUE=DATE:20230308\r\n"));
+        assert!(unfolded.contains("DTSTAMP:20230301T093000Z\r\n"));
+        assert!(unfolded.contains("STATUS:COMPLETED\r\n"));
+        assert!(unfolded.contains("CATEGORIES:Web\\, mobile\r\n"));
+        assert!(unfolded.contains("DESCRIPTION:Check the release notes\\;\\nthen tag\\n\\nhttp"));
+        assert!(unfolded.contains(&format!("SUMMARY:{}\r\n", "Release ".repeat(12))));
+
+        // the UIDs stay the same when the task changes
+        let moved = Task {
+            custom_fields: done_task(&board, "", Some("2023-03-09")).custom_fields,
+            ..task.clone()
+        };
+        let calendar = to_icalendar("Web", &[board], &[moved], "http://localhost:3000");
+        assert!(calendar.contains(&format!("UID:{}-due@kanban-board\r\n", task.id)));
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20230309\r\n"));
+    }
+}
@@ -0,0 +1,162 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::board::service as board_service;
+use crate::chat::model::ChatWebhook;
+use crate::chat::service;
+use crate::chat::utils::validate_webhook;
+use crate::config::AppState;
+use crate::dto::{Response, SetChatWebhookRequest};
+use crate::error::{AppError, ChatRepoError};
+use crate::user::extractor::CurrentUser;
+
+// Returns the chat webhook of a board
+#[axum_macros::debug_handler]
+pub async fn get_chat_webhook_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting chat webhook of board {}", board_id);
+    match service::get_webhook(&board_id, state.get_chat_webhooks_collection()).await {
+        Ok(webhook) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(webhook),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_chat_webhook_handler] Error getting chat webhook of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Sets the chat webhook of a board and the events it posts
+#[axum_macros::debug_handler]
+pub async fn chat_webhook_set_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<SetChatWebhookRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[chat_webhook_set_handler] Setting chat webhook of board {}",
+        board_id
+    );
+
+    async fn validate_and_save(
+        board_id: &String,
+        req: SetChatWebhookRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<ChatWebhook, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let stored =
+            match service::get_webhook(board_id, state.get_chat_webhooks_collection()).await {
+                Ok(webhook) => Some(webhook),
+                Err(AppError::ChatRepo(ChatRepoError::NotFound)) => None,
+                Err(e) => return Err(e),
+            };
+        let webhook = ChatWebhook::from_request(board.id, req, stored.as_ref(), actor);
+        validate_webhook(&webhook)?;
+        service::save(&webhook, state.get_chat_webhooks_collection()).await?;
+        Ok(webhook)
+    }
+
+    match validate_and_save(&board_id, req, user.map(|u| u.0), state).await {
+        Ok(webhook) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(webhook),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[chat_webhook_set_handler] Error setting chat webhook of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Removes the chat webhook of a board, which stops posting messages
+#[axum_macros::debug_handler]
+pub async fn chat_webhook_delete_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[chat_webhook_delete_handler] Deleting chat webhook of board {}",
+        board_id
+    );
+
+    async fn delete(board_id: &String, state: AppState) -> Result<(), AppError> {
+        service::get_webhook(board_id, state.get_chat_webhooks_collection()).await?;
+        service::delete_of_board(board_id, state.get_chat_webhooks_collection()).await
+    }
+
+    match delete(&board_id, state).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(board_id),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[chat_webhook_delete_handler] Error deleting chat webhook of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
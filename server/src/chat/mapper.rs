@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use uuid::Uuid;
+
+use crate::chat::model::{ChatEvent, ChatWebhook};
+use crate::error::{AppError, ChatRepoError, Result};
+
+pub fn doc_to_webhook(doc: &bson::document::Document) -> Result<ChatWebhook> {
+    let board_id = doc.get_str("board_id")?;
+    let url = doc.get_str("url")?;
+    let created_at = doc.get_datetime("created_at")?;
+    let events = doc
+        .get_array("events")?
+        .iter()
+        .filter_map(|e| e.as_str().and_then(|e| ChatEvent::from_str(e).ok()))
+        .collect::<Vec<ChatEvent>>();
+    match Uuid::from_str(board_id) {
+        Ok(board_uuid) => Ok(ChatWebhook {
+            board_id: board_uuid,
+            url: url.to_owned(),
+            events,
+            enabled: doc.get_bool("enabled").unwrap_or(true),
+            created_by: doc.get_str("created_by").ok().map(|c| c.to_owned()),
+            created_at: chrono::DateTime::from(*created_at),
+            updated_at: doc
+                .get_datetime("updated_at")
+                .ok()
+                .map(|v| chrono::DateTime::from(*v)),
+        }),
+        Err(_) => Err(AppError::ChatRepo(ChatRepoError::DecodeError(format!(
+            "Chat webhook doesnt have a valid board_id {}",
+            board_id
+        )))),
+    }
+}
+
+pub fn webhook_to_doc(webhook: &ChatWebhook) -> bson::document::Document {
+    doc! {
+        "board_id" : webhook.board_id.to_string(),
+        "url" : webhook.url.clone(),
+        "events" : webhook.events.iter().map(|e| e.to_string()).collect::<Vec<String>>(),
+        "enabled" : webhook.enabled,
+        "created_by" : webhook.created_by.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(webhook.created_at),
+        "updated_at" : webhook.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    }
+}
@@ -0,0 +1,6 @@
+pub mod handlers;
+pub mod mapper;
+pub mod model;
+pub mod service;
+pub mod utils;
+pub mod webhook;
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+use crate::dto::SetChatWebhookRequest;
+
+/// Incoming webhook of a chat channel (Slack, Mattermost, ...) the events of a
+/// board are posted to, one per board.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ChatWebhook {
+    pub board_id: uuid::Uuid,
+    pub url: String,
+    /// Events that post a message.
+    pub events: Vec<ChatEvent>,
+    pub enabled: bool,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ChatEvent {
+    /// A task got created in a column.
+    TaskCreated,
+    /// A task entered a done column.
+    TaskDone,
+    /// A column went over its WIP limit.
+    WipExceeded,
+}
+
+/// Message in the incoming webhook format of Slack, which Mattermost accepts too.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ChatMessage {
+    pub text: String,
+}
+
+impl ChatWebhook {
+    /// Builds the webhook of a board from a request, all the events are posted
+    /// when the request doesn't choose them.
+    pub fn from_request(
+        board_id: uuid::Uuid,
+        r: SetChatWebhookRequest,
+        stored: Option<&ChatWebhook>,
+        actor: Option<String>,
+    ) -> Self {
+        Self {
+            board_id,
+            url: r.url.trim().to_owned(),
+            events: r.events.unwrap_or_else(|| {
+                vec![
+                    ChatEvent::TaskCreated,
+                    ChatEvent::TaskDone,
+                    ChatEvent::WipExceeded,
+                ]
+            }),
+            enabled: r.enabled.unwrap_or(true),
+            created_by: stored.map_or(actor, |s| s.created_by.clone()),
+            created_at: stored.map_or_else(Utc::now, |s| s.created_at),
+            updated_at: stored.map(|_| Utc::now()),
+        }
+    }
+}
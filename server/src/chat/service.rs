@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use bson::{doc, Document};
+use mongodb::options::ReplaceOptions;
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::board::service as board_service;
+use crate::chat::mapper::{doc_to_webhook, webhook_to_doc};
+use crate::chat::model::{ChatMessage, ChatWebhook};
+use crate::chat::utils::chat_messages;
+use crate::chat::webhook;
+use crate::config::AppState;
+use crate::error::{AppError, ChatRepoError, Result};
+use crate::task::model::Task;
+use crate::task::service as task_service;
+
+pub async fn get_webhook(
+    board_id: &String,
+    collection: Collection<Document>,
+) -> Result<ChatWebhook> {
+    match collection
+        .find_one(doc! { "board_id": board_id }, None)
+        .await?
+    {
+        Some(doc) => doc_to_webhook(&doc),
+        None => Err(AppError::ChatRepo(ChatRepoError::NotFound)),
+    }
+}
+
+pub async fn save(webhook: &ChatWebhook, collection: Collection<Document>) -> Result<&ChatWebhook> {
+    debug!(
+        "[save_webhook] Saving chat webhook of board {}",
+        webhook.board_id
+    );
+    let options = ReplaceOptions::builder().upsert(true).build();
+    collection
+        .replace_one(
+            doc! { "board_id": webhook.board_id.to_string() },
+            webhook_to_doc(webhook),
+            options,
+        )
+        .await?;
+    Ok(webhook)
+}
+
+/// Deletes the chat webhook of a board, if it has one.
+pub async fn delete_of_board(board_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!(
+        "[delete_of_board] Deleting chat webhook of board {}",
+        board_id
+    );
+    collection
+        .delete_many(doc! { "board_id": board_id }, None)
+        .await?;
+    Ok(())
+}
+
+/// Posts messages in the background, logging the ones that couldn't be posted.
+fn post_all(url: String, messages: Vec<ChatMessage>) {
+    if messages.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        for message in messages.iter() {
+            if let Err(e) = webhook::post(&client, &url, message).await {
+                error!("ERROR [post_all] Couldn't post chat message: {:?}", e);
+            }
+        }
+    });
+}
+
+async fn announce_on_board(
+    board_id: &String,
+    stored: &[Task],
+    changed: &[Task],
+    actor: Option<&str>,
+    state: &AppState,
+) -> Result<()> {
+    let webhook = match get_webhook(board_id, state.get_chat_webhooks_collection()).await {
+        Ok(webhook) if webhook.enabled => webhook,
+        Ok(_) | Err(AppError::ChatRepo(ChatRepoError::NotFound)) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+    let board_tasks = task_service::get_tasks(board_id, state.get_tasks_collection()).await?;
+    let messages = chat_messages(
+        &webhook,
+        &board,
+        stored,
+        changed,
+        &board_tasks,
+        actor,
+        &state.public_url,
+    );
+    post_all(webhook.url, messages);
+    Ok(())
+}
+
+/// Posts the events of changed tasks to the chat webhooks of their boards.
+/// `stored` holds the tasks as they were before, created tasks are missing
+/// from it. Failures are logged rather than failing the change.
+pub async fn announce(stored: &[Task], changed: &[Task], actor: Option<&str>, state: &AppState) {
+    let board_ids = changed
+        .iter()
+        .map(|t| t.board_id.to_string())
+        .collect::<HashSet<String>>();
+    for board_id in board_ids {
+        if let Err(e) = announce_on_board(&board_id, stored, changed, actor, state).await {
+            error!(
+                "ERROR [announce] Couldn't announce changes of board {}: {:?}",
+                board_id, e
+            );
+        }
+    }
+}
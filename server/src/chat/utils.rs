@@ -0,0 +1,256 @@
+use crate::board::model::Board;
+use crate::board::utils::columns_over_wip_limit;
+use crate::chat::model::{ChatEvent, ChatMessage, ChatWebhook};
+use crate::error::{ChatRepoError, Result};
+use crate::task::model::Task;
+
+pub fn validate_webhook(webhook: &ChatWebhook) -> Result<()> {
+    match reqwest::Url::parse(&webhook.url) {
+        Ok(url) if ["http", "https"].contains(&url.scheme()) => {}
+        _ => {
+            return Err(ChatRepoError::InvalidWebhook(format!(
+                "{} is not an http(s) URL",
+                webhook.url
+            ))
+            .into())
+        }
+    }
+    if webhook.events.is_empty() {
+        return Err(ChatRepoError::InvalidWebhook(String::from(
+            "at least one event has to be chosen",
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Escapes the characters with a meaning in chat messages.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn board_link(board: &Board, base_url: &str) -> String {
+    format!(
+        "<{}/boards/{}|{}>",
+        base_url.trim_end_matches('/'),
+        board.id,
+        escape(&board.title)
+    )
+}
+
+fn column_name(board: &Board, column_id: Option<uuid::Uuid>) -> Option<String> {
+    board
+        .columns
+        .iter()
+        .find(|c| Some(c.id) == column_id)
+        .map(|c| escape(&c.name))
+}
+
+fn by_actor(actor: Option<&str>) -> String {
+    actor
+        .map(|a| format!(" by {}", escape(a)))
+        .unwrap_or_default()
+}
+
+/// Messages announcing a change of the tasks of a board to its chat channel,
+/// for the events the webhook posts. `stored` holds the changed tasks as they
+/// were before, created tasks are missing from it, and `board_tasks` all the
+/// tasks of the board after the change.
+pub fn chat_messages(
+    webhook: &ChatWebhook,
+    board: &Board,
+    stored: &[Task],
+    changed: &[Task],
+    board_tasks: &[Task],
+    actor: Option<&str>,
+    base_url: &str,
+) -> Vec<ChatMessage> {
+    let posts = |event: ChatEvent| webhook.enabled && webhook.events.contains(&event);
+    let done_columns = board.done_column_ids();
+    let is_done = |task: &Task| task.column_id.is_some_and(|c| done_columns.contains(&c));
+    let link = board_link(board, base_url);
+    let mut messages = Vec::new();
+    for task in changed.iter().filter(|t| t.board_id == board.id) {
+        let before = stored.iter().find(|s| s.id == task.id);
+        let text = match (before, column_name(board, task.column_id)) {
+            (None, Some(column)) if posts(ChatEvent::TaskCreated) => format!(
+                "*{}* was created in *{}* on {}{}",
+                escape(&task.title),
+                column,
+                link,
+                by_actor(actor)
+            ),
+            (Some(before), Some(column))
+                if posts(ChatEvent::TaskDone) && is_done(task) && !is_done(before) =>
+            {
+                format!(
+                    ":white_check_mark: *{}* was moved to *{}* on {}{}",
+                    escape(&task.title),
+                    column,
+                    link,
+                    by_actor(actor)
+                )
+            }
+            _ => continue,
+        };
+        messages.push(ChatMessage { text });
+    }
+    if posts(ChatEvent::WipExceeded) {
+        let before = board_tasks
+            .iter()
+            .filter_map(|t| match changed.iter().any(|c| c.id == t.id) {
+                true => stored.iter().find(|s| s.id == t.id),
+                false => Some(t),
+            })
+            .cloned()
+            .collect::<Vec<Task>>();
+        for (column, count) in columns_over_wip_limit(board, &before, board_tasks) {
+            messages.push(ChatMessage {
+                text: format!(
+                    ":warning: *{}* is over its WIP limit ({}/{}) on {}",
+                    escape(&column.name),
+                    count,
+                    column.wip_limit.unwrap_or_default(),
+                    link
+                ),
+            });
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use crate::board::model::{Board, BoardColumn};
+    use crate::chat::model::{ChatEvent, ChatWebhook};
+    use crate::chat::utils::{chat_messages, validate_webhook};
+    use crate::fixtures::{mock_board, mock_column, mock_task};
+    use crate::task::model::Task;
+
+    fn board_with_wip_limit() -> Board {
+        Board {
+            title: String::from("R&D"),
+            ..mock_board(vec![
+                mock_column("To do", false),
+                BoardColumn {
+                    wip_limit: Some(1),
+                    ..mock_column("Doing", false)
+                },
+                mock_column("Done", true),
+            ])
+        }
+    }
+
+    fn titled_task(board: &Board, title: &str, column: usize) -> Task {
+        Task {
+            title: String::from(title),
+            ..mock_task(board.id, Some(board.columns[column].id))
+        }
+    }
+
+    #[test]
+    fn it_announces_the_events_a_webhook_posts() {
+        let board = board_with_wip_limit();
+        let mut webhook = ChatWebhook {
+            board_id: board.id,
+            url: String::from("http://localhost:8065/hooks/abc"),
+            events: vec![
+                ChatEvent::TaskCreated,
+                ChatEvent::TaskDone,
+                ChatEvent::WipExceeded,
+            ],
+            enabled: true,
+            created_by: None,
+            created_at: DateTime::<Utc>::default(),
+            updated_at: None,
+        };
+        assert!(validate_webhook(&webhook).is_ok());
+        let base_url = "http://localhost:3000/";
+
+        let created = titled_task(&board, "Write <docs>", 0);
+        let messages = chat_messages(
+            &webhook,
+            &board,
+            &[],
+            std::slice::from_ref(&created),
+            std::slice::from_ref(&created),
+            Some("alice"),
+            base_url,
+        );
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0].text,
+            format!(
+                "*Write &lt;docs&gt;* was created in *To do* on <http://localhost:3000/boards/{}|R&amp;D> by alice",
+                board.id
+            )
+        );
+
+        let doing = titled_task(&board, "Review", 1);
+        let stored = vec![created.clone(), titled_task(&board, "Release", 1)];
+        let changed = vec![
+            Task {
+                column_id: Some(board.columns[2].id),
+                ..stored[0].clone()
+            },
+            Task {
+                column_id: Some(board.columns[2].id),
+                ..stored[1].clone()
+            },
+        ];
+        let mut board_tasks = changed.clone();
+        board_tasks.push(doing.clone());
+        let messages = chat_messages(
+            &webhook,
+            &board,
+            &stored,
+            &changed,
+            &board_tasks,
+            None,
+            base_url,
+        );
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0]
+            .text
+            .starts_with(":white_check_mark: *Write &lt;docs&gt;* was moved to *Done*"));
+
+        // moving a second task into the column with a WIP limit of 1
+        let stored = vec![created.clone()];
+        let changed = vec![Task {
+            column_id: Some(board.columns[1].id),
+            ..created.clone()
+        }];
+        let board_tasks = vec![changed[0].clone(), doing];
+        let messages = chat_messages(
+            &webhook,
+            &board,
+            &stored,
+            &changed,
+            &board_tasks,
+            None,
+            base_url,
+        );
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0]
+            .text
+            .starts_with(":warning: *Doing* is over its WIP limit (2/1)"));
+
+        webhook.events = vec![ChatEvent::TaskDone];
+        let messages = chat_messages(
+            &webhook,
+            &board,
+            &stored,
+            &changed,
+            &board_tasks,
+            None,
+            base_url,
+        );
+        assert!(messages.is_empty());
+        webhook.url = String::from("ftp://localhost/hooks");
+        assert!(validate_webhook(&webhook).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use reqwest::header::CONTENT_TYPE;
+
+use crate::chat::model::ChatMessage;
+use crate::error::{ChatRepoError, Result};
+
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn post_error(e: impl ToString) -> ChatRepoError {
+    ChatRepoError::Post(e.to_string())
+}
+
+/// Posts a message to an incoming webhook of a chat channel.
+pub async fn post(client: &reqwest::Client, url: &str, message: &ChatMessage) -> Result<()> {
+    let body = serde_json::to_vec(message).map_err(post_error)?;
+    let response = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .timeout(POST_TIMEOUT)
+        .body(body)
+        .send()
+        .await
+        .map_err(post_error)?;
+    if !response.status().is_success() {
+        return Err(post_error(format!("{} answered {}", url, response.status())).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    use crate::chat::model::ChatMessage;
+    use crate::chat::webhook;
+
+    type Received = Arc<Mutex<Vec<ChatMessage>>>;
+
+    /// Incoming webhook keeping the messages posted to it in memory.
+    async fn start_receiver() -> (SocketAddr, Received) {
+        let received: Received = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route(
+                "/hooks/board",
+                post(
+                    |State(received): State<Received>, Json(message): Json<ChatMessage>| async move {
+                        received.lock().unwrap().push(message);
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        (addr, received)
+    }
+
+    #[tokio::test]
+    async fn it_posts_messages_to_webhooks() {
+        let (addr, received) = start_receiver().await;
+        let client = reqwest::Client::new();
+        let message = ChatMessage {
+            text: String::from("*Release* was moved to *Done*"),
+        };
+
+        webhook::post(&client, &format!("http://{}/hooks/board", addr), &message)
+            .await
+            .unwrap();
+        assert_eq!(*received.lock().unwrap(), vec![message.clone()]);
+        assert!(
+            webhook::post(&client, &format!("http://{}/hooks/other", addr), &message)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::comment::model::Comment;
+use crate::comment::service;
+use crate::config::AppState;
+use crate::dto::{CommentRequest, Response};
+use crate::error::AppError;
+use crate::notification::service as notification_service;
+use crate::notification::utils::comment_notifications;
+use crate::task::service as task_service;
+use crate::user::extractor::CurrentUser;
+
+// Returns all comments of a task, oldest first
+#[axum_macros::debug_handler]
+pub async fn get_comments_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!("Getting comments of task {}", task_id);
+    match service::get_comments(&task_id, state.get_comments_collection()).await {
+        Ok(comments) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(comments),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_comments_handler] Error getting comments of task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Creates a new comment on a task
+#[axum_macros::debug_handler]
+pub async fn comment_create_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    CurrentUser(author): CurrentUser,
+    Json(req): Json<CommentRequest>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!(
+        "[comment_create_handler] Creating comment on task {} by {}",
+        task_id, author
+    );
+
+    async fn create(
+        task_id: &String,
+        author: String,
+        body: String,
+        state: AppState,
+    ) -> Result<Comment, AppError> {
+        let task = task_service::get_task(task_id, state.get_tasks_collection()).await?;
+        let comment = Comment::new(&task, author, body);
+        service::create(&comment, state.get_comments_collection()).await?;
+        notification_service::notify(
+            comment_notifications(None, &comment, &task),
+            state.get_notifications_collection(),
+        )
+        .await;
+        Ok(comment)
+    }
+
+    match create(&task_id, author, req.body, state).await {
+        Ok(comment) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(comment),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[comment_create_handler] Error creating comment on task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Edits the body of a comment, only allowed to its author
+#[axum_macros::debug_handler]
+pub async fn comment_update_handler(
+    Path((task_id, comment_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<CommentRequest>,
+) -> impl IntoResponse {
+    debug!(
+        "[comment_update_handler] Updating comment {} of task {}",
+        comment_id, task_id
+    );
+
+    async fn update(
+        task_id: &String,
+        comment_id: &String,
+        user: String,
+        body: String,
+        state: AppState,
+    ) -> Result<Comment, AppError> {
+        let comment =
+            service::get_comment(task_id, comment_id, state.get_comments_collection()).await?;
+        if comment.author != user {
+            return Err(AppError::Forbidden(String::from(
+                "only the author can edit a comment",
+            )));
+        }
+        let task = task_service::get_task(task_id, state.get_tasks_collection()).await?;
+        let updated = comment.clone().with_body(body);
+        service::update(&updated, state.get_comments_collection()).await?;
+        notification_service::notify(
+            comment_notifications(Some(&comment), &updated, &task),
+            state.get_notifications_collection(),
+        )
+        .await;
+        Ok(updated)
+    }
+
+    match update(&task_id, &comment_id, user, req.body, state).await {
+        Ok(comment) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(comment),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[comment_update_handler] Error updating comment {}: {:?}",
+                comment_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes a comment, only allowed to its author
+#[axum_macros::debug_handler]
+pub async fn comment_delete_handler(
+    Path((task_id, comment_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    debug!(
+        "[comment_delete_handler] Deleting comment {} of task {}",
+        comment_id, task_id
+    );
+
+    async fn delete(
+        task_id: &String,
+        comment_id: &String,
+        user: String,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        let comment =
+            service::get_comment(task_id, comment_id, state.get_comments_collection()).await?;
+        if comment.author != user {
+            return Err(AppError::Forbidden(String::from(
+                "only the author can delete a comment",
+            )));
+        }
+        service::delete(comment_id, state.get_comments_collection()).await
+    }
+
+    match delete(&task_id, &comment_id, user, state).await {
+        Ok(_) => {
+            let msg = format!("Deleted comment with id {}", comment_id);
+            debug!("[comment_delete_handler] {}", msg);
+            (
+                StatusCode::OK,
+                Json(Response {
+                    success: true,
+                    data: Some(msg),
+                    error_message: None,
+                    warnings: None,
+                }),
+            )
+        }
+        Err(e) => {
+            let msg = format!("Error in deleting comment {}: {}", comment_id, e);
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::comment::model::Comment;
+use crate::error::{AppError, CommentRepoError, Result};
+use crate::util::get_string_list;
+
+pub async fn parse_comments(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<Comment>> {
+    let mut result: Vec<Comment> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_comment(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_comment(doc: &bson::document::Document) -> Result<Comment> {
+    let id = doc.get_str("id")?;
+    let task_id = doc.get_str("task_id")?;
+    let board_id = doc.get_str("board_id")?;
+    let author = doc.get_str("author")?;
+    let body = doc.get_str("body")?;
+    let body_html = doc.get_str("body_html")?;
+    let mentions = get_string_list(doc, "mentions");
+    let created_at = doc.get_datetime("created_at")?;
+    let edited_at = doc
+        .get_datetime("edited_at")
+        .ok()
+        .map(|v| chrono::DateTime::from(*v));
+    match (
+        Uuid::from_str(id),
+        Uuid::from_str(task_id),
+        Uuid::from_str(board_id),
+    ) {
+        (Ok(comment_uuid), Ok(task_uuid), Ok(board_uuid)) => Ok(Comment {
+            id: comment_uuid,
+            task_id: task_uuid,
+            board_id: board_uuid,
+            author: author.to_owned(),
+            body: body.to_owned(),
+            body_html: body_html.to_owned(),
+            mentions,
+            created_at: chrono::DateTime::from(*created_at),
+            edited_at,
+        }),
+        _ => Err(AppError::CommentRepo(CommentRepoError::DecodeError(
+            format!("Comment doesnt have id, task_id or board_id {}", id),
+        ))),
+    }
+}
+
+pub fn comment_to_doc(comment: &Comment) -> bson::document::Document {
+    doc! {
+        "id" : comment.id.to_string(),
+        "task_id" : comment.task_id.to_string(),
+        "board_id" : comment.board_id.to_string(),
+        "author" : comment.author.clone(),
+        "body" : comment.body.clone(),
+        "body_html" : comment.body_html.clone(),
+        "mentions" : comment.mentions.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(comment.created_at),
+        "edited_at" : comment.edited_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into)
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::comment::utils::render_markdown;
+use crate::notification::utils::parse_mentions;
+use crate::task::model::Task;
+
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Comment {
+    pub id: uuid::Uuid,
+    pub task_id: uuid::Uuid,
+    pub board_id: uuid::Uuid,
+    pub author: String,
+    /// Markdown source as written by the author.
+    pub body: String,
+    /// Sanitized HTML rendered from `body`.
+    pub body_html: String,
+    pub mentions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    pub fn new(task: &Task, author: String, body: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            task_id: task.id,
+            board_id: task.board_id,
+            author,
+            body_html: render_markdown(&body),
+            mentions: parse_mentions(&body),
+            body,
+            created_at: Utc::now(),
+            edited_at: None,
+        }
+    }
+
+    pub fn with_body(self, body: String) -> Self {
+        Self {
+            body_html: render_markdown(&body),
+            mentions: parse_mentions(&body),
+            body,
+            edited_at: Some(Utc::now()),
+            ..self
+        }
+    }
+}
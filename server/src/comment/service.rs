@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use bson::{doc, Document};
+use mongodb::Collection;
+use tokio_stream::StreamExt;
+use tracing::{debug, error};
+
+use crate::comment::mapper::{comment_to_doc, doc_to_comment, parse_comments};
+use crate::comment::model::Comment;
+use crate::error::{AppError, CommentRepoError, Result};
+
+pub async fn get_comments(
+    task_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Comment>> {
+    let filter = doc! { "task_id": task_id };
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .build();
+    let cursor = collection.find(filter, options).await.map_err(|_e| {
+        debug!("ERROR [get_comments] {:?}", _e);
+        CommentRepoError::NotFound
+    })?;
+    parse_comments(cursor).await
+}
+
+pub async fn get_comments_of_tasks(
+    task_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<Vec<Comment>> {
+    let ids = task_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    let filter = doc! { "task_id": { "$in": ids } };
+    let cursor = collection.find(filter, None).await?;
+    parse_comments(cursor).await
+}
+
+pub async fn get_comment(
+    task_id: &String,
+    comment_id: &String,
+    collection: Collection<Document>,
+) -> Result<Comment> {
+    let filter = doc! { "id": comment_id, "task_id": task_id };
+    let comment_opt = collection
+        .find_one(filter, None)
+        .await
+        .map_err(|e| {
+            debug!("Error while getting comment with id {}: {}", comment_id, e);
+            AppError::MongoError(e)
+        })?
+        .and_then(|doc| doc_to_comment(&doc).ok());
+
+    match comment_opt {
+        Some(comment) => Ok(comment),
+        None => Err(AppError::CommentRepo(CommentRepoError::NotFound)),
+    }
+}
+
+pub async fn create(comment: &Comment, collection: Collection<Document>) -> Result<&Comment> {
+    debug!("[create_comment] Creating comment with id={}", &comment.id);
+    collection
+        .insert_one(comment_to_doc(comment), None)
+        .await
+        .map_err(|_e| {
+            error!("ERROR [create_comment] {:?}", _e);
+            CommentRepoError::InvalidComment(_e.to_string())
+        })?;
+    Ok(comment)
+}
+
+pub async fn update(comment: &Comment, collection: Collection<Document>) -> Result<&Comment> {
+    debug!("[update_comment] Updating comment with id={}", &comment.id);
+    let filter = doc! { "id": comment.id.to_string() };
+    let updates = doc! { "$set": comment_to_doc(comment) };
+    collection.update_one(filter, updates, None).await?;
+    Ok(comment)
+}
+
+pub async fn delete(comment_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!("[delete_comment] Deleting comment with id={}", comment_id);
+    let filter = doc! { "id": comment_id };
+    collection.delete_one(filter, None).await?;
+    Ok(())
+}
+
+/// Returns the number of comments of each of the given tasks, tasks without comments are omitted.
+pub async fn count_by_tasks(
+    task_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<HashMap<uuid::Uuid, usize>> {
+    let ids = task_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    let pipeline = vec![
+        doc! { "$match": { "task_id": { "$in": ids } } },
+        doc! { "$group": { "_id": "$task_id", "count": { "$sum": 1 } } },
+    ];
+    let mut cursor = collection.aggregate(pipeline, None).await?;
+    let mut result = HashMap::new();
+    while let Some(doc) = cursor.next().await {
+        let doc = doc?;
+        if let Ok(task_id) = uuid::Uuid::from_str(doc.get_str("_id")?) {
+            result.insert(task_id, doc.get_i32("count")? as usize);
+        }
+    }
+    Ok(result)
+}
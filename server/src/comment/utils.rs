@@ -0,0 +1,25 @@
+use pulldown_cmark::{html, Options, Parser};
+
+/// Renders the Markdown of a comment to HTML, stripping anything that could run scripts
+/// or alter the page (raw `<script>`, event handlers, `javascript:` links...).
+pub fn render_markdown(body: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(body, options));
+    ammonia::clean(&unsafe_html)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::comment::utils::render_markdown;
+
+    #[test]
+    fn it_renders_sanitized_markdown() {
+        let html =
+            render_markdown("**bold** [link](javascript:alert(1)) <script>alert(1)</script>");
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(!html.contains("script"));
+        assert!(!html.contains("javascript:"));
+    }
+}
@@ -1,18 +1,46 @@
+use std::sync::Arc;
+
 use bson::Document;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use mongodb::{Client, Collection, Database};
 
+use crate::attachment::store::BlobStore;
+
 /// Simple key/value store with an HTTP API
 #[derive(Debug, Parser)]
 pub struct Config {
     /// The port to listen on
     #[clap(short = 'p', long, default_value = "8080")]
     pub port: u16,
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Maintenance commands, the server is not started when one is given
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Reports the tasks whose board or column doesn't exist anymore
+    Orphans {
+        /// Deletes the tasks of missing boards and unassigns the tasks of missing columns
+        #[clap(long)]
+        fix: bool,
+    },
 }
 
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub client: Client,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub attachment_limits: AttachmentLimits,
+    /// URL the web client is served at, linked from emails and chat messages.
+    pub public_url: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct AttachmentLimits {
+    pub max_bytes: u64,
+    /// Accepted content types, `type/*` accepts all the subtypes.
+    pub allowed_types: Vec<String>,
 }
 
 const DB_NAME: &str = "rust-kanban-board-local";
@@ -29,4 +57,60 @@ impl AppState {
     pub fn get_boards_collection(&self) -> Collection<Document> {
         self.get_database().collection("boards")
     }
+
+    pub fn get_comments_collection(&self) -> Collection<Document> {
+        self.get_database().collection("comments")
+    }
+
+    pub fn get_notifications_collection(&self) -> Collection<Document> {
+        self.get_database().collection("notifications")
+    }
+
+    pub fn get_attachments_collection(&self) -> Collection<Document> {
+        self.get_database().collection("attachments")
+    }
+
+    pub fn get_sprints_collection(&self) -> Collection<Document> {
+        self.get_database().collection("sprints")
+    }
+
+    pub fn get_templates_collection(&self) -> Collection<Document> {
+        self.get_database().collection("board_templates")
+    }
+
+    pub fn get_task_templates_collection(&self) -> Collection<Document> {
+        self.get_database().collection("task_templates")
+    }
+
+    pub fn get_automations_collection(&self) -> Collection<Document> {
+        self.get_database().collection("automation_rules")
+    }
+
+    pub fn get_automation_runs_collection(&self) -> Collection<Document> {
+        self.get_database().collection("automation_runs")
+    }
+
+    pub fn get_reminder_preferences_collection(&self) -> Collection<Document> {
+        self.get_database().collection("reminder_preferences")
+    }
+
+    pub fn get_chat_webhooks_collection(&self) -> Collection<Document> {
+        self.get_database().collection("chat_webhooks")
+    }
+
+    pub fn get_calendar_tokens_collection(&self) -> Collection<Document> {
+        self.get_database().collection("calendar_tokens")
+    }
+
+    pub fn get_trash_collection(&self) -> Collection<Document> {
+        self.get_database().collection("trash")
+    }
+
+    pub fn get_activity_collection(&self) -> Collection<Document> {
+        self.get_database().collection("activity")
+    }
+
+    pub fn get_transitions_collection(&self) -> Collection<Document> {
+        self.get_database().collection("transitions")
+    }
 }
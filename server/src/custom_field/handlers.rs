@@ -0,0 +1,298 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::activity::service as activity_service;
+use crate::activity::utils::{board_activities, task_activities};
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::custom_field::model::CustomField;
+use crate::custom_field::utils::validate_field;
+use crate::dto::{CustomFieldRequest, Response, UpdateCustomFieldRequest};
+use crate::error::{AppError, BoardRepoError};
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::user::extractor::CurrentUser;
+
+// Returns the custom fields defined on a board
+#[axum_macros::debug_handler]
+pub async fn get_custom_fields_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting custom fields of board {}", board_id);
+    match board_service::get_board(&board_id, state.get_boards_collection()).await {
+        Ok(board) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(board.custom_fields),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_custom_fields_handler] Error getting custom fields of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::NOT_FOUND,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Defines a new custom field on a board
+#[axum_macros::debug_handler]
+pub async fn custom_field_create_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CustomFieldRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[custom_field_create_handler] Creating custom field {} on board {}",
+        req.name, board_id
+    );
+    let field = CustomField::from_request(req);
+
+    async fn validate_and_create(
+        board_id: &String,
+        field: &CustomField,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        validate_field(field, &board.custom_fields)?;
+        let mut custom_fields = board.custom_fields.clone();
+        custom_fields.push(field.clone());
+        let updated = board_service::update(
+            &board.clone().with_custom_fields(custom_fields),
+            state.get_boards_collection(),
+        )
+        .await?;
+        activity_service::record(
+            board_activities(Some(&board), Some(&updated), actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        Ok(())
+    }
+
+    match validate_and_create(&board_id, &field, user.map(|u| u.0), state).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(field),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[custom_field_create_handler] Error creating custom field {} on board {}: {:?}",
+                field.name,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Renames a custom field or changes its options
+#[axum_macros::debug_handler]
+pub async fn custom_field_update_handler(
+    Path((board_id, field_id)): Path<(String, uuid::Uuid)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<UpdateCustomFieldRequest>,
+) -> impl IntoResponse {
+    debug!(
+        "[custom_field_update_handler] Updating custom field {} on board {}",
+        field_id, board_id
+    );
+
+    async fn validate_and_update(
+        board_id: &String,
+        field_id: uuid::Uuid,
+        req: UpdateCustomFieldRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<CustomField, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let field = board
+            .custom_fields
+            .iter()
+            .find(|f| f.id == field_id)
+            .cloned()
+            .ok_or_else(|| {
+                BoardRepoError::InvalidBoard(format!("unknown custom field {}", field_id))
+            })?
+            .with_update_request(req);
+        validate_field(&field, &board.custom_fields)?;
+        let custom_fields = board
+            .custom_fields
+            .iter()
+            .map(|f| match f.id == field_id {
+                true => field.clone(),
+                false => f.clone(),
+            })
+            .collect();
+        let updated = board_service::update(
+            &board.clone().with_custom_fields(custom_fields),
+            state.get_boards_collection(),
+        )
+        .await?;
+        activity_service::record(
+            board_activities(Some(&board), Some(&updated), actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        Ok(field)
+    }
+
+    match validate_and_update(&board_id, field_id, req, user.map(|u| u.0), state).await {
+        Ok(field) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(field),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[custom_field_update_handler] Error updating custom field {} on board {}: {:?}",
+                field_id,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes a custom field along with its values on the tasks of the board
+#[axum_macros::debug_handler]
+pub async fn custom_field_delete_handler(
+    Path((board_id, field_id)): Path<(String, uuid::Uuid)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    debug!(
+        "[custom_field_delete_handler] Deleting custom field {} on board {}",
+        field_id, board_id
+    );
+
+    async fn delete(
+        board_id: &String,
+        field_id: uuid::Uuid,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let custom_fields = board
+            .custom_fields
+            .iter()
+            .filter(|f| f.id != field_id)
+            .cloned()
+            .collect::<Vec<CustomField>>();
+        if custom_fields.len() == board.custom_fields.len() {
+            return Err(
+                BoardRepoError::InvalidBoard(format!("unknown custom field {}", field_id)).into(),
+            );
+        }
+        let updated = board_service::update(
+            &board.clone().with_custom_fields(custom_fields),
+            state.get_boards_collection(),
+        )
+        .await?;
+        let field_id = field_id.to_string();
+        let stored = task_service::get_tasks(board_id, state.get_tasks_collection())
+            .await?
+            .into_iter()
+            .filter(|t| t.custom_fields.contains_key(&field_id))
+            .collect::<Vec<Task>>();
+        task_service::unset_custom_field(board_id, &field_id, state.get_tasks_collection()).await?;
+        let unset = stored
+            .iter()
+            .map(|t| {
+                let mut task = t.clone();
+                task.custom_fields.remove(&field_id);
+                task
+            })
+            .collect::<Vec<Task>>();
+        let mut activities = board_activities(Some(&board), Some(&updated), actor.as_deref());
+        activities.extend(task_activities(&stored, &unset, actor.as_deref()));
+        activity_service::record(activities, state.get_activity_collection()).await;
+        Ok(())
+    }
+
+    match delete(&board_id, field_id, user.map(|u| u.0), state).await {
+        Ok(_) => {
+            let msg = format!("Deleted custom field with id {}", field_id);
+            debug!("[custom_field_delete_handler] {}", msg);
+            (
+                StatusCode::OK,
+                Json(Response {
+                    success: true,
+                    data: Some(msg),
+                    error_message: None,
+                    warnings: None,
+                }),
+            )
+        }
+        Err(e) => {
+            let msg = format!(
+                "[custom_field_delete_handler] Error deleting custom field {} on board {}: {:?}",
+                field_id,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use bson::{doc, Bson, Document};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::custom_field::model::{CustomField, CustomFieldKind, CustomFieldValue};
+use crate::error::{BoardRepoError, Result};
+use crate::util::get_string_list;
+
+pub fn doc_to_custom_field(doc: &Document) -> Result<CustomField> {
+    let id = doc.get_str("id")?;
+    let name = doc.get_str("name")?;
+    let kind = doc.get_str("kind")?;
+    let created_at = *doc.get_datetime("created_at")?;
+    let updated_at = doc
+        .get_datetime("updated_at")
+        .ok()
+        .map(|v| chrono::DateTime::from(*v));
+    match (Uuid::from_str(id), CustomFieldKind::from_str(kind)) {
+        (Ok(field_id), Ok(kind)) => Ok(CustomField {
+            id: field_id,
+            name: name.to_owned(),
+            kind,
+            options: get_string_list(doc, "options"),
+            created_at: chrono::DateTime::from(created_at),
+            updated_at,
+        }),
+        _ => Err(BoardRepoError::DecodeError(format!(
+            "Custom field doesnt have id or kind {} {}",
+            id, kind
+        ))
+        .into()),
+    }
+}
+
+pub fn custom_field_to_doc(field: &CustomField) -> Document {
+    doc! {
+        "id" : field.id.to_string(),
+        "name" : field.name.clone(),
+        "kind" : field.kind.to_string(),
+        "options" : field.options.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(field.created_at),
+        "updated_at" : field.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    }
+}
+
+pub fn get_custom_fields(doc: &Document) -> Vec<CustomField> {
+    doc.get_array("custom_fields")
+        .map(|fields| {
+            fields
+                .iter()
+                .filter_map(|f| f.as_document().and_then(|d| doc_to_custom_field(d).ok()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads the custom field values of a task, keyed by field id.
+pub fn get_custom_values(doc: &Document) -> BTreeMap<String, CustomFieldValue> {
+    doc.get_document("custom_fields")
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|(field_id, value)| {
+                    let value = match value {
+                        Bson::Double(n) => CustomFieldValue::Number(*n),
+                        Bson::Int32(n) => CustomFieldValue::Number(f64::from(*n)),
+                        Bson::Int64(n) => CustomFieldValue::Number(*n as f64),
+                        Bson::String(s) => CustomFieldValue::Text(s.clone()),
+                        Bson::Array(values) => CustomFieldValue::Options(
+                            values
+                                .iter()
+                                .filter_map(|v| v.as_str().map(|v| v.to_owned()))
+                                .collect(),
+                        ),
+                        _ => return None,
+                    };
+                    Some((field_id.clone(), value))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn custom_values_to_doc(values: &BTreeMap<String, CustomFieldValue>) -> Document {
+    values
+        .iter()
+        .map(|(field_id, value)| {
+            let value = match value {
+                CustomFieldValue::Number(n) => Bson::Double(*n),
+                CustomFieldValue::Text(s) => Bson::String(s.clone()),
+                CustomFieldValue::Options(values) => Bson::from(values.clone()),
+            };
+            (field_id.clone(), value)
+        })
+        .collect()
+}
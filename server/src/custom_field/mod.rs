@@ -0,0 +1,4 @@
+pub mod handlers;
+pub mod mapper;
+pub mod model;
+pub mod utils;
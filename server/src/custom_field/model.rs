@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+use crate::dto::{CustomFieldRequest, UpdateCustomFieldRequest};
+
+/// A typed field defined on a board, every task of the board can hold a value for it.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct CustomField {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub kind: CustomFieldKind,
+    /// Allowed values of the select kinds.
+    #[serde(default)]
+    pub options: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum CustomFieldKind {
+    Text,
+    Number,
+    /// `YYYY-MM-DD`
+    Date,
+    SingleSelect,
+    MultiSelect,
+    User,
+    Url,
+}
+
+/// Value of a custom field on a task, its shape depends on the kind of the field:
+/// numbers for `number`, lists for `multi_select` and strings for the others.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum CustomFieldValue {
+    Number(f64),
+    Text(String),
+    Options(Vec<String>),
+}
+
+impl CustomField {
+    pub fn from_request(r: CustomFieldRequest) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            name: r.name.trim().to_owned(),
+            kind: r.kind,
+            options: r.options.unwrap_or_default(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    /// The kind of a field cannot change as it would invalidate the stored values.
+    pub fn with_update_request(self, r: UpdateCustomFieldRequest) -> Self {
+        Self {
+            name: r.name.map(|n| n.trim().to_owned()).unwrap_or(self.name),
+            options: r.options.unwrap_or(self.options),
+            updated_at: Some(Utc::now()),
+            ..self
+        }
+    }
+}
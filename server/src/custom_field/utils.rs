@@ -0,0 +1,263 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, LinkedList};
+
+use chrono::NaiveDate;
+
+use crate::custom_field::model::{CustomField, CustomFieldKind, CustomFieldValue};
+use crate::dto::SortOrder;
+use crate::error::{BoardRepoError, Result, TaskRepoError};
+use crate::task::model::SortedTask;
+
+/// Prefix of the query parameters filtering tasks by custom field, e.g. `field.<field_id>=value`.
+pub const FILTER_PREFIX: &str = "field.";
+
+pub fn validate_field(field: &CustomField, board_fields: &[CustomField]) -> Result<()> {
+    let invalid = |msg: String| BoardRepoError::InvalidBoard(msg).into();
+    if field.name.is_empty() {
+        return Err(invalid(String::from("custom field name cannot be empty")));
+    }
+    if board_fields
+        .iter()
+        .any(|f| f.id != field.id && f.name.eq_ignore_ascii_case(&field.name))
+    {
+        return Err(invalid(format!(
+            "custom field {} already exists",
+            field.name
+        )));
+    }
+    match field.kind {
+        CustomFieldKind::SingleSelect | CustomFieldKind::MultiSelect
+            if field.options.is_empty() =>
+        {
+            Err(invalid(format!(
+                "custom field {} needs options",
+                field.name
+            )))
+        }
+        CustomFieldKind::SingleSelect | CustomFieldKind::MultiSelect => Ok(()),
+        _ if !field.options.is_empty() => Err(invalid(format!(
+            "custom field {} of kind {} cannot have options",
+            field.name, field.kind
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the custom field values of a task against the fields of its board.
+/// Only values that differ from the stored ones are checked, so that tasks
+/// holding a value that is not an option anymore can still be moved around.
+pub fn validate_values(
+    values: &BTreeMap<String, CustomFieldValue>,
+    stored: Option<&BTreeMap<String, CustomFieldValue>>,
+    board_fields: &[CustomField],
+) -> Result<()> {
+    for (field_id, value) in values {
+        if stored.and_then(|s| s.get(field_id)) == Some(value) {
+            continue;
+        }
+        let field = board_fields
+            .iter()
+            .find(|f| f.id.to_string() == *field_id)
+            .ok_or_else(|| {
+                TaskRepoError::InvalidTask(format!("unknown custom field {}", field_id))
+            })?;
+        if !is_valid_value(field, value) {
+            return Err(TaskRepoError::InvalidTask(format!(
+                "invalid value {:?} for custom field {} of kind {}",
+                value, field.name, field.kind
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn is_valid_value(field: &CustomField, value: &CustomFieldValue) -> bool {
+    match (field.kind, value) {
+        (CustomFieldKind::Number, CustomFieldValue::Number(n)) => n.is_finite(),
+        (CustomFieldKind::Text, CustomFieldValue::Text(_)) => true,
+        (CustomFieldKind::User, CustomFieldValue::Text(user)) => !user.trim().is_empty(),
+        (CustomFieldKind::Date, CustomFieldValue::Text(date)) => {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+        }
+        (CustomFieldKind::Url, CustomFieldValue::Text(link)) => url::Url::parse(link)
+            .map(|u| u.scheme() == "http" || u.scheme() == "https")
+            .unwrap_or(false),
+        (CustomFieldKind::SingleSelect, CustomFieldValue::Text(option)) => {
+            field.options.contains(option)
+        }
+        (CustomFieldKind::MultiSelect, CustomFieldValue::Options(options)) => {
+            options.iter().all(|o| field.options.contains(o))
+        }
+        _ => false,
+    }
+}
+
+/// Extracts the `field.<field_id>=value` filters from the query parameters.
+pub fn get_filters(params: &HashMap<String, String>) -> Vec<(String, String)> {
+    params
+        .iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(FILTER_PREFIX)
+                .map(|field_id| (field_id.to_owned(), value.clone()))
+        })
+        .collect()
+}
+
+pub fn matches(value: Option<&CustomFieldValue>, query: &str) -> bool {
+    match value {
+        Some(CustomFieldValue::Number(n)) => query.parse::<f64>() == Ok(*n),
+        Some(CustomFieldValue::Text(s)) => s.eq_ignore_ascii_case(query),
+        Some(CustomFieldValue::Options(options)) => {
+            options.iter().any(|o| o.eq_ignore_ascii_case(query))
+        }
+        None => false,
+    }
+}
+
+pub fn filter_tasks(
+    tasks: LinkedList<SortedTask>,
+    filters: &[(String, String)],
+) -> LinkedList<SortedTask> {
+    tasks
+        .into_iter()
+        .filter(|t| {
+            filters
+                .iter()
+                .all(|(field_id, query)| matches(t.custom_fields.get(field_id), query))
+        })
+        .collect()
+}
+
+/// Sorts the tasks of every column by the value of a custom field, tasks
+/// without a value come last. Columns keep their order.
+pub fn sort_tasks(
+    tasks: LinkedList<SortedTask>,
+    field_id: &str,
+    order: SortOrder,
+) -> LinkedList<SortedTask> {
+    let mut column_order: Vec<Option<uuid::Uuid>> = Vec::new();
+    for task in tasks.iter() {
+        if !column_order.contains(&task.column_id) {
+            column_order.push(task.column_id);
+        }
+    }
+    let column_index = |t: &SortedTask| column_order.iter().position(|c| *c == t.column_id);
+    let mut sorted = tasks.into_iter().collect::<Vec<SortedTask>>();
+    sorted.sort_by(|a, b| {
+        column_index(a).cmp(&column_index(b)).then_with(|| {
+            compare_values(
+                a.custom_fields.get(field_id),
+                b.custom_fields.get(field_id),
+                order,
+            )
+        })
+    });
+    sorted.into_iter().collect()
+}
+
+fn compare_values(
+    a: Option<&CustomFieldValue>,
+    b: Option<&CustomFieldValue>,
+    order: SortOrder,
+) -> Ordering {
+    let ordering = match (a, b) {
+        (None, None) => return Ordering::Equal,
+        (None, Some(_)) => return Ordering::Greater,
+        (Some(_), None) => return Ordering::Less,
+        (Some(CustomFieldValue::Number(a)), Some(CustomFieldValue::Number(b))) => {
+            a.partial_cmp(b).unwrap_or(Ordering::Equal)
+        }
+        (Some(CustomFieldValue::Text(a)), Some(CustomFieldValue::Text(b))) => {
+            a.to_lowercase().cmp(&b.to_lowercase())
+        }
+        (Some(CustomFieldValue::Options(a)), Some(CustomFieldValue::Options(b))) => a.cmp(b),
+        _ => Ordering::Equal,
+    };
+    match order {
+        SortOrder::Asc => ordering,
+        SortOrder::Desc => ordering.reverse(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use uuid::Uuid;
+
+    use crate::custom_field::model::{CustomField, CustomFieldKind, CustomFieldValue};
+    use crate::custom_field::utils::{compare_values, matches, validate_field, validate_values};
+    use crate::dto::SortOrder;
+    use crate::fixtures::mock_field;
+
+    #[test]
+    fn it_validates_values_against_field_kinds() {
+        let number = mock_field("number", CustomFieldKind::Number, &[]);
+        let date = mock_field("date", CustomFieldKind::Date, &[]);
+        let url = mock_field("url", CustomFieldKind::Url, &[]);
+        let env = mock_field(
+            "multi_select",
+            CustomFieldKind::MultiSelect,
+            &["dev", "prod"],
+        );
+        let fields = vec![number.clone(), date.clone(), url.clone(), env.clone()];
+        let values = |entries: Vec<(&CustomField, CustomFieldValue)>| {
+            entries
+                .into_iter()
+                .map(|(f, v)| (f.id.to_string(), v))
+                .collect::<BTreeMap<String, CustomFieldValue>>()
+        };
+        let text = |s: &str| CustomFieldValue::Text(s.to_owned());
+
+        let valid = values(vec![
+            (&number, CustomFieldValue::Number(3.5)),
+            (&date, text("2023-02-28")),
+            (&url, text("https://example.com/issue/1")),
+            (&env, CustomFieldValue::Options(vec![String::from("prod")])),
+        ]);
+        assert!(validate_values(&valid, None, &fields).is_ok());
+        assert!(validate_values(&values(vec![(&number, text("3"))]), None, &fields).is_err());
+        assert!(
+            validate_values(&values(vec![(&date, text("2023-02-30"))]), None, &fields).is_err()
+        );
+        assert!(validate_values(&values(vec![(&url, text("example"))]), None, &fields).is_err());
+        let stale = values(vec![(
+            &env,
+            CustomFieldValue::Options(vec![String::from("qa")]),
+        )]);
+        assert!(validate_values(&stale, None, &fields).is_err());
+        assert!(validate_values(&stale, Some(&stale), &fields).is_ok());
+
+        let mut unknown = BTreeMap::new();
+        unknown.insert(Uuid::new_v4().to_string(), text("x"));
+        assert!(validate_values(&unknown, None, &fields).is_err());
+    }
+
+    #[test]
+    fn it_validates_field_definitions() {
+        let select = mock_field("single_select", CustomFieldKind::SingleSelect, &[]);
+        assert!(validate_field(&select, &[]).is_err());
+        let text = mock_field("text", CustomFieldKind::Text, &["a"]);
+        assert!(validate_field(&text, &[]).is_err());
+        let text = mock_field("text", CustomFieldKind::Text, &[]);
+        let duplicate = mock_field("text", CustomFieldKind::Text, &[]);
+        assert!(validate_field(&text, std::slice::from_ref(&text)).is_ok());
+        assert!(validate_field(&duplicate, &[text]).is_err());
+    }
+
+    #[test]
+    fn it_filters_and_sorts_values() {
+        let two = CustomFieldValue::Number(2.0);
+        let ten = CustomFieldValue::Number(10.0);
+        assert!(matches(Some(&ten), "10"));
+        assert!(matches(
+            Some(&CustomFieldValue::Options(vec![String::from("Prod")])),
+            "prod"
+        ));
+        assert!(!matches(None, "10"));
+        assert!(compare_values(Some(&two), Some(&ten), SortOrder::Asc).is_lt());
+        assert!(compare_values(Some(&two), Some(&ten), SortOrder::Desc).is_gt());
+        assert!(compare_values(None, Some(&ten), SortOrder::Desc).is_gt());
+    }
+}
@@ -1,8 +1,13 @@
 use crate::EnvVars;
-use mongodb::options::ClientOptions;
-use mongodb::Client;
+use futures::future::BoxFuture;
+use mongodb::error::{TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT};
+use mongodb::options::{
+    Acknowledgment, ClientOptions, ReadConcern, TransactionOptions, WriteConcern,
+};
+use mongodb::{Client, ClientSession};
+use tracing::debug;
 
-use crate::error::Result;
+use crate::error::{AppError, Result};
 
 #[derive(Clone, Debug)]
 pub struct DB {
@@ -23,3 +28,59 @@ impl DB {
         })
     }
 }
+
+const MAX_TRANSACTION_ATTEMPTS: u8 = 3;
+
+/// Runs `action` in a transaction, retrying it when MongoDB reports a
+/// transient error. The future returned by `action` has to own what it uses
+/// besides the session.
+pub async fn run_transaction<F>(client: &Client, action: F) -> Result<()>
+where
+    F: for<'s> Fn(&'s mut ClientSession) -> BoxFuture<'s, mongodb::error::Result<()>>,
+{
+    let mut session = client.start_session(None).await?;
+    let options = TransactionOptions::builder()
+        .read_concern(ReadConcern::majority())
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Majority).build())
+        .build();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        session.start_transaction(options.clone()).await?;
+        let result = match action(&mut session).await {
+            Ok(()) => commit(&mut session).await,
+            Err(e) => {
+                // the transaction may already be aborted by the server
+                session.abort_transaction().await.ok();
+                Err(e)
+            }
+        };
+        match result {
+            Err(e)
+                if e.contains_label(TRANSIENT_TRANSACTION_ERROR)
+                    && attempts < MAX_TRANSACTION_ATTEMPTS =>
+            {
+                debug!("Retrying transaction after transient error {:?}", e);
+            }
+            result => return result.map_err(AppError::MongoError),
+        }
+    }
+}
+
+/// An "UnknownTransactionCommitResult" label means it is unknown whether the
+/// commit satisfied the write concern, in which case it is safe to retry it.
+async fn commit(session: &mut ClientSession) -> mongodb::error::Result<()> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match session.commit_transaction().await {
+            Err(e)
+                if e.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT)
+                    && attempts < MAX_TRANSACTION_ATTEMPTS =>
+            {
+                debug!("Retrying commit after error {:?}", e);
+            }
+            result => return result,
+        }
+    }
+}
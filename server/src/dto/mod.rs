@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
-use std::collections::LinkedList;
+use std::collections::{BTreeMap, HashMap, LinkedList};
 
+use crate::automation::model::{Action, Condition, Trigger};
+use crate::board::model::BoardSettings;
+use crate::chat::model::ChatEvent;
+use crate::custom_field::model::{CustomFieldKind, CustomFieldValue};
+use crate::report::model::ReportInterval;
 use crate::task::model::SortedTask;
+use crate::task_template::model::Recurrence;
 use crate::{board::model::BoardColumn, task::model::Task};
 
 #[skip_serializing_none]
@@ -12,6 +18,8 @@ pub struct Response<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error_message: Option<String>,
+    /// Problems that did not prevent the request from succeeding.
+    pub warnings: Option<Vec<String>>,
 }
 
 #[skip_serializing_none]
@@ -20,8 +28,133 @@ pub struct CreateTaskRequest {
     pub title: String,
     pub description: Option<String>,
     pub column_id: Option<uuid::Uuid>,
+    pub swimlane_id: Option<uuid::Uuid>,
     pub above_task_id: Option<uuid::Uuid>,
+    pub parent_task_id: Option<uuid::Uuid>,
+    pub assignee: Option<String>,
     pub board_id: uuid::Uuid,
+    pub custom_fields: Option<BTreeMap<String, CustomFieldValue>>,
+    pub estimate: Option<f64>,
+    /// Task template of the board pre-filling the fields missing from the request.
+    pub template_id: Option<uuid::Uuid>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ArchivedParams {
+    /// Also returns the archived boards, columns and tasks.
+    pub include_archived: Option<bool>,
+}
+
+const DEFAULT_PER_PAGE: u64 = 50;
+const MAX_PER_PAGE: u64 = 200;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PageParams {
+    /// Starts at 1.
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+impl PageParams {
+    pub fn page(&self) -> u64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub per_page: u64,
+    /// Number of items over all pages.
+    pub total: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ReportParams {
+    /// Start of the reported period, 12 weeks before `to` by default.
+    pub from: Option<DateTime<Utc>>,
+    /// End of the reported period, now by default.
+    pub to: Option<DateTime<Utc>>,
+    /// Step of the time series, a day by default.
+    pub interval: Option<ReportInterval>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct BoardTasksParams {
+    /// Only returns the tasks of this sprint.
+    pub sprint_id: Option<uuid::Uuid>,
+    /// Id of the custom field to sort the tasks of every column by.
+    pub sort_by: Option<String>,
+    pub order: Option<SortOrder>,
+    /// Custom field filters, given as `field.<field_id>=value`.
+    #[serde(flatten)]
+    pub filters: HashMap<String, String>,
+}
+
+/// What happens to the children of a task when it gets deleted.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildrenStrategy {
+    /// Children are kept and become top level tasks.
+    #[default]
+    Detach,
+    /// Children (and their own descendants) are deleted along with the parent.
+    Delete,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct UpdateTasksParams {
+    /// Allows moving blocked tasks into a done column.
+    pub force: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AddDependencyRequest {
+    pub blocked_by: uuid::Uuid,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DeleteTaskParams {
+    pub children: Option<ChildrenStrategy>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CommentRequest {
+    pub body: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct NotificationsParams {
+    /// Only returns the notifications not read yet.
+    pub unread: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CustomFieldRequest {
+    pub name: String,
+    pub kind: CustomFieldKind,
+    pub options: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpdateCustomFieldRequest {
+    pub name: Option<String>,
+    pub options: Option<Vec<String>>,
 }
 
 #[skip_serializing_none]
@@ -29,6 +162,8 @@ pub struct CreateTaskRequest {
 pub struct CreateBoardRequest {
     pub title: String,
     pub description: Option<String>,
+    /// Id of the template whose layout the board starts with.
+    pub template_id: Option<String>,
 }
 
 #[skip_serializing_none]
@@ -38,8 +173,8 @@ pub struct UpdateBoardRequest {
     pub title: String,
     pub description: Option<String>,
     pub columns: Vec<UpdateBoardRequestColumn>,
+    pub settings: Option<BoardSettings>,
     pub created_at: DateTime<Utc>,
-    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[skip_serializing_none]
@@ -47,14 +182,61 @@ pub struct UpdateBoardRequest {
 pub struct UpdateBoardRequestColumn {
     pub id: Option<uuid::Uuid>,
     pub name: String,
+    pub colour: Option<String>,
+    pub description: Option<String>,
+    pub done: Option<bool>,
+    pub wip_limit: Option<u32>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// Fields of a column to change, the missing ones are left untouched.
+#[derive(Clone, Deserialize, Debug)]
+pub struct UpdateColumnRequest {
+    pub name: Option<String>,
+    /// `null` clears the colour.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub colour: Option<Option<String>>,
+    /// `null` clears the description.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub description: Option<Option<String>>,
+    pub done: Option<bool>,
+    /// `null` removes the WIP limit.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub wip_limit: Option<Option<u32>>,
+}
+
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct DeleteColumnParams {
+    /// Id of the column receiving the tasks of the deleted column, or `unassigned`.
+    /// Columns holding tasks cannot be deleted without it.
+    pub move_tasks_to: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct ReorderColumnsRequest {
+    /// New order of the listed columns, they swap the places they occupy among
+    /// the columns of the board while the other columns keep theirs.
+    pub column_ids: Vec<uuid::Uuid>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct CreateSwimlaneRequest {
+    pub name: String,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct ReorderSwimlanesRequest {
+    /// New order of the listed swimlanes, they swap the places they occupy
+    /// among the swimlanes of the board while the other swimlanes keep theirs.
+    pub swimlane_ids: Vec<uuid::Uuid>,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, Deserialize, Debug)]
 pub struct CreateBoardColumnRequest {
     pub title: String,
+    pub wip_limit: Option<u32>,
     pub items: Vec<Task>,
     pub was_unassigned: Option<bool>,
 }
@@ -65,3 +247,116 @@ pub struct CreateBoardColumnResponse {
     pub column: BoardColumn,
     pub items: LinkedList<SortedTask>,
 }
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct CreateSprintRequest {
+    pub name: String,
+    pub goal: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Fields of a sprint to change, the missing ones are left untouched.
+#[derive(Clone, Deserialize, Debug)]
+pub struct UpdateSprintRequest {
+    pub name: Option<String>,
+    pub goal: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct SprintTasksRequest {
+    pub task_ids: Vec<uuid::Uuid>,
+}
+
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct CloseSprintRequest {
+    /// Sprint receiving the unfinished tasks, they go back to the backlog without it.
+    pub carry_over_to: Option<uuid::Uuid>,
+}
+
+/// Parts of the board copied along with its columns and swimlanes, none by default.
+#[skip_serializing_none]
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct CloneBoardRequest {
+    /// Title of the copy, the title of the board followed by `(copy)` by default.
+    pub title: Option<String>,
+    /// The tasks that are not archived, in their order.
+    pub include_tasks: Option<bool>,
+    /// The custom fields of the board and their values on the copied tasks.
+    pub include_custom_fields: Option<bool>,
+    pub include_settings: Option<bool>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct SaveTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct CreateTaskTemplateRequest {
+    pub name: String,
+    pub title_pattern: String,
+    pub description: Option<String>,
+    pub custom_fields: Option<BTreeMap<String, CustomFieldValue>>,
+    pub checklist: Option<Vec<String>>,
+    pub column_id: Option<uuid::Uuid>,
+    pub recurrence: Option<Recurrence>,
+}
+
+/// Fields of a task template to change, the missing ones are left untouched.
+#[derive(Clone, Deserialize, Debug)]
+pub struct UpdateTaskTemplateRequest {
+    pub name: Option<String>,
+    pub title_pattern: Option<String>,
+    pub description: Option<String>,
+    pub custom_fields: Option<BTreeMap<String, CustomFieldValue>>,
+    pub checklist: Option<Vec<String>>,
+    pub column_id: Option<uuid::Uuid>,
+    /// `null` stops the recurrence.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub recurrence: Option<Option<Recurrence>>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct CreateAutomationRuleRequest {
+    pub name: String,
+    pub enabled: Option<bool>,
+    pub trigger: Trigger,
+    pub conditions: Option<Vec<Condition>>,
+    pub actions: Vec<Action>,
+}
+
+/// Parts of an automation rule to change, the missing ones are left untouched.
+#[derive(Clone, Deserialize, Debug)]
+pub struct UpdateAutomationRuleRequest {
+    pub name: Option<String>,
+    pub enabled: Option<bool>,
+    pub trigger: Option<Trigger>,
+    pub conditions: Option<Vec<Condition>>,
+    pub actions: Option<Vec<Action>>,
+}
+
+/// Reminder preferences of the caller to change, the missing ones are left untouched.
+#[derive(Clone, Deserialize, Debug)]
+pub struct UpdateReminderPreferencesRequest {
+    /// `null` stops all the reminders.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub email: Option<Option<String>>,
+    pub due_soon: Option<bool>,
+    pub overdue: Option<bool>,
+    pub assignment: Option<bool>,
+    pub due_soon_days: Option<u32>,
+    pub digest_hours: Option<u32>,
+}
+
+/// Chat webhook of a board, replacing the one it had.
+#[derive(Clone, Deserialize, Debug)]
+pub struct SetChatWebhookRequest {
+    pub url: String,
+    /// All the events when missing.
+    pub events: Option<Vec<ChatEvent>>,
+    pub enabled: Option<bool>,
+}
@@ -1,3 +1,4 @@
+use axum::http::StatusCode;
 use thiserror::Error;
 
 pub type Result<T> = core::result::Result<T, AppError>;
@@ -9,27 +10,74 @@ pub enum AppError {
     TaskRepo(TaskRepoError),
     #[error("action in boards repo failed")]
     BoardRepo(BoardRepoError),
+    #[error("action in comments repo failed")]
+    CommentRepo(CommentRepoError),
+    #[error("action in notifications repo failed")]
+    NotificationRepo(NotificationRepoError),
+    #[error("action in attachments repo failed: {0}")]
+    AttachmentRepo(AttachmentRepoError),
+    #[error("action in trash repo failed: {0}")]
+    TrashRepo(TrashRepoError),
+    #[error("action in activity repo failed: {0}")]
+    ActivityRepo(ActivityRepoError),
+    #[error("action in transitions repo failed: {0}")]
+    TransitionRepo(TransitionRepoError),
+    #[error("action in sprints repo failed: {0}")]
+    SprintRepo(SprintRepoError),
+    #[error("action in templates repo failed: {0}")]
+    TemplateRepo(TemplateRepoError),
+    #[error("action in automations repo failed: {0}")]
+    AutomationRepo(AutomationRepoError),
+    #[error("action in reminders repo failed: {0}")]
+    ReminderRepo(ReminderRepoError),
+    #[error("action in chat webhooks repo failed: {0}")]
+    ChatRepo(ChatRepoError),
+    #[error("action in calendars repo failed: {0}")]
+    CalendarRepo(CalendarRepoError),
     #[error("mongodb error: {0}")]
     MongoError(#[from] mongodb::error::Error),
     #[error("could not access field in document: {0}")]
     MongoDataError(#[from] bson::document::ValueAccessError),
     #[error("internal server error")]
     InternalServerError,
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+}
+
+impl AppError {
+    /// Status code of errors that map to a specific HTTP status, `default` for all the others.
+    pub fn status_or(&self, default: StatusCode) -> StatusCode {
+        match self {
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::TrashRepo(TrashRepoError::NotFound) => StatusCode::NOT_FOUND,
+            AppError::SprintRepo(SprintRepoError::NotFound) => StatusCode::NOT_FOUND,
+            AppError::TemplateRepo(TemplateRepoError::NotFound) => StatusCode::NOT_FOUND,
+            AppError::AutomationRepo(AutomationRepoError::NotFound) => StatusCode::NOT_FOUND,
+            AppError::ChatRepo(ChatRepoError::NotFound) => StatusCode::NOT_FOUND,
+            AppError::CalendarRepo(CalendarRepoError::NotFound) => StatusCode::NOT_FOUND,
+            AppError::AttachmentRepo(AttachmentRepoError::TooLarge(_)) => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            AppError::AttachmentRepo(AttachmentRepoError::UnsupportedType(_)) => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            _ => default,
+        }
+    }
 }
 
 /// Errors that can happen when using the task repo.
 #[derive(Error, Debug)]
 pub enum TaskRepoError {
-    #[allow(dead_code)]
     #[error("task not found")]
     NotFound,
-    #[allow(dead_code)]
     #[error("task is invalid: {0}")]
     InvalidTask(String),
-    #[allow(dead_code)]
     #[error("decoding task resulted in an error: {0}")]
     DecodeError(String),
-    #[allow(dead_code)]
     #[error("transaction for task resulted in an error: {0}")]
     TransactionError(String),
 }
@@ -37,17 +85,136 @@ pub enum TaskRepoError {
 /// Errors that can happen when using the task repo.
 #[derive(Error, Debug)]
 pub enum BoardRepoError {
-    #[allow(dead_code)]
     #[error("board not found")]
     NotFound,
-    #[allow(dead_code)]
     #[error("board is invalid: {0}")]
     InvalidBoard(String),
-    #[allow(dead_code)]
     #[error("decoding board resulted in an error: {0}")]
     DecodeError(String),
 }
 
+/// Errors that can happen when using the comment repo.
+#[derive(Error, Debug)]
+pub enum CommentRepoError {
+    #[error("comment not found")]
+    NotFound,
+    #[error("comment is invalid: {0}")]
+    InvalidComment(String),
+    #[error("decoding comment resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the notification repo.
+#[derive(Error, Debug)]
+pub enum NotificationRepoError {
+    #[error("notification not found")]
+    NotFound,
+    #[error("decoding notification resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the attachment repo.
+#[derive(Error, Debug)]
+pub enum AttachmentRepoError {
+    #[error("attachment not found")]
+    NotFound,
+    #[error("attachment is invalid: {0}")]
+    InvalidAttachment(String),
+    #[error("attachments of type {0} are not allowed")]
+    UnsupportedType(String),
+    #[error("attachment exceeds the limit of {0} bytes")]
+    TooLarge(u64),
+    #[error("blob store error: {0}")]
+    Storage(String),
+    #[error("decoding attachment resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the trash repo.
+#[derive(Error, Debug)]
+pub enum TrashRepoError {
+    #[error("trash item not found")]
+    NotFound,
+    #[error("decoding trash item resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the activity repo.
+#[derive(Error, Debug)]
+pub enum ActivityRepoError {
+    #[error("decoding activity resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the transition repo.
+#[derive(Error, Debug)]
+pub enum TransitionRepoError {
+    #[error("decoding transition resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the sprint repo.
+#[derive(Error, Debug)]
+pub enum SprintRepoError {
+    #[error("sprint not found")]
+    NotFound,
+    #[error("sprint is invalid: {0}")]
+    InvalidSprint(String),
+    #[error("decoding sprint resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the template repo.
+#[derive(Error, Debug)]
+pub enum TemplateRepoError {
+    #[error("template not found")]
+    NotFound,
+    #[error("template is invalid: {0}")]
+    InvalidTemplate(String),
+}
+
+/// Errors that can happen when using the automation repo.
+#[derive(Error, Debug)]
+pub enum AutomationRepoError {
+    #[error("automation rule not found")]
+    NotFound,
+    #[error("automation rule is invalid: {0}")]
+    InvalidRule(String),
+    #[error("decoding automation resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the reminder repo or sending reminders.
+#[derive(Error, Debug)]
+pub enum ReminderRepoError {
+    #[error("reminder preferences are invalid: {0}")]
+    InvalidPreferences(String),
+    #[error("sending email failed: {0}")]
+    Mail(String),
+    #[error("decoding reminder preferences resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the chat webhook repo.
+#[derive(Error, Debug)]
+pub enum ChatRepoError {
+    #[error("chat webhook not found")]
+    NotFound,
+    #[error("chat webhook is invalid: {0}")]
+    InvalidWebhook(String),
+    #[error("posting to chat webhook failed: {0}")]
+    Post(String),
+    #[error("decoding chat webhook resulted in an error: {0}")]
+    DecodeError(String),
+}
+
+/// Errors that can happen when using the calendar repo.
+#[derive(Error, Debug)]
+pub enum CalendarRepoError {
+    #[error("calendar not found")]
+    NotFound,
+}
+
 /// This makes it possible to use `?` to automatically convert a `TaskRepoError`
 /// into an `AppError`.
 impl From<TaskRepoError> for AppError {
@@ -64,6 +231,46 @@ impl From<BoardRepoError> for AppError {
     }
 }
 
+/// This makes it possible to use `?` to automatically convert a `CommentRepoError`
+/// into an `AppError`.
+impl From<CommentRepoError> for AppError {
+    fn from(inner: CommentRepoError) -> Self {
+        AppError::CommentRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert a `NotificationRepoError`
+/// into an `AppError`.
+impl From<NotificationRepoError> for AppError {
+    fn from(inner: NotificationRepoError) -> Self {
+        AppError::NotificationRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert an `AttachmentRepoError`
+/// into an `AppError`.
+impl From<AttachmentRepoError> for AppError {
+    fn from(inner: AttachmentRepoError) -> Self {
+        AppError::AttachmentRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert a `TrashRepoError`
+/// into an `AppError`.
+impl From<TrashRepoError> for AppError {
+    fn from(inner: TrashRepoError) -> Self {
+        AppError::TrashRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert an `ActivityRepoError`
+/// into an `AppError`.
+impl From<ActivityRepoError> for AppError {
+    fn from(inner: ActivityRepoError) -> Self {
+        AppError::ActivityRepo(inner)
+    }
+}
+
 /// This makes it possible to use `?` to automatically convert a `TaskRepoError`
 /// into an `AppError`.
 impl From<AppError> for std::result::Result<(), AppError> {
@@ -79,3 +286,51 @@ impl From<TaskRepoError> for std::result::Result<(), AppError> {
         std::result::Result::Err(inner.into())
     }
 }
+
+/// This makes it possible to use `?` to automatically convert a `SprintRepoError`
+/// into an `AppError`.
+impl From<SprintRepoError> for AppError {
+    fn from(inner: SprintRepoError) -> Self {
+        AppError::SprintRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert a `TemplateRepoError`
+/// into an `AppError`.
+impl From<TemplateRepoError> for AppError {
+    fn from(inner: TemplateRepoError) -> Self {
+        AppError::TemplateRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert an `AutomationRepoError`
+/// into an `AppError`.
+impl From<AutomationRepoError> for AppError {
+    fn from(inner: AutomationRepoError) -> Self {
+        AppError::AutomationRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert a `ReminderRepoError`
+/// into an `AppError`.
+impl From<ReminderRepoError> for AppError {
+    fn from(inner: ReminderRepoError) -> Self {
+        AppError::ReminderRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert a `ChatRepoError`
+/// into an `AppError`.
+impl From<ChatRepoError> for AppError {
+    fn from(inner: ChatRepoError) -> Self {
+        AppError::ChatRepo(inner)
+    }
+}
+
+/// This makes it possible to use `?` to automatically convert a `CalendarRepoError`
+/// into an `AppError`.
+impl From<CalendarRepoError> for AppError {
+    fn from(inner: CalendarRepoError) -> Self {
+        AppError::CalendarRepo(inner)
+    }
+}
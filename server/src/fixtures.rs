@@ -0,0 +1,71 @@
+//! Models shared by the unit tests, to be adjusted with struct update syntax.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::board::model::{Board, BoardColumn};
+use crate::custom_field::model::{CustomField, CustomFieldKind};
+use crate::task::model::Task;
+
+pub fn mock_column(name: &str, done: bool) -> BoardColumn {
+    BoardColumn {
+        id: Uuid::new_v4(),
+        name: String::from(name),
+        colour: None,
+        description: None,
+        done,
+        wip_limit: None,
+        created_at: DateTime::<Utc>::default(),
+        updated_at: None,
+        archived_at: None,
+    }
+}
+
+pub fn mock_field(name: &str, kind: CustomFieldKind, options: &[&str]) -> CustomField {
+    CustomField {
+        id: Uuid::new_v4(),
+        name: String::from(name),
+        kind,
+        options: options.iter().map(|o| String::from(*o)).collect(),
+        created_at: DateTime::<Utc>::default(),
+        updated_at: None,
+    }
+}
+
+pub fn mock_board(columns: Vec<BoardColumn>) -> Board {
+    Board {
+        id: Uuid::new_v4(),
+        title: String::from("mock-board"),
+        description: None,
+        columns,
+        custom_fields: Vec::new(),
+        swimlanes: Vec::new(),
+        settings: Default::default(),
+        created_at: DateTime::<Utc>::default(),
+        updated_at: None,
+        archived_at: None,
+    }
+}
+
+pub fn mock_task(board_id: Uuid, column_id: Option<Uuid>) -> Task {
+    Task {
+        id: Uuid::new_v4(),
+        title: String::from("mock-task"),
+        description: None,
+        column_id,
+        swimlane_id: None,
+        above_task_id: None,
+        parent_task_id: None,
+        blocked_by: Vec::new(),
+        assignee: None,
+        watchers: Vec::new(),
+        mentions: Vec::new(),
+        custom_fields: Default::default(),
+        estimate: None,
+        sprint_id: None,
+        board_id,
+        created_at: DateTime::<Utc>::default(),
+        updated_at: None,
+        archived_at: None,
+    }
+}
@@ -4,22 +4,67 @@ use clap::Parser;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
-use crate::config::Config;
-use crate::server::app;
+use crate::attachment::store::s3::S3Config;
+use crate::config::{Command, Config};
+use crate::reminder::mailer::SmtpConfig;
+use crate::server::{app, state};
 
+mod activity;
+mod admin;
+mod attachment;
+mod automation;
 mod board;
+mod calendar;
+mod chat;
+mod comment;
 mod config;
+mod custom_field;
 mod db;
 mod dto;
 mod error;
+#[cfg(test)]
+mod fixtures;
+mod notification;
+mod reminder;
+mod report;
 mod routes;
 mod server;
+mod sprint;
+mod swimlane;
 mod task;
+mod task_template;
+mod template;
+mod transition;
+mod trash;
+mod user;
 mod util;
 
 #[derive(Clone, Debug)]
 pub struct EnvVars {
     mongo_uri: String,
+    /// `local` (default) or `s3`
+    blob_store: String,
+    blob_store_path: String,
+    s3: S3Config,
+    attachment_max_bytes: u64,
+    attachment_allowed_types: Vec<String>,
+    /// Days deleted items stay in the trash before being purged.
+    trash_retention_days: i64,
+    /// Relay of the reminder emails, they are not sent without a host.
+    smtp: SmtpConfig,
+    /// URL the web client is served at, linked from emails and chat messages.
+    public_url: String,
+}
+
+const DEFAULT_ATTACHMENT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+/// Types browsers don't run scripts out of, `text/html` or `image/svg+xml` being left out.
+const DEFAULT_ATTACHMENT_ALLOWED_TYPES: &str =
+    "image/png,image/jpeg,image/gif,image/webp,text/plain,text/csv,application/pdf,application/json,application/zip";
+const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_owned())
 }
 
 #[tokio::main]
@@ -33,10 +78,55 @@ async fn main() {
 
     let env_vars = EnvVars {
         mongo_uri: std::env::var("MONGO_URI").expect("MONGO_URI environment variable must be set."),
+        blob_store: env_or("BLOB_STORE", "local"),
+        blob_store_path: env_or("BLOB_STORE_PATH", "./attachments"),
+        s3: S3Config {
+            endpoint: env_or("S3_ENDPOINT", ""),
+            bucket: env_or("S3_BUCKET", ""),
+            region: env_or("S3_REGION", "us-east-1"),
+            access_key: env_or("S3_ACCESS_KEY", ""),
+            secret_key: env_or("S3_SECRET_KEY", ""),
+        },
+        attachment_max_bytes: std::env::var("ATTACHMENT_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_ATTACHMENT_MAX_BYTES),
+        attachment_allowed_types: env_or(
+            "ATTACHMENT_ALLOWED_TYPES",
+            DEFAULT_ATTACHMENT_ALLOWED_TYPES,
+        )
+        .split(',')
+        .map(|t| t.trim().to_owned())
+        .filter(|t| !t.is_empty())
+        .collect(),
+        trash_retention_days: std::env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS),
+        smtp: SmtpConfig {
+            host: env_or("SMTP_HOST", ""),
+            port: std::env::var("SMTP_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_SMTP_PORT),
+            username: env_or("SMTP_USERNAME", ""),
+            password: env_or("SMTP_PASSWORD", ""),
+            security: env_or("SMTP_SECURITY", "starttls"),
+            from: env_or("MAIL_FROM", "Kanban <kanban@localhost>"),
+        },
+        public_url: env_or("PUBLIC_URL", "http://localhost:3000"),
     };
     // Parse command line arguments
     let config = Config::parse();
 
+    if let Some(Command::Orphans { fix }) = config.command {
+        let state = state(env_vars).await.unwrap();
+        admin::orphans::run(state, fix)
+            .await
+            .expect("orphans command failed");
+        return;
+    }
+
     // Run our service
     let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, config.port));
     info!("listening on {}", addr);
@@ -0,0 +1,169 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::config::AppState;
+use crate::dto::{NotificationsParams, Response};
+use crate::error::{AppError, NotificationRepoError};
+use crate::notification::service;
+use crate::user::extractor::CurrentUser;
+
+// Returns the notifications of the current user, newest first
+#[axum_macros::debug_handler]
+pub async fn get_notifications_handler(
+    Query(params): Query<NotificationsParams>,
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    debug!("Getting notifications of {}", user);
+    match service::get_notifications(
+        &user,
+        params.unread.unwrap_or(false),
+        state.get_notifications_collection(),
+    )
+    .await
+    {
+        Ok(notifications) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(notifications),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_notifications_handler] Error getting notifications of {}: {:?}",
+                user,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+async fn set_read(
+    user: String,
+    notification_id: String,
+    read: bool,
+    state: AppState,
+) -> (StatusCode, Json<Response<String>>) {
+    debug!(
+        "Marking notification {} of {} as read={}",
+        notification_id, user, read
+    );
+
+    async fn update(
+        user: &String,
+        notification_id: &String,
+        read: bool,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        match service::set_read(
+            user,
+            notification_id,
+            read,
+            state.get_notifications_collection(),
+        )
+        .await?
+        {
+            true => Ok(()),
+            false => Err(NotificationRepoError::NotFound.into()),
+        }
+    }
+
+    match update(&user, &notification_id, read, state).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(format!("Updated notification with id {}", notification_id)),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[set_read] Error updating notification {}: {:?}",
+                notification_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+#[axum_macros::debug_handler]
+pub async fn notification_read_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    set_read(user, path.0, true, state).await
+}
+
+#[axum_macros::debug_handler]
+pub async fn notification_unread_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    set_read(user, path.0, false, state).await
+}
+
+#[axum_macros::debug_handler]
+pub async fn notifications_read_all_handler(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    debug!("Marking all notifications of {} as read", user);
+    match service::mark_all_read(&user, state.get_notifications_collection()).await {
+        Ok(count) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(format!("Marked {} notifications as read", count)),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[notifications_read_all_handler] Error updating notifications of {}: {:?}",
+                user,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
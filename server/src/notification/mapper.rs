@@ -0,0 +1,79 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::error::{AppError, NotificationRepoError, Result};
+use crate::notification::model::{Notification, NotificationKind};
+use crate::util::get_optional_uuid;
+
+pub async fn parse_notifications(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<Notification>> {
+    let mut result: Vec<Notification> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_notification(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_notification(doc: &bson::document::Document) -> Result<Notification> {
+    let id = doc.get_str("id")?;
+    let recipient = doc.get_str("recipient")?;
+    let kind = doc.get_str("kind")?;
+    let task_id = doc.get_str("task_id")?;
+    let task_title = doc.get_str("task_title")?;
+    let board_id = doc.get_str("board_id")?;
+    let actor = doc.get_str("actor").ok();
+    let comment_id = get_optional_uuid(doc, "comment_id");
+    let column_id = get_optional_uuid(doc, "column_id");
+    let created_at = doc.get_datetime("created_at")?;
+    let read_at = doc
+        .get_datetime("read_at")
+        .ok()
+        .map(|v| chrono::DateTime::from(*v));
+    match (
+        Uuid::from_str(id),
+        NotificationKind::from_str(kind),
+        Uuid::from_str(task_id),
+        Uuid::from_str(board_id),
+    ) {
+        (Ok(notification_uuid), Ok(kind), Ok(task_uuid), Ok(board_uuid)) => Ok(Notification {
+            id: notification_uuid,
+            recipient: recipient.to_owned(),
+            kind,
+            task_id: task_uuid,
+            task_title: task_title.to_owned(),
+            board_id: board_uuid,
+            actor: actor.map(|a| a.to_owned()),
+            comment_id,
+            column_id,
+            created_at: chrono::DateTime::from(*created_at),
+            read_at,
+        }),
+        _ => Err(AppError::NotificationRepo(
+            NotificationRepoError::DecodeError(format!(
+                "Notification {} has an invalid id, kind, task_id or board_id",
+                id
+            )),
+        )),
+    }
+}
+
+pub fn notification_to_doc(notification: &Notification) -> bson::document::Document {
+    doc! {
+        "id" : notification.id.to_string(),
+        "recipient" : notification.recipient.clone(),
+        "kind" : notification.kind.to_string(),
+        "task_id" : notification.task_id.to_string(),
+        "task_title" : notification.task_title.clone(),
+        "board_id" : notification.board_id.to_string(),
+        "actor" : notification.actor.clone(),
+        "comment_id" : notification.comment_id.map(|v| v.to_string()),
+        "column_id" : notification.column_id.map(|v| v.to_string()),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(notification.created_at),
+        "read_at" : notification.read_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into)
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+use crate::task::model::Task;
+
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Notification {
+    pub id: uuid::Uuid,
+    pub recipient: String,
+    pub kind: NotificationKind,
+    pub task_id: uuid::Uuid,
+    pub task_title: String,
+    pub board_id: uuid::Uuid,
+    /// User whose action generated the notification, if known.
+    pub actor: Option<String>,
+    pub comment_id: Option<uuid::Uuid>,
+    /// Column the task was moved to, for `TaskMoved` notifications.
+    pub column_id: Option<uuid::Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationKind {
+    Mention,
+    Assignment,
+    TaskMoved,
+}
+
+impl Notification {
+    pub fn new(recipient: &str, kind: NotificationKind, task: &Task, actor: Option<&str>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            recipient: recipient.to_owned(),
+            kind,
+            task_id: task.id,
+            task_title: task.title.clone(),
+            board_id: task.board_id,
+            actor: actor.map(|a| a.to_owned()),
+            comment_id: None,
+            column_id: None,
+            created_at: Utc::now(),
+            read_at: None,
+        }
+    }
+}
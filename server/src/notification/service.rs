@@ -0,0 +1,64 @@
+use bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::error::{NotificationRepoError, Result};
+use crate::notification::mapper::{notification_to_doc, parse_notifications};
+use crate::notification::model::Notification;
+
+pub async fn get_notifications(
+    recipient: &String,
+    unread_only: bool,
+    collection: Collection<Document>,
+) -> Result<Vec<Notification>> {
+    let mut filter = doc! { "recipient": recipient };
+    if unread_only {
+        filter.insert("read_at", doc! { "$eq": null });
+    }
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .build();
+    let cursor = collection.find(filter, options).await.map_err(|_e| {
+        debug!("ERROR [get_notifications] {:?}", _e);
+        NotificationRepoError::NotFound
+    })?;
+    parse_notifications(cursor).await
+}
+
+/// Stores the given notifications. Failures are only logged, as notifications are
+/// generated as a side effect of changes that already succeeded.
+pub async fn notify(notifications: Vec<Notification>, collection: Collection<Document>) {
+    if notifications.is_empty() {
+        return;
+    }
+    debug!("[notify] Creating {} notifications", notifications.len());
+    let docs = notifications
+        .iter()
+        .map(notification_to_doc)
+        .collect::<Vec<Document>>();
+    if let Err(e) = collection.insert_many(docs, None).await {
+        error!("ERROR [notify] Couldn't store notifications: {:?}", e);
+    }
+}
+
+/// Marks a notification of `recipient` as read (or unread), returns false if it doesn't exist.
+pub async fn set_read(
+    recipient: &String,
+    notification_id: &String,
+    read: bool,
+    collection: Collection<Document>,
+) -> Result<bool> {
+    let filter = doc! { "id": notification_id, "recipient": recipient };
+    let read_at = read.then(bson::DateTime::now);
+    let updates = doc! { "$set": { "read_at": read_at } };
+    let res = collection.update_one(filter, updates, None).await?;
+    Ok(res.matched_count == 1)
+}
+
+pub async fn mark_all_read(recipient: &String, collection: Collection<Document>) -> Result<u64> {
+    let filter = doc! { "recipient": recipient, "read_at": { "$eq": null } };
+    let updates = doc! { "$set": { "read_at": bson::DateTime::now() } };
+    let res = collection.update_many(filter, updates, None).await?;
+    Ok(res.modified_count)
+}
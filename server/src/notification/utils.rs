@@ -0,0 +1,143 @@
+use crate::comment::model::Comment;
+use crate::notification::model::{Notification, NotificationKind};
+use crate::task::model::Task;
+
+/// Extracts the `@username` mentions of a text, in order of appearance and without duplicates.
+/// An `@` preceded by a word character (e.g. in an email address) is not a mention.
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '.';
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        let starts_mention = c == '@' && !previous.map(|p| p.is_alphanumeric()).unwrap_or(false);
+        previous = Some(c);
+        if !starts_mention {
+            continue;
+        }
+        let rest = &text[i + 1..];
+        let name = rest
+            .split(|c: char| !is_name_char(c))
+            .next()
+            .unwrap_or("")
+            .trim_end_matches(['.', '-']);
+        if !name.is_empty() && !mentions.iter().any(|m| m == name) {
+            mentions.push(name.to_owned());
+        }
+    }
+    mentions
+}
+
+/// Notifications generated by a task going from `before` (None when just created) to `after`.
+/// Users are never notified about their own actions.
+pub fn task_notifications(
+    before: Option<&Task>,
+    after: &Task,
+    actor: Option<&str>,
+) -> Vec<Notification> {
+    let mut notifications = Vec::new();
+    let previous_mentions = before.map(|t| t.mentions.clone()).unwrap_or_default();
+    after
+        .mentions
+        .iter()
+        .filter(|m| !previous_mentions.contains(m))
+        .for_each(|m| {
+            notifications.push(Notification::new(
+                m,
+                NotificationKind::Mention,
+                after,
+                actor,
+            ))
+        });
+
+    if let Some(assignee) = &after.assignee {
+        if before.and_then(|t| t.assignee.as_ref()) != Some(assignee) {
+            notifications.push(Notification::new(
+                assignee,
+                NotificationKind::Assignment,
+                after,
+                actor,
+            ));
+        }
+    }
+
+    if let Some(before) = before {
+        if before.column_id != after.column_id {
+            after.watchers.iter().for_each(|w| {
+                notifications.push(Notification {
+                    column_id: after.column_id,
+                    ..Notification::new(w, NotificationKind::TaskMoved, after, actor)
+                })
+            });
+        }
+    }
+
+    notifications
+        .into_iter()
+        .filter(|n| Some(n.recipient.as_str()) != actor)
+        .collect()
+}
+
+/// Notifications for the users newly mentioned in a comment.
+pub fn comment_notifications(
+    before: Option<&Comment>,
+    after: &Comment,
+    task: &Task,
+) -> Vec<Notification> {
+    let previous_mentions = before.map(|c| c.mentions.clone()).unwrap_or_default();
+    after
+        .mentions
+        .iter()
+        .filter(|m| !previous_mentions.contains(m) && **m != after.author)
+        .map(|m| Notification {
+            comment_id: Some(after.id),
+            ..Notification::new(m, NotificationKind::Mention, task, Some(&after.author))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::fixtures::mock_task;
+    use crate::notification::model::NotificationKind;
+    use crate::notification::utils::{parse_mentions, task_notifications};
+    use crate::task::model::Task;
+
+    #[test]
+    fn it_parses_mentions() {
+        assert_eq!(
+            parse_mentions("@alice can you check with @bob.smith? cc @alice, mail me@example.com"),
+            vec![String::from("alice"), String::from("bob.smith")]
+        );
+        assert!(parse_mentions("no mentions @ all").is_empty());
+    }
+
+    #[test]
+    fn it_notifies_mentions_assignments_and_moves() {
+        let before = Task {
+            watchers: vec![String::from("carol"), String::from("dave")],
+            mentions: vec![String::from("alice")],
+            ..mock_task(Uuid::new_v4(), Some(Uuid::new_v4()))
+        };
+        let after = Task {
+            column_id: Some(Uuid::new_v4()),
+            assignee: Some(String::from("bob")),
+            mentions: vec![String::from("alice"), String::from("erin")],
+            ..before.clone()
+        };
+
+        let kinds = task_notifications(Some(&before), &after, Some("dave"))
+            .into_iter()
+            .map(|n| (n.recipient, n.kind))
+            .collect::<Vec<(String, NotificationKind)>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (String::from("erin"), NotificationKind::Mention),
+                (String::from("bob"), NotificationKind::Assignment),
+                (String::from("carol"), NotificationKind::TaskMoved),
+            ]
+        );
+    }
+}
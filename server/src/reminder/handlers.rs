@@ -0,0 +1,103 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::config::AppState;
+use crate::dto::{Response, UpdateReminderPreferencesRequest};
+use crate::error::AppError;
+use crate::reminder::model::ReminderPreferences;
+use crate::reminder::service;
+use crate::reminder::utils::validate_preferences;
+use crate::user::extractor::CurrentUser;
+
+// Returns the email reminders the current user receives
+#[axum_macros::debug_handler]
+pub async fn get_reminder_preferences_handler(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    debug!("Getting reminder preferences of {}", user);
+    match service::get_preferences(&user, state.get_reminder_preferences_collection()).await {
+        Ok(preferences) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(preferences),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_reminder_preferences_handler] Error getting reminder preferences of {}: {:?}",
+                user,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Sets the email address of the current user and the reminders they opt out of
+#[axum_macros::debug_handler]
+pub async fn reminder_preferences_update_handler(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(payload): Json<UpdateReminderPreferencesRequest>,
+) -> impl IntoResponse {
+    debug!("Updating reminder preferences of {}", user);
+
+    async fn update(
+        user: &String,
+        payload: UpdateReminderPreferencesRequest,
+        state: AppState,
+    ) -> Result<ReminderPreferences, AppError> {
+        let collection = state.get_reminder_preferences_collection();
+        let preferences = service::get_preferences(user, collection.clone())
+            .await?
+            .with_update_request(payload);
+        validate_preferences(&preferences)?;
+        service::save(&preferences, collection).await?;
+        Ok(preferences)
+    }
+
+    match update(&user, payload, state).await {
+        Ok(preferences) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(preferences),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[reminder_preferences_update_handler] Error updating reminder preferences of {}: {:?}",
+                user,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+use crate::config::AppState;
+use crate::reminder::mailer::Mailer;
+use crate::reminder::service;
+
+const DIGEST_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Sends the reminder digests whose period is over, every 15 minutes.
+pub fn spawn_digests(state: AppState, mailer: Mailer, base_url: String) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DIGEST_INTERVAL);
+        loop {
+            interval.tick().await;
+            match service::send_digests(&mailer, &base_url, &state).await {
+                Ok(sent) => debug!("[spawn_digests] Sent {} reminder digests", sent),
+                Err(e) => error!(
+                    "ERROR [spawn_digests] Couldn't send the reminder digests: {:?}",
+                    e
+                ),
+            }
+        }
+    })
+}
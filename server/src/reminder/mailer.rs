@@ -0,0 +1,144 @@
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::error::{ReminderRepoError, Result};
+
+#[derive(Clone, Debug, Default)]
+pub struct SmtpConfig {
+    /// Relay the emails are sent through, reminders are disabled when empty.
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    /// `starttls`, `tls` or `none`, e.g. for a local SMTP sink.
+    pub security: String,
+    /// Sender of the emails, e.g. `Kanban <kanban@example.com>`.
+    pub from: String,
+}
+
+/// Sends emails through an SMTP relay.
+#[derive(Clone)]
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+fn mail_error(e: impl ToString) -> ReminderRepoError {
+    ReminderRepoError::Mail(e.to_string())
+}
+
+impl Mailer {
+    pub fn new(config: &SmtpConfig) -> Result<Self> {
+        let mut builder = match config.security.as_str() {
+            "tls" => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host).map_err(mail_error)?
+            }
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(mail_error)?,
+        }
+        .port(config.port);
+        if !config.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                config.username.clone(),
+                config.password.clone(),
+            ));
+        }
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.parse().map_err(mail_error)?,
+        })
+    }
+
+    /// Sends an email with a text and an HTML version of its body.
+    pub async fn send(&self, to: &str, subject: &str, text: String, html: String) -> Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse().map_err(mail_error)?)
+            .subject(subject)
+            .multipart(MultiPart::alternative_plain_html(text, html))
+            .map_err(mail_error)?;
+        self.transport.send(message).await.map_err(mail_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    use crate::reminder::mailer::{Mailer, SmtpConfig};
+
+    /// SMTP sink accepting every message of a single connection.
+    async fn start_sink() -> (SocketAddr, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let received = messages.clone();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = socket.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            writer.write_all(b"220 sink ready\r\n").await.unwrap();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250 sink\r\n"
+                } else if command.starts_with("DATA") {
+                    writer.write_all(b"354 go ahead\r\n").await.unwrap();
+                    let mut message = Vec::new();
+                    while let Some(line) = lines.next_line().await.unwrap() {
+                        if line == "." {
+                            break;
+                        }
+                        message.push(line);
+                    }
+                    received.lock().unwrap().push(message.join("\n"));
+                    b"250 queued\r\n"
+                } else if command.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+        });
+        (addr, messages)
+    }
+
+    #[tokio::test]
+    async fn it_sends_emails_through_smtp() {
+        let (addr, messages) = start_sink().await;
+        let mailer = Mailer::new(&SmtpConfig {
+            host: addr.ip().to_string(),
+            port: addr.port(),
+            security: String::from("none"),
+            from: String::from("Kanban <kanban@localhost>"),
+            ..Default::default()
+        })
+        .unwrap();
+
+        mailer
+            .send(
+                "alice@example.com",
+                "Reminders",
+                String::from("plain reminders"),
+                String::from("<p>html reminders</p>"),
+            )
+            .await
+            .unwrap();
+        let messages = messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("Subject: Reminders"));
+        assert!(messages[0].contains("To: alice@example.com"));
+        assert!(messages[0].contains("Content-Type: text/plain"));
+        assert!(messages[0].contains("plain reminders"));
+        assert!(messages[0].contains("Content-Type: text/html"));
+    }
+}
@@ -0,0 +1,59 @@
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+
+use crate::error::Result;
+use crate::reminder::model::ReminderPreferences;
+
+pub async fn parse_preferences(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<ReminderPreferences>> {
+    let mut result: Vec<ReminderPreferences> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_preferences(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_preferences(doc: &bson::document::Document) -> Result<ReminderPreferences> {
+    let user = doc.get_str("user")?;
+    let last_digest_at = doc.get_datetime("last_digest_at")?;
+    let created_at = doc.get_datetime("created_at")?;
+    let defaults = ReminderPreferences::new(user);
+    Ok(ReminderPreferences {
+        user: user.to_owned(),
+        email: doc.get_str("email").ok().map(|e| e.to_owned()),
+        due_soon: doc.get_bool("due_soon").unwrap_or(defaults.due_soon),
+        overdue: doc.get_bool("overdue").unwrap_or(defaults.overdue),
+        assignment: doc.get_bool("assignment").unwrap_or(defaults.assignment),
+        due_soon_days: doc
+            .get_i32("due_soon_days")
+            .map(|d| d as u32)
+            .unwrap_or(defaults.due_soon_days),
+        digest_hours: doc
+            .get_i32("digest_hours")
+            .map(|h| h as u32)
+            .unwrap_or(defaults.digest_hours),
+        last_digest_at: chrono::DateTime::from(*last_digest_at),
+        created_at: chrono::DateTime::from(*created_at),
+        updated_at: doc
+            .get_datetime("updated_at")
+            .ok()
+            .map(|v| chrono::DateTime::from(*v)),
+    })
+}
+
+pub fn preferences_to_doc(preferences: &ReminderPreferences) -> bson::document::Document {
+    doc! {
+        "user" : preferences.user.clone(),
+        "email" : preferences.email.clone(),
+        "due_soon" : preferences.due_soon,
+        "overdue" : preferences.overdue,
+        "assignment" : preferences.assignment,
+        "due_soon_days" : preferences.due_soon_days as i32,
+        "digest_hours" : preferences.digest_hours as i32,
+        "last_digest_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(preferences.last_digest_at),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(preferences.created_at),
+        "updated_at" : preferences.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    }
+}
@@ -0,0 +1,8 @@
+pub mod handlers;
+pub mod jobs;
+pub mod mailer;
+pub mod mapper;
+pub mod model;
+pub mod service;
+pub mod templates;
+pub mod utils;
@@ -0,0 +1,93 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+use crate::dto::UpdateReminderPreferencesRequest;
+
+const DEFAULT_DUE_SOON_DAYS: u32 = 1;
+const DEFAULT_DIGEST_HOURS: u32 = 24;
+
+/// Email reminders a user receives, users without an address receive none.
+/// Reminders are batched into one digest per `digest_hours`.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ReminderPreferences {
+    pub user: String,
+    pub email: Option<String>,
+    /// Tasks assigned to the user that are due within `due_soon_days`.
+    pub due_soon: bool,
+    /// Tasks assigned to the user whose due date passed.
+    pub overdue: bool,
+    /// Tasks that got assigned to the user.
+    pub assignment: bool,
+    pub due_soon_days: u32,
+    pub digest_hours: u32,
+    /// End of the period covered by the last digest, managed by the sender.
+    pub last_digest_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ReminderKind {
+    DueSoon,
+    Overdue,
+    Assignment,
+}
+
+/// Entry of a digest.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Reminder {
+    pub kind: ReminderKind,
+    pub task_id: uuid::Uuid,
+    pub task_title: String,
+    pub board_id: uuid::Uuid,
+    pub board_title: String,
+    pub due_date: Option<NaiveDate>,
+}
+
+/// Reminders of a user sent in one email.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Digest {
+    pub user: String,
+    pub email: String,
+    pub reminders: Vec<Reminder>,
+}
+
+impl ReminderPreferences {
+    /// Users opt out of the kinds of reminders they don't want, all are sent
+    /// by default.
+    pub fn new(user: &str) -> Self {
+        Self {
+            user: user.to_owned(),
+            email: None,
+            due_soon: true,
+            overdue: true,
+            assignment: true,
+            due_soon_days: DEFAULT_DUE_SOON_DAYS,
+            digest_hours: DEFAULT_DIGEST_HOURS,
+            last_digest_at: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    pub fn with_update_request(self, r: UpdateReminderPreferencesRequest) -> Self {
+        Self {
+            email: r
+                .email
+                .map(|e| e.map(|e| e.trim().to_owned()))
+                .unwrap_or(self.email),
+            due_soon: r.due_soon.unwrap_or(self.due_soon),
+            overdue: r.overdue.unwrap_or(self.overdue),
+            assignment: r.assignment.unwrap_or(self.assignment),
+            due_soon_days: r.due_soon_days.unwrap_or(self.due_soon_days),
+            digest_hours: r.digest_hours.unwrap_or(self.digest_hours),
+            updated_at: Some(Utc::now()),
+            ..self
+        }
+    }
+}
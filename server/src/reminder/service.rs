@@ -0,0 +1,154 @@
+use bson::{doc, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::ReplaceOptions;
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::board::model::Board;
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::error::Result;
+use crate::notification::service as notification_service;
+use crate::reminder::mailer::Mailer;
+use crate::reminder::mapper::{doc_to_preferences, parse_preferences, preferences_to_doc};
+use crate::reminder::model::{Digest, ReminderPreferences};
+use crate::reminder::templates::{render_html, render_text, subject};
+use crate::reminder::utils::{assignment_reminders, due_reminders, is_digest_due};
+use crate::task::service as task_service;
+
+/// Returns the reminder preferences of a user, the defaults when none were saved.
+pub async fn get_preferences(
+    user: &String,
+    collection: Collection<Document>,
+) -> Result<ReminderPreferences> {
+    match collection.find_one(doc! { "user": user }, None).await? {
+        Some(doc) => doc_to_preferences(&doc),
+        None => Ok(ReminderPreferences::new(user)),
+    }
+}
+
+pub async fn save(
+    preferences: &ReminderPreferences,
+    collection: Collection<Document>,
+) -> Result<&ReminderPreferences> {
+    debug!(
+        "[save_preferences] Saving reminder preferences of {}",
+        preferences.user
+    );
+    let options = ReplaceOptions::builder().upsert(true).build();
+    collection
+        .replace_one(
+            doc! { "user": preferences.user.clone() },
+            preferences_to_doc(preferences),
+            options,
+        )
+        .await?;
+    Ok(preferences)
+}
+
+async fn get_with_email(collection: Collection<Document>) -> Result<Vec<ReminderPreferences>> {
+    let cursor = collection
+        .find(doc! { "email": { "$ne": null } }, None)
+        .await?;
+    parse_preferences(cursor).await
+}
+
+/// Moves the period of the digests of a user from `from` to `to` unless it
+/// moved since the preferences were read, returns whether it moved.
+async fn move_digest_period(
+    user: &String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    collection: Collection<Document>,
+) -> Result<bool> {
+    let filter = doc! {
+        "user": user,
+        "last_digest_at": <DateTime<Utc> as Into<bson::DateTime>>::into(from),
+    };
+    let updates = doc! {
+        "$set": { "last_digest_at": <DateTime<Utc> as Into<bson::DateTime>>::into(to) },
+    };
+    let result = collection.update_one(filter, updates, None).await?;
+    Ok(result.modified_count == 1)
+}
+
+async fn collect_digest(
+    preferences: &ReminderPreferences,
+    email: &str,
+    boards: &[Board],
+    until: DateTime<Utc>,
+    state: &AppState,
+) -> Result<Digest> {
+    let since = preferences.last_digest_at;
+    let mut reminders = Vec::new();
+    if preferences.due_soon || preferences.overdue {
+        let tasks =
+            task_service::get_tasks_of_assignee(&preferences.user, state.get_tasks_collection())
+                .await?;
+        reminders.extend(due_reminders(preferences, boards, &tasks, since, until));
+    }
+    if preferences.assignment {
+        let notifications = notification_service::get_notifications(
+            &preferences.user,
+            false,
+            state.get_notifications_collection(),
+        )
+        .await?;
+        reminders.extend(assignment_reminders(
+            preferences,
+            boards,
+            &notifications,
+            since,
+            until,
+        ));
+    }
+    Ok(Digest {
+        user: preferences.user.clone(),
+        email: email.to_owned(),
+        reminders,
+    })
+}
+
+/// Sends the digests whose period is over, returns how many. The period of a
+/// digest is moved before it gets sent so that it is sent once across running
+/// instances, and moved back when sending fails to be retried.
+pub async fn send_digests(mailer: &Mailer, base_url: &str, state: &AppState) -> Result<usize> {
+    let collection = state.get_reminder_preferences_collection();
+    let now = Utc::now();
+    let due = get_with_email(collection.clone())
+        .await?
+        .into_iter()
+        .filter(|p| is_digest_due(p, now))
+        .collect::<Vec<ReminderPreferences>>();
+    if due.is_empty() {
+        return Ok(0);
+    }
+    let boards = board_service::get_all_boards(state.get_boards_collection()).await?;
+    let mut sent = 0;
+    for preferences in due.iter() {
+        let Some(email) = preferences.email.as_deref() else {
+            continue;
+        };
+        let digest = collect_digest(preferences, email, &boards, now, state).await?;
+        let from = preferences.last_digest_at;
+        if !move_digest_period(&preferences.user, from, now, collection.clone()).await? {
+            continue;
+        }
+        if digest.reminders.is_empty() {
+            continue;
+        }
+        let text = render_text(&digest, base_url);
+        let html = render_html(&digest, base_url);
+        match mailer.send(email, &subject(&digest), text, html).await {
+            Ok(()) => sent += 1,
+            Err(e) => {
+                error!(
+                    "ERROR [send_digests] Couldn't send the digest of {}: {:?}",
+                    preferences.user, e
+                );
+                move_digest_period(&preferences.user, now, from, collection.clone()).await?;
+            }
+        }
+    }
+    Ok(sent)
+}
@@ -0,0 +1,120 @@
+use ammonia::clean_text;
+
+use crate::reminder::model::{Digest, Reminder, ReminderKind};
+
+/// Sections of a digest, in the order they are shown.
+const SECTIONS: [(ReminderKind, &str); 3] = [
+    (ReminderKind::Overdue, "Overdue"),
+    (ReminderKind::DueSoon, "Due soon"),
+    (ReminderKind::Assignment, "Assigned to you"),
+];
+
+pub fn subject(digest: &Digest) -> String {
+    match digest.reminders.len() {
+        1 => String::from("[Kanban] 1 task needs your attention"),
+        n => format!("[Kanban] {} tasks need your attention", n),
+    }
+}
+
+fn board_link(reminder: &Reminder, base_url: &str) -> String {
+    format!(
+        "{}/boards/{}",
+        base_url.trim_end_matches('/'),
+        reminder.board_id
+    )
+}
+
+fn sections(digest: &Digest) -> impl Iterator<Item = (&'static str, Vec<&Reminder>)> {
+    SECTIONS.iter().filter_map(|(kind, title)| {
+        let reminders = digest
+            .reminders
+            .iter()
+            .filter(|r| r.kind == *kind)
+            .collect::<Vec<&Reminder>>();
+        (!reminders.is_empty()).then_some((*title, reminders))
+    })
+}
+
+pub fn render_text(digest: &Digest, base_url: &str) -> String {
+    let mut text = format!("Hello {},\n", digest.user);
+    for (title, reminders) in sections(digest) {
+        text.push_str(&format!("\n{}\n", title));
+        for reminder in reminders {
+            text.push_str(&format!(
+                "- {} ({})",
+                reminder.task_title, reminder.board_title
+            ));
+            if let Some(due) = reminder.due_date {
+                text.push_str(&format!(", due {}", due));
+            }
+            text.push_str(&format!("\n  {}\n", board_link(reminder, base_url)));
+        }
+    }
+    text.push_str("\nYou can change which reminders you receive in your reminder preferences.\n");
+    text
+}
+
+pub fn render_html(digest: &Digest, base_url: &str) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<body style=\"font-family: sans-serif\">\n<p>Hello {},</p>\n",
+        clean_text(&digest.user)
+    );
+    for (title, reminders) in sections(digest) {
+        html.push_str(&format!("<h3>{}</h3>\n<ul>\n", title));
+        for reminder in reminders {
+            html.push_str(&format!(
+                "<li><a href=\"{}\">{}</a> ({})",
+                clean_text(&board_link(reminder, base_url)),
+                clean_text(&reminder.task_title),
+                clean_text(&reminder.board_title)
+            ));
+            if let Some(due) = reminder.due_date {
+                html.push_str(&format!(", due {}", due));
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str(
+        "<p><small>You can change which reminders you receive in your reminder preferences.</small></p>\n</body>\n</html>\n",
+    );
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use crate::reminder::model::{Digest, Reminder, ReminderKind};
+    use crate::reminder::templates::{render_html, render_text, subject};
+
+    #[test]
+    fn it_renders_digests() {
+        let reminder = |kind: ReminderKind, title: &str| Reminder {
+            kind,
+            task_id: Uuid::new_v4(),
+            task_title: String::from(title),
+            board_id: Uuid::nil(),
+            board_title: String::from("Web"),
+            due_date: NaiveDate::from_ymd_opt(2023, 3, 7),
+        };
+        let digest = Digest {
+            user: String::from("alice"),
+            email: String::from("alice@example.com"),
+            reminders: vec![
+                reminder(ReminderKind::DueSoon, "Fix <script> tags"),
+                reminder(ReminderKind::Overdue, "Release"),
+            ],
+        };
+
+        assert_eq!(subject(&digest), "[Kanban] 2 tasks need your attention");
+        let text = render_text(&digest, "http://localhost:3000/");
+        assert!(text.find("Overdue\n- Release (Web), due 2023-03-07") < text.find("Due soon"));
+        assert!(text.contains(&format!("  http://localhost:3000/boards/{}\n", Uuid::nil())));
+        let html = render_html(&digest, "http://localhost:3000");
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("Assigned to you"));
+    }
+}
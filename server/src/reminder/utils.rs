@@ -0,0 +1,254 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+use crate::board::model::Board;
+use crate::custom_field::model::{CustomField, CustomFieldKind, CustomFieldValue};
+use crate::error::{ReminderRepoError, Result};
+use crate::notification::model::{Notification, NotificationKind};
+use crate::reminder::model::{Reminder, ReminderKind, ReminderPreferences};
+use crate::task::model::Task;
+
+/// Names of the date custom field holding the due date of the tasks of a
+/// board, whatever their case.
+const DUE_DATE_FIELDS: [&str; 2] = ["due date", "due"];
+
+pub fn validate_preferences(preferences: &ReminderPreferences) -> Result<()> {
+    let invalid = |msg: &str| -> Result<()> {
+        Err(ReminderRepoError::InvalidPreferences(String::from(msg)).into())
+    };
+    if preferences
+        .email
+        .as_ref()
+        .is_some_and(|e| e.parse::<lettre::Address>().is_err())
+    {
+        return invalid("invalid email address");
+    }
+    if preferences.due_soon_days == 0 {
+        return invalid("due soon days have to be at least 1");
+    }
+    if preferences.digest_hours == 0 {
+        return invalid("digest hours have to be at least 1");
+    }
+    Ok(())
+}
+
+/// Whether the period of the next digest of a user is over.
+pub fn is_digest_due(preferences: &ReminderPreferences, now: DateTime<Utc>) -> bool {
+    preferences.email.is_some()
+        && (preferences.due_soon || preferences.overdue || preferences.assignment)
+        && now - preferences.last_digest_at >= Duration::hours(preferences.digest_hours as i64)
+}
+
+pub fn due_date_field(board: &Board) -> Option<&CustomField> {
+    board.custom_fields.iter().find(|f| {
+        f.kind == CustomFieldKind::Date
+            && DUE_DATE_FIELDS
+                .iter()
+                .any(|name| f.name.trim().eq_ignore_ascii_case(name))
+    })
+}
+
+pub fn due_date(task: &Task, field: &CustomField) -> Option<NaiveDate> {
+    match task.custom_fields.get(&field.id.to_string())? {
+        CustomFieldValue::Text(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+        _ => None,
+    }
+}
+
+/// A task is overdue once its due day is over, in UTC.
+fn overdue_at(due: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&(due + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Reminders of the tasks assigned to a user that got due soon or overdue in
+/// the period `(since, until]`, so that each is sent once. Tasks sitting in a
+/// done column are left out.
+pub fn due_reminders(
+    preferences: &ReminderPreferences,
+    boards: &[Board],
+    tasks: &[Task],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<Reminder> {
+    let within = |at: DateTime<Utc>| since < at && at <= until;
+    tasks
+        .iter()
+        .filter(|t| t.assignee.as_ref() == Some(&preferences.user) && t.archived_at.is_none())
+        .filter_map(|task| {
+            let board = boards
+                .iter()
+                .find(|b| b.id == task.board_id && b.archived_at.is_none())?;
+            if task
+                .column_id
+                .is_some_and(|c| board.done_column_ids().contains(&c))
+            {
+                return None;
+            }
+            let due = due_date(task, due_date_field(board)?)?;
+            let at = overdue_at(due);
+            let soon = at - Duration::days(preferences.due_soon_days as i64);
+            let kind = if preferences.overdue && within(at) {
+                ReminderKind::Overdue
+            } else if preferences.due_soon && within(soon) && at > until {
+                ReminderKind::DueSoon
+            } else {
+                return None;
+            };
+            Some(Reminder {
+                kind,
+                task_id: task.id,
+                task_title: task.title.clone(),
+                board_id: board.id,
+                board_title: board.title.clone(),
+                due_date: Some(due),
+            })
+        })
+        .collect()
+}
+
+/// Reminders of the tasks assigned to a user in the period `(since, until]`,
+/// read from the assignment notifications.
+pub fn assignment_reminders(
+    preferences: &ReminderPreferences,
+    boards: &[Board],
+    notifications: &[Notification],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<Reminder> {
+    if !preferences.assignment {
+        return Vec::new();
+    }
+    let mut seen = HashSet::new();
+    notifications
+        .iter()
+        .filter(|n| {
+            n.kind == NotificationKind::Assignment
+                && n.recipient == preferences.user
+                && since < n.created_at
+                && n.created_at <= until
+        })
+        .filter(|n| seen.insert(n.task_id))
+        .filter_map(|n| {
+            let board = boards.iter().find(|b| b.id == n.board_id)?;
+            Some(Reminder {
+                kind: ReminderKind::Assignment,
+                task_id: n.task_id,
+                task_title: n.task_title.clone(),
+                board_id: board.id,
+                board_title: board.title.clone(),
+                due_date: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+    use crate::board::model::Board;
+    use crate::custom_field::model::{CustomFieldKind, CustomFieldValue};
+    use crate::fixtures::{mock_board, mock_column, mock_field, mock_task};
+    use crate::notification::model::{Notification, NotificationKind};
+    use crate::reminder::model::{ReminderKind, ReminderPreferences};
+    use crate::reminder::utils::{
+        assignment_reminders, due_reminders, is_digest_due, validate_preferences,
+    };
+    use crate::task::model::Task;
+
+    fn board_with_due_date() -> Board {
+        Board {
+            custom_fields: vec![mock_field("Due date", CustomFieldKind::Date, &[])],
+            ..mock_board(vec![
+                mock_column("mock-column", false),
+                mock_column("mock-column", true),
+            ])
+        }
+    }
+
+    fn task_due(board: &Board, due: &str, done: bool) -> Task {
+        Task {
+            title: String::from(due),
+            assignee: Some(String::from("alice")),
+            custom_fields: [(
+                board.custom_fields[0].id.to_string(),
+                CustomFieldValue::Text(String::from(due)),
+            )]
+            .into(),
+            ..mock_task(board.id, Some(board.columns[done as usize].id))
+        }
+    }
+
+    #[test]
+    fn it_collects_the_reminders_of_a_digest() {
+        let board = board_with_due_date();
+        let since = Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 0).unwrap();
+        let until = since + Duration::days(1);
+        let preferences = ReminderPreferences {
+            email: Some(String::from("alice@example.com")),
+            last_digest_at: since,
+            ..ReminderPreferences::new("alice")
+        };
+        assert!(validate_preferences(&preferences).is_ok());
+        assert!(is_digest_due(&preferences, until));
+        assert!(!is_digest_due(&preferences, until - Duration::hours(1)));
+
+        let overdue = task_due(&board, "2023-03-06", false);
+        let due_soon = task_due(&board, "2023-03-07", false);
+        let tasks = vec![
+            overdue.clone(),
+            due_soon.clone(),
+            task_due(&board, "2023-03-06", true),
+            // due soon in a later digest
+            task_due(&board, "2023-03-09", false),
+            task_due(&board, "2023-03-07", true),
+            Task {
+                assignee: Some(String::from("bob")),
+                ..task_due(&board, "2023-03-07", false)
+            },
+        ];
+        let boards = vec![board.clone()];
+
+        let reminders = due_reminders(&preferences, &boards, &tasks, since, until);
+        assert_eq!(reminders.len(), 2);
+        assert_eq!(reminders[0].task_id, overdue.id);
+        assert_eq!(reminders[0].kind, ReminderKind::Overdue);
+        assert_eq!(reminders[1].task_id, due_soon.id);
+        assert_eq!(reminders[1].kind, ReminderKind::DueSoon);
+        assert_eq!(reminders[1].due_date, NaiveDate::from_ymd_opt(2023, 3, 7));
+
+        let opted_out = ReminderPreferences {
+            due_soon: false,
+            ..preferences.clone()
+        };
+        let reminders = due_reminders(&opted_out, &boards, &tasks, since, until);
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].kind, ReminderKind::Overdue);
+
+        let notification = |at: DateTime<Utc>| Notification {
+            created_at: at,
+            ..Notification::new(
+                "alice",
+                NotificationKind::Assignment,
+                &due_soon,
+                Some("bob"),
+            )
+        };
+        let notifications = vec![
+            notification(since + Duration::hours(1)),
+            notification(since + Duration::hours(2)),
+            notification(since - Duration::hours(1)),
+        ];
+        let reminders = assignment_reminders(&preferences, &boards, &notifications, since, until);
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].kind, ReminderKind::Assignment);
+        assert_eq!(reminders[0].board_title, "mock-board");
+
+        let invalid = ReminderPreferences {
+            email: Some(String::from("alice")),
+            ..preferences
+        };
+        assert!(validate_preferences(&invalid).is_err());
+    }
+}
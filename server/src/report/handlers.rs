@@ -0,0 +1,152 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
+use tracing::{debug, error};
+
+use crate::board::model::Board;
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::dto::{ReportParams, Response};
+use crate::error::AppError;
+use crate::report::model::{CumulativeFlow, FlowReport};
+use crate::report::utils::{cumulative_flow, flow_report};
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::transition::model::Transition;
+use crate::transition::service as transition_service;
+
+const DEFAULT_REPORT_WEEKS: i64 = 12;
+
+/// The board with its tasks and their transitions, oldest first.
+async fn get_board_history(
+    board_id: &String,
+    state: &AppState,
+) -> Result<(Board, Vec<Task>, Vec<Transition>), AppError> {
+    let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+    let tasks = task_service::get_tasks(board_id, state.get_tasks_collection()).await?;
+    let transitions =
+        transition_service::get_transitions_of_board(board_id, state.get_transitions_collection())
+            .await?;
+    Ok((board, tasks, transitions))
+}
+
+/// Reported period, the last weeks by default.
+fn get_period(params: &ReportParams, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let to = params.to.unwrap_or(now);
+    let from = params
+        .from
+        .unwrap_or(to - Duration::weeks(DEFAULT_REPORT_WEEKS));
+    (from, to)
+}
+
+// Returns the lead time, cycle time, weekly throughput and work item age of a board
+#[axum_macros::debug_handler]
+pub async fn get_flow_report_handler(
+    path: Path<String>,
+    Query(params): Query<ReportParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting flow report of board {}", board_id);
+
+    async fn report(
+        board_id: &String,
+        params: ReportParams,
+        state: AppState,
+    ) -> Result<FlowReport, AppError> {
+        let (board, tasks, transitions) = get_board_history(board_id, &state).await?;
+        let now = Utc::now();
+        let (from, to) = get_period(&params, now);
+        Ok(flow_report(&board, &tasks, &transitions, from, to, now))
+    }
+
+    match report(&board_id, params, state).await {
+        Ok(report) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(report),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_flow_report_handler] Error getting flow report of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Returns the number of tasks in each column of a board over time
+#[axum_macros::debug_handler]
+pub async fn get_cumulative_flow_handler(
+    path: Path<String>,
+    Query(params): Query<ReportParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting cumulative flow of board {}", board_id);
+
+    async fn report(
+        board_id: &String,
+        params: ReportParams,
+        state: AppState,
+    ) -> Result<CumulativeFlow, AppError> {
+        let (board, tasks, transitions) = get_board_history(board_id, &state).await?;
+        let now = Utc::now();
+        let (from, to) = get_period(&params, now);
+        Ok(cumulative_flow(
+            &board,
+            &tasks,
+            &transitions,
+            from,
+            to,
+            params.interval.unwrap_or_default(),
+            now,
+        ))
+    }
+
+    match report(&board_id, params, state).await {
+        Ok(report) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(report),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_cumulative_flow_handler] Error getting cumulative flow of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
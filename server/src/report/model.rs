@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+/// Flow metrics of a board over a period.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct FlowReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Columns the metrics were computed with, after falling back to the defaults.
+    pub started_column_id: Option<uuid::Uuid>,
+    pub done_column_id: Option<uuid::Uuid>,
+    /// From the creation of a task to its completion, for the tasks done in the period.
+    pub lead_time: DurationStats,
+    /// From the start of the work on a task to its completion, for the tasks done in the period.
+    pub cycle_time: DurationStats,
+    pub throughput: Vec<WeeklyThroughput>,
+    /// Started but not yet done tasks, oldest first.
+    pub work_item_age: Vec<WorkItemAge>,
+}
+
+/// Percentiles of durations in seconds, missing when there is no duration.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Debug, PartialEq, Default)]
+pub struct DurationStats {
+    pub count: usize,
+    pub p50: Option<i64>,
+    pub p85: Option<i64>,
+    pub p95: Option<i64>,
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct WeeklyThroughput {
+    /// Monday of the week, at midnight UTC.
+    pub week_start: DateTime<Utc>,
+    pub done: usize,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct WorkItemAge {
+    pub task_id: uuid::Uuid,
+    pub title: String,
+    pub column_id: Option<uuid::Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub age_seconds: i64,
+}
+
+/// Step of the time series reports.
+#[derive(
+    Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, Display, EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ReportInterval {
+    #[default]
+    Day,
+    /// Weeks start on Monday.
+    Week,
+}
+
+/// Number of tasks in each column of a board over time.
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct CumulativeFlow {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub interval: ReportInterval,
+    pub points: Vec<CumulativeFlowPoint>,
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct CumulativeFlowPoint {
+    /// Start of the interval.
+    pub interval_start: DateTime<Utc>,
+    /// When the tasks were counted, the end of the interval or of the period.
+    pub at: DateTime<Utc>,
+    /// In the order of the columns of the board.
+    pub columns: Vec<ColumnCount>,
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct ColumnCount {
+    pub column_id: uuid::Uuid,
+    pub name: String,
+    pub tasks: usize,
+}
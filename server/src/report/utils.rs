@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+
+use crate::board::model::Board;
+use crate::report::model::{
+    ColumnCount, CumulativeFlow, CumulativeFlowPoint, DurationStats, FlowReport, ReportInterval,
+    WeeklyThroughput, WorkItemAge,
+};
+use crate::task::model::Task;
+use crate::transition::model::{ColumnPeriod, Transition};
+use crate::transition::utils::{column_at, column_periods};
+
+/// Started and done columns of a board, falling back to the defaults when they
+/// aren't configured or no longer belong to the board.
+pub fn flow_columns(board: &Board) -> (Option<uuid::Uuid>, Option<uuid::Uuid>) {
+    let exists = |column_id: &uuid::Uuid| board.column(*column_id).is_some();
+    let started_column_id = board
+        .settings
+        .started_column_id
+        .filter(exists)
+        .or_else(|| board.columns.get(1).or(board.columns.first()).map(|c| c.id));
+    let done_column_id = board.settings.done_column_id.filter(exists).or_else(|| {
+        board
+            .columns
+            .iter()
+            .find(|c| c.done)
+            .or(board.columns.last())
+            .map(|c| c.id)
+    });
+    (started_column_id, done_column_id)
+}
+
+/// Percentiles using the nearest-rank method.
+pub fn duration_stats(mut seconds: Vec<i64>) -> DurationStats {
+    seconds.sort_unstable();
+    let percentile = |p: usize| {
+        let rank = (p * seconds.len()).div_ceil(100);
+        seconds.get(rank.max(1) - 1).copied()
+    };
+    DurationStats {
+        count: seconds.len(),
+        p50: percentile(50),
+        p85: percentile(85),
+        p95: percentile(95),
+    }
+}
+
+/// Monday of the week of `at`, at midnight.
+pub fn week_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    let date = at.date_naive();
+    let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    Utc.from_utc_datetime(&monday.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Start of the interval `at` falls in.
+pub fn interval_start(at: DateTime<Utc>, interval: ReportInterval) -> DateTime<Utc> {
+    match interval {
+        ReportInterval::Day => {
+            Utc.from_utc_datetime(&at.date_naive().and_hms_opt(0, 0, 0).unwrap())
+        }
+        ReportInterval::Week => week_start(at),
+    }
+}
+
+fn interval_duration(interval: ReportInterval) -> Duration {
+    match interval {
+        ReportInterval::Day => Duration::days(1),
+        ReportInterval::Week => Duration::weeks(1),
+    }
+}
+
+/// Groups the transitions by task, keeping their order.
+fn transitions_by_task(transitions: &[Transition]) -> HashMap<uuid::Uuid, Vec<Transition>> {
+    let mut transitions_of_tasks: HashMap<uuid::Uuid, Vec<Transition>> = HashMap::new();
+    for transition in transitions {
+        transitions_of_tasks
+            .entry(transition.task_id)
+            .or_default()
+            .push(transition.clone());
+    }
+    transitions_of_tasks
+}
+
+/// Positions of the columns of a board, the columns that follow a boundary
+/// column sharing its state.
+struct Boundaries {
+    positions: HashMap<uuid::Uuid, usize>,
+    started: Option<usize>,
+    done: Option<usize>,
+}
+
+impl Boundaries {
+    fn new(
+        board: &Board,
+        started_column_id: Option<uuid::Uuid>,
+        done_column_id: Option<uuid::Uuid>,
+    ) -> Self {
+        let positions = board
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.id, i))
+            .collect::<HashMap<uuid::Uuid, usize>>();
+        let position = |column_id: Option<uuid::Uuid>| positions.get(&column_id?).copied();
+        let started = position(started_column_id);
+        let done = position(done_column_id);
+        Self {
+            positions,
+            started,
+            done,
+        }
+    }
+
+    fn reached(&self, period: &ColumnPeriod, boundary: Option<usize>) -> bool {
+        let position = period.column_id.and_then(|c| self.positions.get(&c));
+        matches!((position, boundary), (Some(p), Some(b)) if *p >= b)
+    }
+
+    /// When work on a task started, and when it was finished if it is still done.
+    fn flow_dates(
+        &self,
+        task: &Task,
+        transitions: &[Transition],
+        now: DateTime<Utc>,
+    ) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let periods = column_periods(task, transitions, now);
+        let started_at = periods
+            .iter()
+            .find(|p| self.reached(p, self.started))
+            .map(|p| p.entered_at);
+        let done_at = periods
+            .iter()
+            .rev()
+            .take_while(|p| self.reached(p, self.done))
+            .last()
+            .map(|p| p.entered_at);
+        (started_at, done_at)
+    }
+}
+
+/// Computes the flow metrics of a board between `from` and `to`. `transitions`
+/// are the transitions of the tasks of the board, oldest first.
+pub fn flow_report(
+    board: &Board,
+    tasks: &[Task],
+    transitions: &[Transition],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> FlowReport {
+    let (started_column_id, done_column_id) = flow_columns(board);
+    let boundaries = Boundaries::new(board, started_column_id, done_column_id);
+    let transitions_of_tasks = transitions_by_task(transitions);
+
+    let mut lead_times = Vec::new();
+    let mut cycle_times = Vec::new();
+    let mut throughput = Vec::new();
+    let mut week = week_start(from);
+    while week < to {
+        throughput.push(WeeklyThroughput {
+            week_start: week,
+            done: 0,
+        });
+        week += Duration::weeks(1);
+    }
+    let mut work_item_age = Vec::new();
+
+    for task in tasks {
+        let task_transitions = transitions_of_tasks
+            .get(&task.id)
+            .map(|t| t.as_slice())
+            .unwrap_or_default();
+        match boundaries.flow_dates(task, task_transitions, now) {
+            (started_at, Some(done_at)) => {
+                if done_at < from || done_at >= to {
+                    continue;
+                }
+                lead_times.push((done_at - task.created_at).num_seconds());
+                if let Some(started_at) = started_at {
+                    cycle_times.push((done_at - started_at).num_seconds());
+                }
+                let week = week_start(done_at);
+                if let Some(bucket) = throughput.iter_mut().find(|w| w.week_start == week) {
+                    bucket.done += 1;
+                }
+            }
+            (Some(started_at), None) if task.archived_at.is_none() => {
+                work_item_age.push(WorkItemAge {
+                    task_id: task.id,
+                    title: task.title.clone(),
+                    column_id: task.column_id,
+                    started_at,
+                    age_seconds: (now - started_at).num_seconds(),
+                })
+            }
+            _ => {}
+        }
+    }
+    work_item_age.sort_by_key(|a| a.started_at);
+
+    FlowReport {
+        from,
+        to,
+        started_column_id,
+        done_column_id,
+        lead_time: duration_stats(lead_times),
+        cycle_time: duration_stats(cycle_times),
+        throughput,
+        work_item_age,
+    }
+}
+
+/// Counts the tasks in each column of a board at the end of every interval
+/// between `from` and `to`, replaying the transitions of the tasks.
+/// `transitions` are expected oldest first.
+pub fn cumulative_flow(
+    board: &Board,
+    tasks: &[Task],
+    transitions: &[Transition],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    interval: ReportInterval,
+    now: DateTime<Utc>,
+) -> CumulativeFlow {
+    let transitions_of_tasks = transitions_by_task(transitions);
+    let periods_of_tasks = tasks
+        .iter()
+        .map(|task| {
+            let task_transitions = transitions_of_tasks
+                .get(&task.id)
+                .map(|t| t.as_slice())
+                .unwrap_or_default();
+            (task, column_periods(task, task_transitions, now))
+        })
+        .collect::<Vec<(&Task, Vec<ColumnPeriod>)>>();
+
+    let mut points = Vec::new();
+    let mut start = interval_start(from, interval);
+    while start < to {
+        let at = (start + interval_duration(interval)).min(to);
+        let columns = board
+            .columns
+            .iter()
+            .map(|column| ColumnCount {
+                column_id: column.id,
+                name: column.name.clone(),
+                tasks: periods_of_tasks
+                    .iter()
+                    .filter(|(task, periods)| {
+                        task.archived_at.is_none_or(|archived_at| archived_at > at)
+                            && column_at(periods, at) == Some(Some(column.id))
+                    })
+                    .count(),
+            })
+            .collect();
+        points.push(CumulativeFlowPoint {
+            interval_start: start,
+            at,
+            columns,
+        });
+        start += interval_duration(interval);
+    }
+
+    CumulativeFlow {
+        from,
+        to,
+        interval,
+        points,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use uuid::Uuid;
+
+    use crate::board::model::{Board, BoardSettings};
+    use crate::fixtures::{mock_board, mock_column, mock_task};
+    use crate::report::model::ReportInterval;
+    use crate::report::utils::{cumulative_flow, duration_stats, flow_columns, flow_report};
+    use crate::task::model::Task;
+    use crate::transition::model::Transition;
+
+    fn flow_board() -> Board {
+        mock_board(vec![
+            mock_column("todo", false),
+            mock_column("doing", false),
+            mock_column("review", false),
+            mock_column("done", true),
+        ])
+    }
+
+    fn created_task(board: &Board, created_at: DateTime<Utc>) -> Task {
+        Task {
+            created_at,
+            ..mock_task(board.id, Some(board.columns[0].id))
+        }
+    }
+
+    fn transition(task: &Task, from: Uuid, to: Uuid, at: DateTime<Utc>) -> Transition {
+        Transition {
+            id: Uuid::new_v4(),
+            task_id: task.id,
+            board_id: task.board_id,
+            from_column_id: Some(from),
+            to_column_id: Some(to),
+            at,
+        }
+    }
+
+    #[test]
+    fn it_computes_percentiles_of_durations() {
+        let stats = duration_stats((1..=20).rev().collect());
+        assert_eq!(stats.count, 20);
+        assert_eq!(stats.p50, Some(10));
+        assert_eq!(stats.p85, Some(17));
+        assert_eq!(stats.p95, Some(19));
+        assert_eq!(duration_stats(Vec::new()).p50, None);
+    }
+
+    #[test]
+    fn it_picks_the_started_and_done_columns() {
+        let board = flow_board();
+        assert_eq!(
+            flow_columns(&board),
+            (Some(board.columns[1].id), Some(board.columns[3].id))
+        );
+
+        let configured = Board {
+            settings: BoardSettings {
+                started_column_id: Some(board.columns[2].id),
+                done_column_id: Some(Uuid::new_v4()),
+                ..Default::default()
+            },
+            ..board.clone()
+        };
+        assert_eq!(
+            flow_columns(&configured),
+            (Some(board.columns[2].id), Some(board.columns[3].id))
+        );
+    }
+
+    #[test]
+    fn it_reports_cycle_and_lead_times() {
+        let board = flow_board();
+        let [todo, doing, review, done] = [0, 1, 2, 3].map(|i| board.columns[i].id);
+        // Monday
+        let start = Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 0).unwrap();
+        let hours = |h: i64| start + Duration::hours(h);
+
+        let mut finished = created_task(&board, start);
+        finished.column_id = Some(done);
+        // Reopened once, only the last completion counts
+        let mut reopened = created_task(&board, hours(24));
+        reopened.column_id = Some(done);
+        let mut in_progress = created_task(&board, hours(24));
+        in_progress.column_id = Some(review);
+        let waiting = created_task(&board, hours(48));
+
+        let transitions = vec![
+            transition(&finished, todo, doing, hours(2)),
+            transition(&finished, doing, done, hours(10)),
+            transition(&reopened, todo, done, hours(26)),
+            transition(&reopened, done, doing, hours(30)),
+            transition(&in_progress, todo, review, hours(30)),
+            transition(&reopened, doing, done, hours(24 * 8)),
+        ];
+        let tasks = vec![finished, reopened, in_progress, waiting];
+
+        let now = hours(24 * 10);
+        let report = flow_report(&board, &tasks, &transitions, start, now, now);
+
+        assert_eq!(report.lead_time.count, 2);
+        assert_eq!(report.lead_time.p50, Some(10 * 3600));
+        assert_eq!(report.lead_time.p95, Some(7 * 24 * 3600));
+        assert_eq!(report.cycle_time.p50, Some(8 * 3600));
+        assert_eq!(report.cycle_time.p95, Some((24 * 8 - 26) * 3600));
+        assert_eq!(
+            report
+                .throughput
+                .iter()
+                .map(|w| w.done)
+                .collect::<Vec<usize>>(),
+            vec![1, 1]
+        );
+        assert_eq!(
+            report.throughput[1].week_start,
+            start - Duration::hours(9) + Duration::weeks(1)
+        );
+        assert_eq!(report.work_item_age.len(), 1);
+        assert_eq!(report.work_item_age[0].task_id, tasks[2].id);
+        assert_eq!(report.work_item_age[0].age_seconds, (24 * 10 - 30) * 3600);
+    }
+
+    #[test]
+    fn it_counts_tasks_per_column_over_time() {
+        let board = flow_board();
+        let [todo, doing, _, done] = [0, 1, 2, 3].map(|i| board.columns[i].id);
+        let start = Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 0).unwrap();
+        let days = |d: i64| start + Duration::days(d);
+
+        let mut moved = created_task(&board, start);
+        moved.column_id = Some(done);
+        let mut archived = created_task(&board, start);
+        archived.archived_at = Some(days(2));
+        let late = created_task(&board, days(2));
+
+        let transitions = vec![
+            transition(&moved, todo, doing, days(1)),
+            transition(&moved, doing, done, days(3)),
+        ];
+        let tasks = vec![moved, archived, late];
+
+        let now = days(4);
+        let flow = cumulative_flow(
+            &board,
+            &tasks,
+            &transitions,
+            start,
+            now,
+            ReportInterval::Day,
+            now,
+        );
+
+        let counts = flow
+            .points
+            .iter()
+            .map(|p| p.columns.iter().map(|c| c.tasks).collect::<Vec<usize>>())
+            .collect::<Vec<Vec<usize>>>();
+        assert_eq!(
+            counts,
+            vec![
+                vec![2, 0, 0, 0],
+                vec![1, 1, 0, 0],
+                vec![1, 1, 0, 0],
+                vec![1, 0, 0, 1],
+                vec![1, 0, 0, 1],
+            ]
+        );
+        assert_eq!(flow.points[0].interval_start, start - Duration::hours(9));
+        assert_eq!(flow.points[4].at, now);
+    }
+}
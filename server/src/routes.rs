@@ -1,15 +1,71 @@
-use axum::routing::{get, post};
+use axum::extract::DefaultBodyLimit;
+use axum::handler::Handler;
+use axum::routing::{get, patch, post, put};
 use axum::{routing, Router};
 
+use crate::activity::handlers::{get_board_activity_handler, get_task_history_handler};
+use crate::attachment::handlers::{
+    attachment_delete_handler, attachment_download_handler, attachment_upload_handler,
+    get_attachments_handler,
+};
+use crate::automation::handlers::{
+    automation_rule_create_handler, automation_rule_delete_handler, automation_rule_update_handler,
+    get_automation_rules_handler, get_automation_runs_handler,
+};
 use crate::board::handlers::{
+    board_archive_column_handler, board_archive_handler, board_clone_handler,
     board_create_column_handler, board_create_handler, board_delete_column_handler,
-    board_delete_handler, board_update_handler, get_board_handler, get_boards_handler,
+    board_delete_handler, board_reorder_columns_handler, board_unarchive_column_handler,
+    board_unarchive_handler, board_update_column_handler, board_update_handler, get_board_handler,
+    get_boards_handler,
+};
+use crate::calendar::handlers::{
+    calendar_feed_reset_handler, get_board_calendar_handler, get_calendar_feed_handler,
+    get_user_calendar_handler,
+};
+use crate::chat::handlers::{
+    chat_webhook_delete_handler, chat_webhook_set_handler, get_chat_webhook_handler,
+};
+use crate::comment::handlers::{
+    comment_create_handler, comment_delete_handler, comment_update_handler, get_comments_handler,
 };
 use crate::config::AppState;
+use crate::custom_field::handlers::{
+    custom_field_create_handler, custom_field_delete_handler, custom_field_update_handler,
+    get_custom_fields_handler,
+};
+use crate::notification::handlers::{
+    get_notifications_handler, notification_read_handler, notification_unread_handler,
+    notifications_read_all_handler,
+};
+use crate::reminder::handlers::{
+    get_reminder_preferences_handler, reminder_preferences_update_handler,
+};
+use crate::report::handlers::{get_cumulative_flow_handler, get_flow_report_handler};
+use crate::sprint::handlers::{
+    get_sprint_burndown_handler, get_sprint_handler, get_sprints_handler, sprint_add_tasks_handler,
+    sprint_close_handler, sprint_create_handler, sprint_delete_handler, sprint_remove_task_handler,
+    sprint_update_handler,
+};
+use crate::swimlane::handlers::{
+    get_swimlanes_handler, swimlane_create_handler, swimlane_delete_handler,
+    swimlane_derive_handler, swimlane_reorder_handler,
+};
 use crate::task::handlers::{
-    get_board_tasks_handler, get_task_handler, get_tasks_handler, task_create_handler,
-    task_delete_handler, task_update_handler,
+    get_board_tasks_handler, get_task_children_handler, get_task_dependencies_handler,
+    get_task_handler, get_tasks_handler, task_add_dependency_handler, task_archive_handler,
+    task_create_handler, task_delete_handler, task_remove_dependency_handler,
+    task_unarchive_handler, task_unwatch_handler, task_update_handler, task_watch_handler,
+};
+use crate::task_template::handlers::{
+    get_task_template_handler, get_task_templates_handler, task_template_create_handler,
+    task_template_delete_handler, task_template_update_handler,
 };
+use crate::template::handlers::{
+    get_templates_handler, template_delete_handler, template_save_handler,
+};
+use crate::transition::handlers::get_task_timeline_handler;
+use crate::trash::handlers::{get_trash_handler, trash_purge_handler, trash_restore_handler};
 
 pub fn get_routes() -> Router<AppState> {
     let api_routes: Router<AppState> = Router::new()
@@ -23,6 +79,42 @@ pub fn get_routes() -> Router<AppState> {
             "/tasks/:task_id",
             get(get_task_handler).delete(task_delete_handler),
         )
+        .route("/tasks/:task_id/children", get(get_task_children_handler))
+        .route("/tasks/:task_id/history", get(get_task_history_handler))
+        .route("/tasks/:task_id/timeline", get(get_task_timeline_handler))
+        .route("/tasks/:task_id/archive", post(task_archive_handler))
+        .route("/tasks/:task_id/unarchive", post(task_unarchive_handler))
+        .route(
+            "/tasks/:task_id/dependencies",
+            get(get_task_dependencies_handler).post(task_add_dependency_handler),
+        )
+        .route(
+            "/tasks/:task_id/dependencies/:blocker_id",
+            routing::delete(task_remove_dependency_handler),
+        )
+        .route(
+            "/tasks/:task_id/watchers",
+            post(task_watch_handler).delete(task_unwatch_handler),
+        )
+        .route(
+            "/tasks/:task_id/comments",
+            get(get_comments_handler).post(comment_create_handler),
+        )
+        .route(
+            "/tasks/:task_id/comments/:comment_id",
+            put(comment_update_handler).delete(comment_delete_handler),
+        )
+        .route(
+            "/tasks/:task_id/attachments",
+            get(get_attachments_handler).post(
+                // the size of attachments is checked against the configured limit while storing them
+                attachment_upload_handler.layer(DefaultBodyLimit::disable()),
+            ),
+        )
+        .route(
+            "/tasks/:task_id/attachments/:attachment_id",
+            get(attachment_download_handler).delete(attachment_delete_handler),
+        )
         .route(
             "/boards",
             get(get_boards_handler)
@@ -34,14 +126,151 @@ pub fn get_routes() -> Router<AppState> {
             get(get_board_handler).delete(board_delete_handler),
         )
         .route("/boards/:board_id/tasks", get(get_board_tasks_handler))
+        .route("/boards/:board_id/clone", post(board_clone_handler))
+        .route("/boards/:board_id/templates", post(template_save_handler))
+        .route(
+            "/boards/:board_id/task-templates",
+            get(get_task_templates_handler).post(task_template_create_handler),
+        )
+        .route(
+            "/task-templates/:template_id",
+            get(get_task_template_handler)
+                .patch(task_template_update_handler)
+                .delete(task_template_delete_handler),
+        )
+        .route(
+            "/boards/:board_id/automations",
+            get(get_automation_rules_handler).post(automation_rule_create_handler),
+        )
+        .route(
+            "/boards/:board_id/automations/runs",
+            get(get_automation_runs_handler),
+        )
+        .route(
+            "/automations/:rule_id",
+            patch(automation_rule_update_handler).delete(automation_rule_delete_handler),
+        )
+        .route(
+            "/boards/:board_id/chat-webhook",
+            get(get_chat_webhook_handler)
+                .put(chat_webhook_set_handler)
+                .delete(chat_webhook_delete_handler),
+        )
+        .route(
+            "/boards/:board_id/calendar.ics",
+            get(get_board_calendar_handler),
+        )
+        .route(
+            "/calendar/:token/calendar.ics",
+            get(get_user_calendar_handler),
+        )
+        .route("/templates", get(get_templates_handler))
+        .route(
+            "/templates/:template_id",
+            routing::delete(template_delete_handler),
+        )
+        .route(
+            "/boards/:board_id/fields",
+            get(get_custom_fields_handler).post(custom_field_create_handler),
+        )
+        .route(
+            "/boards/:board_id/fields/:field_id",
+            put(custom_field_update_handler).delete(custom_field_delete_handler),
+        )
         .route(
             "/boards/:board_id/columns",
             post(board_create_column_handler),
         )
+        .route("/boards/:board_id/archive", post(board_archive_handler))
+        .route("/boards/:board_id/unarchive", post(board_unarchive_handler))
+        .route(
+            "/boards/:board_id/columns/:column_id/archive",
+            post(board_archive_column_handler),
+        )
+        .route(
+            "/boards/:board_id/columns/:column_id/unarchive",
+            post(board_unarchive_column_handler),
+        )
+        .route(
+            "/boards/:board_id/columns/reorder",
+            post(board_reorder_columns_handler),
+        )
         .route(
             "/boards/:board_id/columns/:column_id",
-            routing::delete(board_delete_column_handler),
-        );
+            patch(board_update_column_handler).delete(board_delete_column_handler),
+        )
+        .route(
+            "/boards/:board_id/swimlanes",
+            get(get_swimlanes_handler).post(swimlane_create_handler),
+        )
+        .route(
+            "/boards/:board_id/swimlanes/reorder",
+            post(swimlane_reorder_handler),
+        )
+        .route(
+            "/boards/:board_id/swimlanes/derive",
+            post(swimlane_derive_handler),
+        )
+        .route(
+            "/boards/:board_id/swimlanes/:swimlane_id",
+            routing::delete(swimlane_delete_handler),
+        )
+        .route("/boards/:board_id/trash", get(get_trash_handler))
+        .route(
+            "/boards/:board_id/activity",
+            get(get_board_activity_handler),
+        )
+        .route(
+            "/boards/:board_id/reports/flow",
+            get(get_flow_report_handler),
+        )
+        .route(
+            "/boards/:board_id/reports/cfd",
+            get(get_cumulative_flow_handler),
+        )
+        .route(
+            "/boards/:board_id/sprints",
+            get(get_sprints_handler).post(sprint_create_handler),
+        )
+        .route(
+            "/sprints/:sprint_id",
+            get(get_sprint_handler)
+                .patch(sprint_update_handler)
+                .delete(sprint_delete_handler),
+        )
+        .route("/sprints/:sprint_id/tasks", post(sprint_add_tasks_handler))
+        .route(
+            "/sprints/:sprint_id/tasks/:task_id",
+            routing::delete(sprint_remove_task_handler),
+        )
+        .route("/sprints/:sprint_id/close", post(sprint_close_handler))
+        .route(
+            "/sprints/:sprint_id/burndown",
+            get(get_sprint_burndown_handler),
+        )
+        .route("/trash/:item_id", routing::delete(trash_purge_handler))
+        .route("/trash/:item_id/restore", post(trash_restore_handler));
+
+    let me_routes: Router<AppState> = Router::new()
+        .route("/notifications", get(get_notifications_handler))
+        .route(
+            "/notifications/read-all",
+            post(notifications_read_all_handler),
+        )
+        .route(
+            "/notifications/:notification_id/read",
+            post(notification_read_handler),
+        )
+        .route(
+            "/notifications/:notification_id/unread",
+            post(notification_unread_handler),
+        )
+        .route(
+            "/reminders",
+            get(get_reminder_preferences_handler).put(reminder_preferences_update_handler),
+        )
+        .route("/calendar", get(get_calendar_feed_handler))
+        .route("/calendar/reset", post(calendar_feed_reset_handler));
 
-    Router::new().nest("/api", api_routes)
+    Router::new().nest("/api", api_routes.nest("/me", me_routes))
 }
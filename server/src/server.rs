@@ -10,18 +10,52 @@ use tower_http::cors::AllowOrigin;
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 use tower_http::{cors::CorsLayer, timeout::TimeoutLayer};
 use tower_http::{LatencyUnit, ServiceBuilderExt};
+use tracing::error;
 
-use crate::config::AppState;
+use crate::attachment::store as blob_store;
+use crate::automation::jobs::spawn_scanner;
+use crate::config::{AppState, AttachmentLimits};
 use crate::db::DB;
 use crate::error::Result;
+use crate::reminder::jobs::spawn_digests;
+use crate::reminder::mailer::Mailer;
 use crate::routes::get_routes;
+use crate::task_template::jobs::spawn_scheduler;
+use crate::trash::jobs::spawn_purge;
+use crate::user::extractor::USER_HEADER;
 use crate::EnvVars;
 
+pub async fn state(env_vars: EnvVars) -> Result<AppState> {
+    Ok(AppState {
+        blob_store: blob_store::from_env(&env_vars),
+        attachment_limits: AttachmentLimits {
+            max_bytes: env_vars.attachment_max_bytes,
+            allowed_types: env_vars.attachment_allowed_types.clone(),
+        },
+        public_url: env_vars.public_url.clone(),
+        client: DB::init(env_vars).await?.client,
+    })
+}
+
 pub async fn app(env_vars: EnvVars) -> Result<Router> {
+    let trash_retention = chrono::Duration::days(env_vars.trash_retention_days);
+    let smtp = env_vars.smtp.clone();
     // Build our database for holding the key/value pairs
-    let state = AppState {
-        client: DB::init(env_vars).await?.client,
-    };
+    let state = state(env_vars).await?;
+    spawn_purge(state.clone(), trash_retention);
+    spawn_scheduler(state.clone());
+    spawn_scanner(state.clone());
+    if !smtp.host.is_empty() {
+        match Mailer::new(&smtp) {
+            Ok(mailer) => {
+                spawn_digests(state.clone(), mailer, state.public_url.clone());
+            }
+            Err(e) => error!(
+                "ERROR [app] Reminders are disabled, invalid SMTP settings: {:?}",
+                e
+            ),
+        }
+    }
 
     let sensitive_headers: Arc<[_]> = vec![header::AUTHORIZATION, header::COOKIE].into();
     let cors = CorsLayer::new()
@@ -30,12 +64,16 @@ pub async fn app(env_vars: EnvVars) -> Result<Router> {
             Method::GET,
             Method::POST,
             Method::PUT,
+            Method::PATCH,
             Method::DELETE,
             Method::OPTIONS,
         ])
         // allow requests from any origin
         .allow_origin(AllowOrigin::any())
-        .allow_headers([header::CONTENT_TYPE]);
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::HeaderName::from_static(USER_HEADER),
+        ]);
 
     // Build our middleware stack
     let middleware = ServiceBuilder::new()
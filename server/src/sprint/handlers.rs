@@ -0,0 +1,594 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::Utc;
+use tracing::{debug, error};
+
+use crate::activity::service as activity_service;
+use crate::activity::utils::task_activities;
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::dto::{
+    CloseSprintRequest, CreateSprintRequest, Response, SprintTasksRequest, UpdateSprintRequest,
+};
+use crate::error::{AppError, SprintRepoError};
+use crate::sprint::model::{Sprint, SprintBurndown};
+use crate::sprint::service;
+use crate::sprint::utils::{split_finished, sprint_burndown, validate_sprint};
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::transition::service as transition_service;
+use crate::user::extractor::CurrentUser;
+
+/// Moves tasks to a sprint, or back to the backlog, and records the change.
+async fn move_tasks(
+    tasks: Vec<Task>,
+    sprint_id: Option<uuid::Uuid>,
+    actor: Option<String>,
+    state: &AppState,
+) -> Result<(), AppError> {
+    let ids = tasks.iter().map(|t| t.id).collect::<Vec<uuid::Uuid>>();
+    task_service::set_sprint(&ids, sprint_id, state.get_tasks_collection()).await?;
+    record_moves(&tasks, sprint_id, actor, state).await;
+    Ok(())
+}
+
+async fn record_moves(
+    tasks: &[Task],
+    sprint_id: Option<uuid::Uuid>,
+    actor: Option<String>,
+    state: &AppState,
+) {
+    let after = tasks
+        .iter()
+        .map(|t| Task {
+            sprint_id,
+            ..t.clone()
+        })
+        .collect::<Vec<Task>>();
+    activity_service::record(
+        task_activities(tasks, &after, actor.as_deref()),
+        state.get_activity_collection(),
+    )
+    .await;
+}
+
+fn check_open(sprint: &Sprint) -> Result<(), AppError> {
+    match sprint.closed_at {
+        Some(_) => Err(AppError::Conflict(format!(
+            "sprint {} is closed",
+            sprint.id
+        ))),
+        None => Ok(()),
+    }
+}
+
+// Returns the sprints of a board, by start date
+#[axum_macros::debug_handler]
+pub async fn get_sprints_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting sprints of board {}", board_id);
+    match service::get_sprints(&board_id, state.get_sprints_collection()).await {
+        Ok(sprints) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(sprints),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_sprints_handler] Error getting sprints of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Returns a sprint with the outcome of its closing
+#[axum_macros::debug_handler]
+pub async fn get_sprint_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let sprint_id = path.0;
+    debug!("Getting sprint {}", sprint_id);
+    match service::get_sprint(&sprint_id, state.get_sprints_collection()).await {
+        Ok(sprint) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(sprint),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_sprint_handler] Error getting sprint {}: {:?}",
+                sprint_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Creates a new sprint on a board
+#[axum_macros::debug_handler]
+pub async fn sprint_create_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    Json(req): Json<CreateSprintRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[sprint_create_handler] Creating sprint on board {}",
+        board_id
+    );
+
+    async fn validate_and_create(
+        board_id: &String,
+        req: CreateSprintRequest,
+        state: AppState,
+    ) -> Result<Sprint, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let sprint = Sprint::from_request(board.id, req);
+        validate_sprint(&sprint)?;
+        service::create(&sprint, state.get_sprints_collection()).await?;
+        Ok(sprint)
+    }
+
+    match validate_and_create(&board_id, req, state).await {
+        Ok(sprint) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(sprint),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[sprint_create_handler] Error creating sprint on board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Updates the name, goal or dates of a sprint
+#[axum_macros::debug_handler]
+pub async fn sprint_update_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    Json(req): Json<UpdateSprintRequest>,
+) -> impl IntoResponse {
+    let sprint_id = path.0;
+    debug!("[sprint_update_handler] Updating sprint {}", sprint_id);
+
+    async fn validate_and_update(
+        sprint_id: &String,
+        req: UpdateSprintRequest,
+        state: AppState,
+    ) -> Result<Sprint, AppError> {
+        let sprint = service::get_sprint(sprint_id, state.get_sprints_collection())
+            .await?
+            .with_update_request(req);
+        validate_sprint(&sprint)?;
+        service::update(&sprint, state.get_sprints_collection()).await?;
+        Ok(sprint)
+    }
+
+    match validate_and_update(&sprint_id, req, state).await {
+        Ok(sprint) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(sprint),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[sprint_update_handler] Error updating sprint {}: {:?}",
+                sprint_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes a sprint, its tasks go back to the backlog
+#[axum_macros::debug_handler]
+pub async fn sprint_delete_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    let sprint_id = path.0;
+    debug!("[sprint_delete_handler] Deleting sprint {}", sprint_id);
+
+    async fn delete(
+        sprint_id: &String,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        let sprint = service::get_sprint(sprint_id, state.get_sprints_collection()).await?;
+        let tasks =
+            task_service::get_tasks_of_sprint(sprint_id, state.get_tasks_collection()).await?;
+        move_tasks(tasks, None, actor, &state).await?;
+        service::delete(&sprint.id.to_string(), state.get_sprints_collection()).await
+    }
+
+    match delete(&sprint_id, user.map(|u| u.0), state).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(sprint_id),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[sprint_delete_handler] Error deleting sprint {}: {:?}",
+                sprint_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Adds tasks of the board of a sprint to the sprint, returns the tasks of the sprint
+#[axum_macros::debug_handler]
+pub async fn sprint_add_tasks_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<SprintTasksRequest>,
+) -> impl IntoResponse {
+    let sprint_id = path.0;
+    debug!(
+        "[sprint_add_tasks_handler] Adding tasks {:?} to sprint {}",
+        req.task_ids, sprint_id
+    );
+
+    async fn add(
+        sprint_id: &String,
+        task_ids: Vec<uuid::Uuid>,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Vec<Task>, AppError> {
+        let sprint = service::get_sprint(sprint_id, state.get_sprints_collection()).await?;
+        check_open(&sprint)?;
+        let tasks = task_service::get_tasks_by_ids(&task_ids, state.get_tasks_collection()).await?;
+        if let Some(id) = task_ids
+            .iter()
+            .find(|id| !tasks.iter().any(|t| t.id == **id))
+        {
+            return Err(SprintRepoError::InvalidSprint(format!("task {} not found", id)).into());
+        }
+        if let Some(task) = tasks.iter().find(|t| t.board_id != sprint.board_id) {
+            return Err(SprintRepoError::InvalidSprint(format!(
+                "task {} is not on the board of the sprint",
+                task.id
+            ))
+            .into());
+        }
+        let moved = tasks
+            .into_iter()
+            .filter(|t| t.sprint_id != Some(sprint.id))
+            .collect::<Vec<Task>>();
+        move_tasks(moved, Some(sprint.id), actor, &state).await?;
+        task_service::get_tasks_of_sprint(sprint_id, state.get_tasks_collection()).await
+    }
+
+    match add(&sprint_id, req.task_ids, user.map(|u| u.0), state).await {
+        Ok(tasks) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(tasks),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[sprint_add_tasks_handler] Error adding tasks to sprint {}: {:?}",
+                sprint_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Moves a task of a sprint back to the backlog
+#[axum_macros::debug_handler]
+pub async fn sprint_remove_task_handler(
+    Path((sprint_id, task_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    debug!(
+        "[sprint_remove_task_handler] Removing task {} from sprint {}",
+        task_id, sprint_id
+    );
+
+    async fn remove(
+        sprint_id: &String,
+        task_id: &String,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Task, AppError> {
+        let sprint = service::get_sprint(sprint_id, state.get_sprints_collection()).await?;
+        check_open(&sprint)?;
+        let task = task_service::get_task(task_id, state.get_tasks_collection()).await?;
+        if task.sprint_id != Some(sprint.id) {
+            return Err(SprintRepoError::InvalidSprint(format!(
+                "task {} is not in the sprint",
+                task_id
+            ))
+            .into());
+        }
+        move_tasks(vec![task.clone()], None, actor, &state).await?;
+        Ok(Task {
+            sprint_id: None,
+            ..task
+        })
+    }
+
+    match remove(&sprint_id, &task_id, user.map(|u| u.0), state).await {
+        Ok(task) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(task),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[sprint_remove_task_handler] Error removing task {} from sprint {}: {:?}",
+                task_id,
+                sprint_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Closes a sprint, its unfinished tasks are carried over to another sprint or
+// go back to the backlog
+#[axum_macros::debug_handler]
+pub async fn sprint_close_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CloseSprintRequest>,
+) -> impl IntoResponse {
+    let sprint_id = path.0;
+    debug!("[sprint_close_handler] Closing sprint {}", sprint_id);
+
+    async fn close(
+        sprint_id: &String,
+        carry_over_to: Option<uuid::Uuid>,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Sprint, AppError> {
+        let sprint = service::get_sprint(sprint_id, state.get_sprints_collection()).await?;
+        check_open(&sprint)?;
+        if let Some(next_id) = carry_over_to {
+            let next =
+                service::get_sprint(&next_id.to_string(), state.get_sprints_collection()).await?;
+            if next.id == sprint.id || next.board_id != sprint.board_id {
+                return Err(SprintRepoError::InvalidSprint(String::from(
+                    "unfinished tasks can only be carried over to another sprint of the board",
+                ))
+                .into());
+            }
+            check_open(&next)?;
+        }
+        let board =
+            board_service::get_board(&sprint.board_id.to_string(), state.get_boards_collection())
+                .await?;
+        let tasks =
+            task_service::get_tasks_of_sprint(sprint_id, state.get_tasks_collection()).await?;
+        let (completed, carried_over) = split_finished(&tasks, &board.done_column_ids());
+        let unfinished = tasks
+            .into_iter()
+            .filter(|t| carried_over.contains(&t.id))
+            .collect::<Vec<Task>>();
+        let closed = Sprint {
+            closed_at: Some(Utc::now()),
+            completed_task_ids: completed,
+            carried_over_task_ids: carried_over,
+            ..sprint
+        };
+        service::close(&closed, carry_over_to, &state).await?;
+        record_moves(&unfinished, carry_over_to, actor, &state).await;
+        Ok(closed)
+    }
+
+    match close(&sprint_id, req.carry_over_to, user.map(|u| u.0), state).await {
+        Ok(sprint) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(sprint),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[sprint_close_handler] Error closing sprint {}: {:?}",
+                sprint_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Returns the burndown and burnup series of a sprint, by task count and by estimate
+#[axum_macros::debug_handler]
+pub async fn get_sprint_burndown_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let sprint_id = path.0;
+    debug!("Getting burndown of sprint {}", sprint_id);
+
+    async fn burndown(sprint_id: &String, state: AppState) -> Result<SprintBurndown, AppError> {
+        let sprint = service::get_sprint(sprint_id, state.get_sprints_collection()).await?;
+        let board_id = sprint.board_id.to_string();
+        let board = board_service::get_board(&board_id, state.get_boards_collection()).await?;
+        // the unfinished tasks of a closed sprint have left it
+        let tasks = match sprint.closed_at {
+            Some(_) => {
+                let ids = [
+                    sprint.completed_task_ids.as_slice(),
+                    sprint.carried_over_task_ids.as_slice(),
+                ]
+                .concat();
+                task_service::get_tasks_by_ids(&ids, state.get_tasks_collection()).await?
+            }
+            None => {
+                task_service::get_tasks_of_sprint(sprint_id, state.get_tasks_collection()).await?
+            }
+        };
+        let transitions = transition_service::get_transitions_of_board(
+            &board_id,
+            state.get_transitions_collection(),
+        )
+        .await?;
+        Ok(sprint_burndown(
+            &sprint,
+            &tasks,
+            &transitions,
+            &board.done_column_ids(),
+            Utc::now(),
+        ))
+    }
+
+    match burndown(&sprint_id, state).await {
+        Ok(burndown) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(burndown),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_sprint_burndown_handler] Error getting burndown of sprint {}: {:?}",
+                sprint_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result, SprintRepoError};
+use crate::sprint::model::Sprint;
+use crate::util::get_uuid_list;
+
+pub async fn parse_sprints(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<Sprint>> {
+    let mut result: Vec<Sprint> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_sprint(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_sprint(doc: &bson::document::Document) -> Result<Sprint> {
+    let id = doc.get_str("id")?;
+    let board_id = doc.get_str("board_id")?;
+    let name = doc.get_str("name")?;
+    let goal = doc.get_str("goal").ok();
+    let start = doc.get_datetime("start")?;
+    let end = doc.get_datetime("end")?;
+    let created_at = doc.get_datetime("created_at")?;
+    let optional_datetime = |field: &str| {
+        doc.get_datetime(field)
+            .ok()
+            .map(|v| chrono::DateTime::from(*v))
+    };
+    match (Uuid::from_str(id), Uuid::from_str(board_id)) {
+        (Ok(sprint_uuid), Ok(board_uuid)) => Ok(Sprint {
+            id: sprint_uuid,
+            board_id: board_uuid,
+            name: name.to_owned(),
+            goal: goal.map(|g| g.to_owned()),
+            start: chrono::DateTime::from(*start),
+            end: chrono::DateTime::from(*end),
+            created_at: chrono::DateTime::from(*created_at),
+            updated_at: optional_datetime("updated_at"),
+            closed_at: optional_datetime("closed_at"),
+            completed_task_ids: get_uuid_list(doc, "completed_task_ids"),
+            carried_over_task_ids: get_uuid_list(doc, "carried_over_task_ids"),
+        }),
+        _ => Err(AppError::SprintRepo(SprintRepoError::DecodeError(format!(
+            "Sprint doesnt have id or board_id {}",
+            id
+        )))),
+    }
+}
+
+pub fn sprint_to_doc(sprint: &Sprint) -> bson::document::Document {
+    let ids = |ids: &[Uuid]| ids.iter().map(|id| id.to_string()).collect::<Vec<String>>();
+    doc! {
+        "id" : sprint.id.to_string(),
+        "board_id" : sprint.board_id.to_string(),
+        "name" : sprint.name.clone(),
+        "goal" : sprint.goal.clone(),
+        "start" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(sprint.start),
+        "end" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(sprint.end),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(sprint.created_at),
+        "updated_at" : sprint.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+        "closed_at" : sprint.closed_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+        "completed_task_ids" : ids(&sprint.completed_task_ids),
+        "carried_over_task_ids" : ids(&sprint.carried_over_task_ids),
+    }
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::dto::{CreateSprintRequest, UpdateSprintRequest};
+
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Sprint {
+    pub id: uuid::Uuid,
+    pub board_id: uuid::Uuid,
+    pub name: String,
+    pub goal: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Set once the sprint is closed, managed through the close endpoint.
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Tasks that were done when the sprint got closed.
+    #[serde(default)]
+    pub completed_task_ids: Vec<uuid::Uuid>,
+    /// Tasks that were unfinished when the sprint got closed.
+    #[serde(default)]
+    pub carried_over_task_ids: Vec<uuid::Uuid>,
+}
+
+impl Sprint {
+    pub fn from_request(board_id: uuid::Uuid, r: CreateSprintRequest) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            board_id,
+            name: r.name.trim().to_owned(),
+            goal: r.goal,
+            start: r.start,
+            end: r.end,
+            created_at: Utc::now(),
+            updated_at: None,
+            closed_at: None,
+            completed_task_ids: Vec::new(),
+            carried_over_task_ids: Vec::new(),
+        }
+    }
+
+    pub fn with_update_request(self, r: UpdateSprintRequest) -> Self {
+        Self {
+            name: r.name.map(|n| n.trim().to_owned()).unwrap_or(self.name),
+            goal: r.goal.or(self.goal),
+            start: r.start.unwrap_or(self.start),
+            end: r.end.unwrap_or(self.end),
+            updated_at: Some(Utc::now()),
+            ..self
+        }
+    }
+}
+
+/// Progress of a sprint over its days, by number of tasks and by estimate.
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct SprintBurndown {
+    pub sprint_id: uuid::Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub points: Vec<BurndownPoint>,
+}
+
+/// State of a sprint at a given time. The actual values are missing for the
+/// times still to come.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct BurndownPoint {
+    pub at: DateTime<Utc>,
+    /// Remaining work if it was burnt at a constant pace.
+    pub ideal_remaining_tasks: f64,
+    pub ideal_remaining_estimate: f64,
+    pub scope_tasks: Option<usize>,
+    pub done_tasks: Option<usize>,
+    pub remaining_tasks: Option<usize>,
+    pub scope_estimate: Option<f64>,
+    pub done_estimate: Option<f64>,
+    pub remaining_estimate: Option<f64>,
+}
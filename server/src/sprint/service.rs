@@ -0,0 +1,115 @@
+use bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use tracing::debug;
+
+use crate::config::AppState;
+use crate::db::run_transaction;
+use crate::error::{AppError, Result, SprintRepoError};
+use crate::sprint::mapper::{doc_to_sprint, parse_sprints, sprint_to_doc};
+use crate::sprint::model::Sprint;
+
+/// Returns the sprints of a board, by start date.
+pub async fn get_sprints(
+    board_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Sprint>> {
+    let options = FindOptions::builder().sort(doc! { "start": 1 }).build();
+    let cursor = collection
+        .find(doc! { "board_id": board_id }, options)
+        .await?;
+    parse_sprints(cursor).await
+}
+
+pub async fn get_sprint(sprint_id: &String, collection: Collection<Document>) -> Result<Sprint> {
+    let sprint_opt = collection
+        .find_one(doc! { "id": sprint_id }, None)
+        .await
+        .map_err(|e| {
+            debug!("Error while getting sprint with id {}: {}", sprint_id, e);
+            AppError::MongoError(e)
+        })?;
+    match sprint_opt {
+        Some(doc) => doc_to_sprint(&doc),
+        None => Err(AppError::SprintRepo(SprintRepoError::NotFound)),
+    }
+}
+
+pub async fn create(sprint: &Sprint, collection: Collection<Document>) -> Result<&Sprint> {
+    debug!("[create_sprint] Creating sprint with id={}", &sprint.id);
+    collection.insert_one(sprint_to_doc(sprint), None).await?;
+    Ok(sprint)
+}
+
+pub async fn update(sprint: &Sprint, collection: Collection<Document>) -> Result<&Sprint> {
+    debug!("[update_sprint] Updating sprint with id={}", &sprint.id);
+    let filter = doc! { "id": sprint.id.to_string() };
+    let updates = doc! { "$set": sprint_to_doc(sprint) };
+    collection.update_one(filter, updates, None).await?;
+    Ok(sprint)
+}
+
+/// Saves a closed sprint and moves its carried over tasks to `carry_over_to`,
+/// or back to the backlog, in a single transaction.
+pub async fn close(
+    sprint: &Sprint,
+    carry_over_to: Option<uuid::Uuid>,
+    state: &AppState,
+) -> Result<()> {
+    debug!(
+        "[close_sprint] Closing sprint with id={}, carrying {} tasks over to {:?}",
+        &sprint.id,
+        sprint.carried_over_task_ids.len(),
+        carry_over_to
+    );
+    let sprint_doc = sprint_to_doc(sprint);
+    let task_ids = sprint
+        .carried_over_task_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    run_transaction(&state.client, |session| {
+        let (sprint_doc, task_ids, state) = (sprint_doc.clone(), task_ids.clone(), state.clone());
+        Box::pin(async move {
+            let filter = doc! { "id": sprint_doc.get_str("id").unwrap_or_default() };
+            state
+                .get_sprints_collection()
+                .update_one_with_session(filter, doc! { "$set": sprint_doc }, None, session)
+                .await?;
+            if !task_ids.is_empty() {
+                state
+                    .get_tasks_collection()
+                    .update_many_with_session(
+                        doc! { "id": { "$in": task_ids } },
+                        doc! {
+                            "$set": {
+                                "sprint_id": carry_over_to.map(|id| id.to_string()),
+                                "updated_at": bson::DateTime::now()
+                            }
+                        },
+                        None,
+                        session,
+                    )
+                    .await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+pub async fn delete(sprint_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!("[delete_sprint] Deleting sprint with id={}", sprint_id);
+    collection
+        .delete_one(doc! { "id": sprint_id }, None)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_of_board(board_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!("[delete_of_board] Deleting sprints of board {}", board_id);
+    collection
+        .delete_many(doc! { "board_id": board_id }, None)
+        .await?;
+    Ok(())
+}
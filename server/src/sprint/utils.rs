@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{Result, SprintRepoError};
+use crate::sprint::model::{BurndownPoint, Sprint, SprintBurndown};
+use crate::task::model::Task;
+use crate::transition::model::Transition;
+use crate::transition::utils::{column_at, column_periods};
+
+pub fn validate_sprint(sprint: &Sprint) -> Result<()> {
+    if sprint.name.is_empty() {
+        return Err(SprintRepoError::InvalidSprint(String::from("name cannot be empty")).into());
+    }
+    if sprint.start >= sprint.end {
+        return Err(
+            SprintRepoError::InvalidSprint(String::from("start has to be before end")).into(),
+        );
+    }
+    Ok(())
+}
+
+/// Splits the tasks of a sprint into the done and the unfinished ones.
+pub fn split_finished(
+    tasks: &[Task],
+    done_column_ids: &[uuid::Uuid],
+) -> (Vec<uuid::Uuid>, Vec<uuid::Uuid>) {
+    let (done, unfinished): (Vec<&Task>, Vec<&Task>) = tasks
+        .iter()
+        .partition(|t| t.column_id.is_some_and(|c| done_column_ids.contains(&c)));
+    (
+        done.iter().map(|t| t.id).collect(),
+        unfinished.iter().map(|t| t.id).collect(),
+    )
+}
+
+/// Replays the transitions of the tasks of a sprint to compute its progress at
+/// the start of the sprint, the end of each of its days and its end. The
+/// progress of a closed sprint stops when it was closed.
+pub fn sprint_burndown(
+    sprint: &Sprint,
+    tasks: &[Task],
+    transitions: &[Transition],
+    done_column_ids: &[uuid::Uuid],
+    now: DateTime<Utc>,
+) -> SprintBurndown {
+    let mut transitions_of_tasks: HashMap<uuid::Uuid, Vec<Transition>> = HashMap::new();
+    for transition in transitions {
+        transitions_of_tasks
+            .entry(transition.task_id)
+            .or_default()
+            .push(transition.clone());
+    }
+    let periods_of_tasks = tasks
+        .iter()
+        .map(|task| {
+            let task_transitions = transitions_of_tasks
+                .get(&task.id)
+                .map(|t| t.as_slice())
+                .unwrap_or_default();
+            (task, column_periods(task, task_transitions, now))
+        })
+        .collect::<Vec<_>>();
+
+    let total_tasks = tasks.len() as f64;
+    let total_estimate = tasks.iter().filter_map(|t| t.estimate).sum::<f64>();
+    let length = (sprint.end - sprint.start).num_seconds() as f64;
+    let last_actual = sprint.closed_at.map_or(now, |closed_at| closed_at.min(now));
+
+    let mut times = Vec::new();
+    let mut at = sprint.start;
+    while at < sprint.end {
+        times.push(at);
+        at += Duration::days(1);
+    }
+    times.push(sprint.end);
+
+    let points = times
+        .into_iter()
+        .map(|at| {
+            let left = 1.0 - (at - sprint.start).num_seconds() as f64 / length;
+            let mut point = BurndownPoint {
+                at,
+                ideal_remaining_tasks: total_tasks * left,
+                ideal_remaining_estimate: total_estimate * left,
+                scope_tasks: None,
+                done_tasks: None,
+                remaining_tasks: None,
+                scope_estimate: None,
+                done_estimate: None,
+                remaining_estimate: None,
+            };
+            if at > last_actual {
+                return point;
+            }
+            let mut scope = (0, 0.0);
+            let mut done = (0, 0.0);
+            for (task, periods) in periods_of_tasks.iter() {
+                let Some(column_id) = column_at(periods, at) else {
+                    continue;
+                };
+                let estimate = task.estimate.unwrap_or_default();
+                scope = (scope.0 + 1, scope.1 + estimate);
+                if column_id.is_some_and(|c| done_column_ids.contains(&c)) {
+                    done = (done.0 + 1, done.1 + estimate);
+                }
+            }
+            point.scope_tasks = Some(scope.0);
+            point.done_tasks = Some(done.0);
+            point.remaining_tasks = Some(scope.0 - done.0);
+            point.scope_estimate = Some(scope.1);
+            point.done_estimate = Some(done.1);
+            point.remaining_estimate = Some(scope.1 - done.1);
+            point
+        })
+        .collect();
+
+    SprintBurndown {
+        sprint_id: sprint.id,
+        start: sprint.start,
+        end: sprint.end,
+        points,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use uuid::Uuid;
+
+    use crate::fixtures::mock_task;
+    use crate::sprint::model::Sprint;
+    use crate::sprint::utils::{split_finished, sprint_burndown, validate_sprint};
+    use crate::task::model::Task;
+    use crate::transition::model::Transition;
+
+    fn mock_sprint() -> Sprint {
+        Sprint {
+            id: Uuid::new_v4(),
+            board_id: Uuid::new_v4(),
+            name: String::from("sprint 1"),
+            goal: None,
+            start: Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2023, 3, 10, 9, 0, 0).unwrap(),
+            created_at: Utc.with_ymd_and_hms(2023, 3, 1, 9, 0, 0).unwrap(),
+            updated_at: None,
+            closed_at: None,
+            completed_task_ids: Vec::new(),
+            carried_over_task_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn it_validates_sprint_names_and_dates() {
+        let sprint = mock_sprint();
+        assert!(validate_sprint(&sprint).is_ok());
+        assert!(validate_sprint(&Sprint {
+            end: sprint.start,
+            ..sprint.clone()
+        })
+        .is_err());
+        assert!(validate_sprint(&Sprint {
+            name: String::new(),
+            ..sprint
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn it_burns_down_sprints_and_splits_finished_tasks() {
+        let (todo, done) = (Uuid::new_v4(), Uuid::new_v4());
+        let done_column_ids = vec![done];
+        let sprint = mock_sprint();
+        let day = |d: i64| sprint.start + Duration::days(d);
+
+        let first = Task {
+            column_id: Some(done),
+            estimate: Some(3.0),
+            ..mock_task(Uuid::new_v4(), Some(todo))
+        };
+        let second = Task {
+            column_id: Some(done),
+            estimate: Some(5.0),
+            ..mock_task(Uuid::new_v4(), Some(todo))
+        };
+        let unfinished = mock_task(Uuid::new_v4(), Some(todo));
+        let transition = |task: &Task, at| Transition {
+            id: Uuid::new_v4(),
+            task_id: task.id,
+            board_id: task.board_id,
+            from_column_id: Some(todo),
+            to_column_id: Some(done),
+            at,
+        };
+        let transitions = vec![
+            transition(&first, day(1) - Duration::hours(1)),
+            transition(&second, day(2) + Duration::hours(1)),
+        ];
+        let tasks = vec![first, second, unfinished];
+
+        let burndown = sprint_burndown(&sprint, &tasks, &transitions, &done_column_ids, day(3));
+        assert_eq!(burndown.points.len(), 5);
+        let remaining = burndown
+            .points
+            .iter()
+            .map(|p| p.remaining_tasks)
+            .collect::<Vec<Option<usize>>>();
+        assert_eq!(remaining, vec![Some(3), Some(2), Some(2), Some(1), None]);
+        assert_eq!(burndown.points[3].done_estimate, Some(8.0));
+        assert_eq!(burndown.points[3].scope_estimate, Some(8.0));
+        assert_eq!(burndown.points[2].ideal_remaining_tasks, 1.5);
+
+        let (completed, carried_over) = split_finished(&tasks, &done_column_ids);
+        assert_eq!(completed, vec![tasks[0].id, tasks[1].id]);
+        assert_eq!(carried_over, vec![tasks[2].id]);
+    }
+}
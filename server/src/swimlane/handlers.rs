@@ -0,0 +1,363 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::activity::service as activity_service;
+use crate::activity::utils::{board_activities, task_activities};
+use crate::board::model::{Board, BoardSettings};
+use crate::board::service as board_service;
+use crate::board::utils::reorder_by_id;
+use crate::config::AppState;
+use crate::dto::{CreateSwimlaneRequest, ReorderSwimlanesRequest, Response};
+use crate::error::{AppError, BoardRepoError};
+use crate::swimlane::model::{Swimlane, SwimlaneSource};
+use crate::swimlane::utils::{derive_swimlanes, move_tasks_to_swimlane, validate_swimlane};
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::user::extractor::CurrentUser;
+
+// Returns the swimlanes of a board, in their order
+#[axum_macros::debug_handler]
+pub async fn get_swimlanes_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting swimlanes of board {}", board_id);
+    match board_service::get_board(&board_id, state.get_boards_collection()).await {
+        Ok(board) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(board.swimlanes),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_swimlanes_handler] Error getting swimlanes of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::NOT_FOUND),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Adds a swimlane at the bottom of a board whose swimlanes are not derived
+#[axum_macros::debug_handler]
+pub async fn swimlane_create_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CreateSwimlaneRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[swimlane_create_handler] Creating swimlane {} on board {}",
+        req.name, board_id
+    );
+    let swimlane = Swimlane::new(&req.name, None);
+
+    async fn validate_and_create(
+        board_id: &String,
+        swimlane: &Swimlane,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        if let Some(source) = &board.settings.swimlanes_by {
+            return Err(BoardRepoError::InvalidBoard(format!(
+                "swimlanes of the board are derived from {:?}",
+                source
+            ))
+            .into());
+        }
+        validate_swimlane(swimlane, &board.swimlanes)?;
+        let mut swimlanes = board.swimlanes.clone();
+        swimlanes.push(swimlane.clone());
+        let updated = board_service::update(
+            &board.clone().with_swimlanes(swimlanes),
+            state.get_boards_collection(),
+        )
+        .await?;
+        activity_service::record(
+            board_activities(Some(&board), Some(&updated), actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        Ok(())
+    }
+
+    match validate_and_create(&board_id, &swimlane, user.map(|u| u.0), state).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(swimlane),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[swimlane_create_handler] Error creating swimlane {} on board {}: {:?}",
+                swimlane.name,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Changes the order of some swimlanes of a board, returns the swimlanes in their new order
+#[axum_macros::debug_handler]
+pub async fn swimlane_reorder_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<ReorderSwimlanesRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[swimlane_reorder_handler] Reordering swimlanes {:?} of board {}",
+        req.swimlane_ids, board_id
+    );
+
+    async fn reorder(
+        board_id: &String,
+        req: &ReorderSwimlanesRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Vec<Swimlane>, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let swimlanes = reorder_by_id(&board.swimlanes, &req.swimlane_ids, |l| l.id, "swimlane")?;
+        let updated = board_service::update(
+            &board.clone().with_swimlanes(swimlanes),
+            state.get_boards_collection(),
+        )
+        .await?;
+        activity_service::record(
+            board_activities(Some(&board), Some(&updated), actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        Ok(updated.swimlanes)
+    }
+
+    match reorder(&board_id, &req, user.map(|u| u.0), state).await {
+        Ok(swimlanes) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(swimlanes),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[swimlane_reorder_handler] Error reordering swimlanes of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Replaces the swimlanes of a board by one lane per value of the assignee, the
+// priority or a custom field, keeps the tasks in their lane as they change
+#[axum_macros::debug_handler]
+pub async fn swimlane_derive_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(source): Json<SwimlaneSource>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[swimlane_derive_handler] Deriving swimlanes of board {} from {:?}",
+        board_id, source
+    );
+
+    async fn derive(
+        board_id: &String,
+        source: SwimlaneSource,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Vec<Swimlane>, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let stored = task_service::get_tasks(board_id, state.get_tasks_collection()).await?;
+        let (swimlanes, changed) = derive_swimlanes(&board, &stored, &source)?;
+        let settings = BoardSettings {
+            swimlanes_by: Some(source),
+            ..board.settings.clone()
+        };
+        let updated = board_service::update(
+            &Board {
+                settings,
+                ..board.clone().with_swimlanes(swimlanes)
+            },
+            state.get_boards_collection(),
+        )
+        .await?;
+        if !changed.is_empty() {
+            task_service::update_many(&changed, state.clone()).await?;
+        }
+        let before = stored
+            .into_iter()
+            .filter(|t| changed.iter().any(|c| c.id == t.id))
+            .collect::<Vec<Task>>();
+        let mut activities = board_activities(Some(&board), Some(&updated), actor.as_deref());
+        activities.extend(task_activities(&before, &changed, actor.as_deref()));
+        activity_service::record(activities, state.get_activity_collection()).await;
+        Ok(updated.swimlanes)
+    }
+
+    match derive(&board_id, source, user.map(|u| u.0), state).await {
+        Ok(swimlanes) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(swimlanes),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[swimlane_derive_handler] Error deriving swimlanes of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes a swimlane, its tasks go to the bottom of the default lane
+#[axum_macros::debug_handler]
+pub async fn swimlane_delete_handler(
+    Path((board_id, swimlane_id)): Path<(String, uuid::Uuid)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    debug!(
+        "[swimlane_delete_handler] Deleting swimlane {} on board {}",
+        swimlane_id, board_id
+    );
+
+    async fn delete(
+        board_id: &String,
+        swimlane_id: uuid::Uuid,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<(), AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        if board.swimlane(swimlane_id).is_none() {
+            return Err(
+                BoardRepoError::InvalidBoard(format!("unknown swimlane {}", swimlane_id)).into(),
+            );
+        }
+        let swimlanes = board
+            .swimlanes
+            .iter()
+            .filter(|l| l.id != swimlane_id)
+            .cloned()
+            .collect();
+        let updated = board_service::update(
+            &board.clone().with_swimlanes(swimlanes),
+            state.get_boards_collection(),
+        )
+        .await?;
+        let board_tasks = task_service::get_tasks(board_id, state.get_tasks_collection()).await?;
+        let stored = board_tasks
+            .iter()
+            .filter(|t| t.swimlane_id == Some(swimlane_id))
+            .cloned()
+            .collect::<Vec<Task>>();
+        let moved = move_tasks_to_swimlane(&stored, &board_tasks, None);
+        if !moved.is_empty() {
+            task_service::update_many(&moved, state.clone()).await?;
+        }
+        let mut activities = board_activities(Some(&board), Some(&updated), actor.as_deref());
+        activities.extend(task_activities(&stored, &moved, actor.as_deref()));
+        activity_service::record(activities, state.get_activity_collection()).await;
+        Ok(())
+    }
+
+    match delete(&board_id, swimlane_id, user.map(|u| u.0), state).await {
+        Ok(_) => {
+            let msg = format!("Deleted swimlane with id {}", swimlane_id);
+            debug!("[swimlane_delete_handler] {}", msg);
+            (
+                StatusCode::OK,
+                Json(Response {
+                    success: true,
+                    data: Some(msg),
+                    error_message: None,
+                    warnings: None,
+                }),
+            )
+        }
+        Err(e) => {
+            let msg = format!(
+                "[swimlane_delete_handler] Error deleting swimlane {} on board {}: {:?}",
+                swimlane_id,
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
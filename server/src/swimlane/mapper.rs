@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use bson::{doc, Document};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::error::{BoardRepoError, Result};
+use crate::swimlane::model::Swimlane;
+
+pub fn doc_to_swimlane(doc: &Document) -> Result<Swimlane> {
+    let id = doc.get_str("id")?;
+    let name = doc.get_str("name")?;
+    let created_at = *doc.get_datetime("created_at")?;
+    let updated_at = doc
+        .get_datetime("updated_at")
+        .ok()
+        .map(|v| chrono::DateTime::from(*v));
+    match Uuid::from_str(id) {
+        Ok(swimlane_id) => Ok(Swimlane {
+            id: swimlane_id,
+            name: name.to_owned(),
+            key: doc.get_str("key").ok().map(|k| k.to_owned()),
+            created_at: chrono::DateTime::from(created_at),
+            updated_at,
+        }),
+        _ => Err(BoardRepoError::DecodeError(format!("Swimlane doesnt have id {}", id)).into()),
+    }
+}
+
+pub fn swimlane_to_doc(swimlane: &Swimlane) -> Document {
+    doc! {
+        "id" : swimlane.id.to_string(),
+        "name" : swimlane.name.clone(),
+        "key" : swimlane.key.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(swimlane.created_at),
+        "updated_at" : swimlane.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    }
+}
+
+pub fn get_swimlanes(doc: &Document) -> Vec<Swimlane> {
+    doc.get_array("swimlanes")
+        .map(|swimlanes| {
+            swimlanes
+                .iter()
+                .filter_map(|l| l.as_document().and_then(|d| doc_to_swimlane(d).ok()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A row of a board, its tasks are ordered in every column independently of
+/// the other rows.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Swimlane {
+    pub id: uuid::Uuid,
+    pub name: String,
+    /// Value gathered by a derived lane.
+    pub key: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Swimlane {
+    pub fn new(name: &str, key: Option<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            name: name.trim().to_owned(),
+            key,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+}
+
+/// What swimlanes are derived from, one lane per value.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum SwimlaneSource {
+    Assignee,
+    /// The custom field of the board named `priority`, whatever its case.
+    Priority,
+    CustomField {
+        field_id: uuid::Uuid,
+    },
+}
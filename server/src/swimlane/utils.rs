@@ -0,0 +1,297 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::board::model::Board;
+use crate::custom_field::model::{CustomField, CustomFieldValue};
+use crate::error::{BoardRepoError, Result, TaskRepoError};
+use crate::swimlane::model::{Swimlane, SwimlaneSource};
+use crate::task::model::Task;
+use crate::task::utils::{build_hierarchy_set, move_tasks_to_column};
+
+pub fn validate_swimlane(swimlane: &Swimlane, board_swimlanes: &[Swimlane]) -> Result<()> {
+    if swimlane.name.is_empty() {
+        return Err(
+            BoardRepoError::InvalidBoard(String::from("swimlane name cannot be empty")).into(),
+        );
+    }
+    if board_swimlanes
+        .iter()
+        .any(|l| l.id != swimlane.id && l.name.eq_ignore_ascii_case(&swimlane.name))
+    {
+        return Err(BoardRepoError::InvalidBoard(format!(
+            "a swimlane named {} already exists",
+            swimlane.name
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Custom field the lanes are derived from, `None` for the assignee.
+fn source_field<'a>(source: &SwimlaneSource, board: &'a Board) -> Result<Option<&'a CustomField>> {
+    let field = match source {
+        SwimlaneSource::Assignee => return Ok(None),
+        SwimlaneSource::Priority => board
+            .custom_fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case("priority")),
+        SwimlaneSource::CustomField { field_id } => {
+            board.custom_fields.iter().find(|f| f.id == *field_id)
+        }
+    };
+    match field {
+        Some(field) => Ok(Some(field)),
+        None => Err(BoardRepoError::InvalidBoard(format!(
+            "no custom field to derive swimlanes from ({:?})",
+            source
+        ))
+        .into()),
+    }
+}
+
+/// Value of a task the lanes are derived from, the first option of multiple
+/// selections.
+fn task_key(task: &Task, field: Option<&CustomField>) -> Option<String> {
+    let Some(field) = field else {
+        return task.assignee.clone();
+    };
+    match task.custom_fields.get(&field.id.to_string())? {
+        CustomFieldValue::Number(n) => Some(n.to_string()),
+        CustomFieldValue::Text(text) => Some(text.clone()),
+        CustomFieldValue::Options(options) => options.first().cloned(),
+    }
+    .filter(|key| !key.is_empty())
+}
+
+/// One lane per value of the tasks, the select options first in their order and
+/// the other values alphabetically. Existing lanes of a value are kept. Returns
+/// the lanes and the tasks of the board that changed lane, ordered in their new
+/// cells as they were in their columns.
+pub fn derive_swimlanes(
+    board: &Board,
+    tasks: &[Task],
+    source: &SwimlaneSource,
+) -> Result<(Vec<Swimlane>, Vec<Task>)> {
+    let field = source_field(source, board)?;
+    let values = tasks
+        .iter()
+        .filter_map(|t| task_key(t, field))
+        .collect::<BTreeSet<String>>();
+    let mut keys = field.map(|f| f.options.clone()).unwrap_or_default();
+    keys.extend(
+        values
+            .into_iter()
+            .filter(|v| !keys.contains(v))
+            .collect::<Vec<String>>(),
+    );
+    let swimlanes = keys
+        .into_iter()
+        .map(|key| {
+            board
+                .swimlanes
+                .iter()
+                .find(|l| l.key.as_ref() == Some(&key))
+                .cloned()
+                .unwrap_or_else(|| Swimlane::new(&key, Some(key.clone())))
+        })
+        .collect::<Vec<Swimlane>>();
+    let lane_of = |task: &Task| {
+        let key = task_key(task, field);
+        swimlanes
+            .iter()
+            .find(|l| key.is_some() && l.key == key)
+            .map(|l| l.id)
+    };
+    let derived = tasks
+        .iter()
+        .map(|t| Task {
+            swimlane_id: lane_of(t),
+            ..t.clone()
+        })
+        .collect::<Vec<Task>>();
+    let changed = relink_cells(&derived)
+        .into_iter()
+        .filter(|t| !tasks.contains(t))
+        .collect();
+    Ok((swimlanes, changed))
+}
+
+/// Chains the tasks of every cell in the order they have in their column.
+pub fn relink_cells(tasks: &[Task]) -> Vec<Task> {
+    let mut columns: HashMap<Option<uuid::Uuid>, Vec<Task>> = HashMap::new();
+    for task in tasks {
+        columns
+            .entry(task.column_id)
+            .or_default()
+            .push(task.clone());
+    }
+    columns
+        .into_values()
+        .flat_map(|column_tasks| {
+            let mut last_of_lanes: HashMap<Option<uuid::Uuid>, uuid::Uuid> = HashMap::new();
+            build_hierarchy_set(column_tasks)
+                .into_iter()
+                .map(|t| {
+                    let above_task_id = last_of_lanes.insert(t.swimlane_id, t.id);
+                    t.with_above_task(above_task_id)
+                })
+                .collect::<Vec<Task>>()
+        })
+        .collect()
+}
+
+/// Moves tasks to the bottom of the given lane in their columns.
+pub fn move_tasks_to_swimlane(
+    moved: &[Task],
+    board_tasks: &[Task],
+    swimlane_id: Option<uuid::Uuid>,
+) -> Vec<Task> {
+    let mut columns: HashMap<Option<uuid::Uuid>, Vec<Task>> = HashMap::new();
+    for task in moved {
+        columns.entry(task.column_id).or_default().push(Task {
+            swimlane_id,
+            ..task.clone()
+        });
+    }
+    columns
+        .into_iter()
+        .flat_map(|(column_id, column_moved)| {
+            let target_tasks = board_tasks
+                .iter()
+                .filter(|t| t.column_id == column_id && !moved.iter().any(|m| m.id == t.id))
+                .cloned()
+                .collect::<Vec<Task>>();
+            move_tasks_to_column(&column_moved, &target_tasks, column_id)
+        })
+        .collect()
+}
+
+/// Checks the swimlane of a task, or puts the task in the lane matching its
+/// value when the lanes of the board are derived. A task changing lane that
+/// way goes to the bottom of its new cell.
+pub fn place_in_swimlane(task: Task, board: &Board, board_tasks: &[Task]) -> Result<Task> {
+    let Some(source) = board.settings.swimlanes_by.as_ref() else {
+        if let Some(swimlane_id) = task.swimlane_id {
+            if board.swimlane(swimlane_id).is_none() {
+                return Err(TaskRepoError::InvalidTask(format!(
+                    "unknown swimlane {}",
+                    swimlane_id
+                ))
+                .into());
+            }
+        }
+        return Ok(task);
+    };
+    let key = task_key(&task, source_field(source, board)?);
+    let swimlane_id = board
+        .swimlanes
+        .iter()
+        .find(|l| key.is_some() && l.key == key)
+        .map(|l| l.id);
+    if swimlane_id == task.swimlane_id {
+        return Ok(task);
+    }
+    let others = board_tasks
+        .iter()
+        .filter(|t| t.id != task.id)
+        .cloned()
+        .collect::<Vec<Task>>();
+    Ok(move_tasks_to_swimlane(&[task], &others, swimlane_id).remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::board::model::{Board, BoardSettings};
+    use crate::fixtures::{mock_board, mock_task};
+    use crate::swimlane::model::{Swimlane, SwimlaneSource};
+    use crate::swimlane::utils::{derive_swimlanes, move_tasks_to_swimlane, place_in_swimlane};
+    use crate::task::model::Task;
+
+    fn assigned_task(column_id: Uuid, assignee: Option<&str>, above: Option<&Task>) -> Task {
+        Task {
+            above_task_id: above.map(|t| t.id),
+            assignee: assignee.map(String::from),
+            ..mock_task(Uuid::new_v4(), Some(column_id))
+        }
+    }
+
+    #[test]
+    fn it_derives_swimlanes_from_assignees() {
+        let column_id = Uuid::new_v4();
+        let first = assigned_task(column_id, Some("bob"), None);
+        let second = assigned_task(column_id, Some("alice"), Some(&first));
+        let third = assigned_task(column_id, Some("bob"), Some(&second));
+        let fourth = assigned_task(column_id, None, Some(&third));
+        let kept = Swimlane::new("Bob", Some(String::from("bob")));
+        let board = Board {
+            swimlanes: vec![kept.clone()],
+            ..mock_board(Vec::new())
+        };
+        let tasks = vec![first.clone(), second.clone(), third.clone(), fourth.clone()];
+
+        let (swimlanes, changed) =
+            derive_swimlanes(&board, &tasks, &SwimlaneSource::Assignee).unwrap();
+        assert_eq!(
+            swimlanes
+                .iter()
+                .map(|l| l.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["alice", "Bob"]
+        );
+        assert_eq!(swimlanes[1].id, kept.id);
+
+        let changed_task = |task: &Task| changed.iter().find(|t| t.id == task.id).cloned();
+        let second = changed_task(&second).unwrap();
+        assert_eq!(second.swimlane_id, Some(swimlanes[0].id));
+        assert_eq!(second.above_task_id, None);
+        let third = changed_task(&third).unwrap();
+        assert_eq!(third.swimlane_id, Some(kept.id));
+        assert_eq!(third.above_task_id, Some(first.id));
+        assert_eq!(changed_task(&fourth).unwrap().above_task_id, None);
+        assert!(derive_swimlanes(&board, &tasks, &SwimlaneSource::Priority).is_err());
+    }
+
+    #[test]
+    fn it_places_tasks_in_their_derived_swimlane() {
+        let column_id = Uuid::new_v4();
+        let lane = Swimlane::new("alice", Some(String::from("alice")));
+        let board = Board {
+            swimlanes: vec![lane.clone()],
+            settings: BoardSettings {
+                swimlanes_by: Some(SwimlaneSource::Assignee),
+                ..Default::default()
+            },
+            ..mock_board(Vec::new())
+        };
+        let in_lane = Task {
+            swimlane_id: Some(lane.id),
+            ..assigned_task(column_id, Some("alice"), None)
+        };
+        let unassigned = assigned_task(column_id, None, None);
+        let board_tasks = vec![in_lane.clone(), unassigned.clone()];
+
+        let placed = place_in_swimlane(
+            assigned_task(column_id, Some("alice"), Some(&unassigned)),
+            &board,
+            &board_tasks,
+        )
+        .unwrap();
+        assert_eq!(placed.swimlane_id, Some(lane.id));
+        assert_eq!(placed.above_task_id, Some(in_lane.id));
+
+        let manual = Board {
+            settings: Default::default(),
+            ..board
+        };
+        let unknown = Task {
+            swimlane_id: Some(Uuid::new_v4()),
+            ..unassigned
+        };
+        assert!(place_in_swimlane(unknown, &manual, &board_tasks).is_err());
+
+        let moved = move_tasks_to_swimlane(std::slice::from_ref(&in_lane), &board_tasks, None);
+        assert_eq!(moved[0].swimlane_id, None);
+        assert_eq!(moved[0].above_task_id, Some(board_tasks[1].id));
+    }
+}
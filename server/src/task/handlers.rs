@@ -1,34 +1,119 @@
-use std::collections::LinkedList;
+use std::collections::{HashMap, HashSet, LinkedList};
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
 use chrono::Utc;
 use tracing::{debug, error};
 
+use crate::activity::service as activity_service;
+use crate::activity::utils::task_activities;
+use crate::automation::service as automation_service;
+use crate::automation::utils::TaskEvent;
+use crate::board::service as board_service;
+use crate::board::utils::check_wip_limits;
+use crate::chat::service as chat_service;
+use crate::comment::service as comment_service;
 use crate::config::AppState;
-use crate::dto::{CreateTaskRequest, Response};
-use crate::task::model::{SortedTask, Task};
+use crate::custom_field::utils::{filter_tasks, get_filters, sort_tasks, validate_values};
+use crate::dto::{
+    AddDependencyRequest, ArchivedParams, BoardTasksParams, ChildrenStrategy, CreateTaskRequest,
+    DeleteTaskParams, Response, UpdateTasksParams,
+};
+use crate::error::{AppError, TaskRepoError};
+use crate::notification::service as notification_service;
+use crate::notification::utils::task_notifications;
+use crate::swimlane::utils::place_in_swimlane;
+use crate::task::model::{DependencyGraph, DependencyNode, SortedTask, Task};
 use crate::task::service;
-use crate::task::utils::map_task_db_to_linked;
+use crate::task::utils::{
+    dependency_edges, is_blocked, map_task_db_to_linked, move_tasks_to_column, unlink_from_column,
+    validate_dependency, validate_parent, with_blocked_flags, with_comment_counts,
+    with_subtask_rollups, without_archived,
+};
+use crate::task_template::service as task_template_service;
+use crate::transition::service as transition_service;
+use crate::transition::utils::column_transitions;
+use crate::trash::model::TrashItem;
+use crate::trash::service as trash_service;
+use crate::user::extractor::CurrentUser;
+
+/// Sorts the tasks and enriches them with the state computed from other tasks
+/// and collections (subtasks roll-up, blocked flag, comment counts).
+async fn to_sorted_tasks(
+    tasks: Vec<Task>,
+    state: &AppState,
+) -> Result<LinkedList<SortedTask>, AppError> {
+    let blockers = get_blockers(tasks.iter(), state).await?;
+    let boards = board_service::get_boards_by_ids(
+        &board_ids_of(tasks.iter().chain(blockers.iter())),
+        state.get_boards_collection(),
+    )
+    .await?;
+    let done_columns = boards
+        .iter()
+        .flat_map(|b| b.done_column_ids())
+        .collect::<HashSet<uuid::Uuid>>();
+    let blocker_columns = blocker_columns(&blockers);
+    let task_ids = tasks.iter().map(|t| t.id).collect::<Vec<uuid::Uuid>>();
+    let comment_counts =
+        comment_service::count_by_tasks(&task_ids, state.get_comments_collection()).await?;
+    let sorted = with_subtask_rollups(map_task_db_to_linked(tasks, &boards), &done_columns);
+    let sorted = with_blocked_flags(sorted, &blocker_columns, &done_columns);
+    Ok(with_comment_counts(sorted, &comment_counts))
+}
+
+/// Returns the tasks blocking the given tasks.
+async fn get_blockers<'a>(
+    tasks: impl Iterator<Item = &'a Task>,
+    state: &AppState,
+) -> Result<Vec<Task>, AppError> {
+    let blocker_ids = tasks
+        .flat_map(|t| t.blocked_by.clone())
+        .collect::<HashSet<uuid::Uuid>>()
+        .into_iter()
+        .collect::<Vec<uuid::Uuid>>();
+    if blocker_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    service::get_tasks_by_ids(&blocker_ids, state.get_tasks_collection()).await
+}
+
+/// Maps every blocker to the column it currently sits in.
+fn blocker_columns(blockers: &[Task]) -> HashMap<uuid::Uuid, Option<uuid::Uuid>> {
+    blockers.iter().map(|t| (t.id, t.column_id)).collect()
+}
+
+/// Ids of the boards the given tasks belong to.
+fn board_ids_of<'a>(tasks: impl Iterator<Item = &'a Task>) -> Vec<uuid::Uuid> {
+    tasks
+        .map(|t| t.board_id)
+        .collect::<HashSet<uuid::Uuid>>()
+        .into_iter()
+        .collect()
+}
 
 // Returns all tasks
 #[axum_macros::debug_handler]
 pub async fn get_tasks_handler(State(state): State<AppState>) -> impl IntoResponse {
     debug!("Getting all tasks");
-    match service::get_all_tasks(state.get_tasks_collection()).await {
-        Ok(tasks) => {
-            let task_hierarchy: LinkedList<SortedTask> = map_task_db_to_linked(tasks);
-            (
-                StatusCode::OK,
-                Json(Response {
-                    success: true,
-                    data: Some(task_hierarchy),
-                    error_message: None,
-                }),
-            )
-        }
+
+    async fn get_sorted(state: AppState) -> Result<LinkedList<SortedTask>, AppError> {
+        let tasks = service::get_all_tasks(state.get_tasks_collection()).await?;
+        to_sorted_tasks(tasks, &state).await
+    }
+
+    match get_sorted(state).await {
+        Ok(task_hierarchy) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(task_hierarchy),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
         Err(e) => {
             let msg = format!(
                 "[get_tasks_handler] Error getting all tasks: {:?}",
@@ -41,32 +126,59 @@ pub async fn get_tasks_handler(State(state): State<AppState>) -> impl IntoRespon
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
     }
 }
 
-// Returns all tasks for a board
+// Returns all tasks for a board, optionally filtered and sorted by custom fields
 #[axum_macros::debug_handler]
 pub async fn get_board_tasks_handler(
     path: Path<String>,
+    Query(params): Query<BoardTasksParams>,
+    Query(archived): Query<ArchivedParams>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     let board_id = path.0;
-    debug!("Getting all tasks for board {}", board_id);
-    match service::get_tasks(&board_id, state.get_tasks_collection()).await {
-        Ok(tasks) => {
-            let task_hierarchy: LinkedList<SortedTask> = map_task_db_to_linked(tasks);
-            (
-                StatusCode::OK,
-                Json(Response {
-                    success: true,
-                    data: Some(task_hierarchy),
-                    error_message: None,
-                }),
-            )
+    debug!("Getting all tasks for board {} ({:?})", board_id, params);
+
+    async fn get_sorted(
+        board_id: &String,
+        params: BoardTasksParams,
+        include_archived: bool,
+        state: AppState,
+    ) -> Result<LinkedList<SortedTask>, AppError> {
+        let mut tasks = service::get_tasks(board_id, state.get_tasks_collection()).await?;
+        if !include_archived {
+            let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+            tasks = without_archived(tasks, &board.archived_column_ids());
         }
+        if let Some(sprint_id) = params.sprint_id {
+            tasks.retain(|t| t.sprint_id == Some(sprint_id));
+        }
+        let sorted = filter_tasks(
+            to_sorted_tasks(tasks, &state).await?,
+            &get_filters(&params.filters),
+        );
+        Ok(match params.sort_by {
+            Some(field_id) => sort_tasks(sorted, &field_id, params.order.unwrap_or_default()),
+            None => sorted,
+        })
+    }
+
+    let include_archived = archived.include_archived.unwrap_or(false);
+    match get_sorted(&board_id, params, include_archived, state).await {
+        Ok(task_hierarchy) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(task_hierarchy),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
         Err(e) => {
             let msg = format!(
                 "[get_tasks_handler] Error getting tasks for board {}: {:?}",
@@ -80,6 +192,7 @@ pub async fn get_board_tasks_handler(
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -100,6 +213,7 @@ pub async fn get_task_handler(
                 success: true,
                 data: Some(task),
                 error_message: None,
+                warnings: None,
             }),
         ),
         Err(e) => {
@@ -115,6 +229,52 @@ pub async fn get_task_handler(
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Returns the direct children of a task
+#[axum_macros::debug_handler]
+pub async fn get_task_children_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!("Getting children of task with id {}", task_id);
+
+    async fn get_children(task_id: &String, state: AppState) -> Result<Vec<Task>, AppError> {
+        // makes sure a missing parent results in an error rather than an empty list
+        service::get_task(task_id, state.get_tasks_collection()).await?;
+        service::get_children(task_id, state.get_tasks_collection()).await
+    }
+
+    match get_children(&task_id, state).await {
+        Ok(children) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(children),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_task_children_handler] Error getting children of task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -125,36 +285,112 @@ pub async fn get_task_handler(
 #[axum_macros::debug_handler]
 pub async fn task_create_handler(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
     Json(req): Json<CreateTaskRequest>,
 ) -> impl IntoResponse {
     debug!(
         "[task_create_handler] Creating task ({}) for board {}",
         req.title, req.board_id
     );
-    let task = Task::from_request(req);
-    match service::create(&task, state.get_tasks_collection()).await {
-        Ok(_) => (
+    let actor = user.map(|u| u.0);
+    let (title, board_id) = (req.title.clone(), req.board_id);
+
+    async fn validate_and_create(
+        req: CreateTaskRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<(Task, Vec<String>), AppError> {
+        let template = match req.template_id {
+            Some(template_id) => {
+                let template = task_template_service::get_task_template(
+                    &template_id.to_string(),
+                    state.get_task_templates_collection(),
+                )
+                .await?;
+                if template.board_id != req.board_id {
+                    return Err(TaskRepoError::InvalidTask(format!(
+                        "task template {} belongs to another board",
+                        template_id
+                    ))
+                    .into());
+                }
+                Some(template)
+            }
+            None => None,
+        };
+        let task = Task::from_request(req, template.as_ref(), actor.clone());
+        let board =
+            board_service::get_board(&task.board_id.to_string(), state.get_boards_collection())
+                .await?;
+        let board_tasks = match task.parent_task_id.is_some()
+            || task.column_id.is_some()
+            || board.settings.swimlanes_by.is_some()
+        {
+            true => {
+                service::get_tasks(&task.board_id.to_string(), state.get_tasks_collection()).await?
+            }
+            false => Vec::new(),
+        };
+        let task = place_in_swimlane(task, &board, &board_tasks)?;
+        if let Some(parent_id) = task.parent_task_id {
+            validate_parent(&task, parent_id, &board_tasks)?;
+        }
+        let mut warnings = Vec::new();
+        if task.column_id.is_some() || !task.custom_fields.is_empty() {
+            validate_values(&task.custom_fields, None, &board.custom_fields)?;
+            let mut after = board_tasks.clone();
+            after.push(task.clone());
+            warnings = check_wip_limits(&board, &board_tasks, &after)?;
+        }
+        service::create(&task, state.get_tasks_collection()).await?;
+        activity_service::record(
+            task_activities(&[], std::slice::from_ref(&task), actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        notification_service::notify(
+            task_notifications(None, &task, actor.as_deref()),
+            state.get_notifications_collection(),
+        )
+        .await;
+        let task = automation_service::react(
+            task.board_id,
+            vec![TaskEvent::Created(task.clone())],
+            &state,
+        )
+        .await
+        .into_iter()
+        .find(|t| t.id == task.id)
+        .unwrap_or(task);
+        chat_service::announce(&[], std::slice::from_ref(&task), actor.as_deref(), &state).await;
+        Ok((task, warnings))
+    }
+
+    match validate_and_create(req, actor, state).await {
+        Ok((task, warnings)) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
                 data: Some(task),
                 error_message: None,
+                warnings: (!warnings.is_empty()).then_some(warnings),
             }),
         ),
         Err(e) => {
             let msg = format!(
                 "[task_create_handler] Error creating task ({}) for board {}: {:?}",
-                task.id,
-                task.board_id,
+                title,
+                board_id,
                 e.to_string()
             );
             error!("{}", msg);
             (
-                StatusCode::BAD_REQUEST,
+                e.status_or(StatusCode::BAD_REQUEST),
                 Json(Response {
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -163,7 +399,9 @@ pub async fn task_create_handler(
 
 // Updates existing task
 pub async fn task_update_handler(
+    Query(params): Query<UpdateTasksParams>,
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
     Json(tasks): Json<Vec<Task>>,
 ) -> impl IntoResponse {
     let tasks_ids = &tasks
@@ -178,7 +416,7 @@ pub async fn task_update_handler(
     );
     let update_time = Utc::now();
 
-    let updated_tasks = &tasks
+    let updated_tasks = tasks
         .into_iter()
         .map(|t| Task {
             updated_at: Some(update_time),
@@ -186,13 +424,111 @@ pub async fn task_update_handler(
         })
         .collect();
 
-    match service::update_many(&updated_tasks, state).await {
-        Ok(_) => (
+    async fn validate_and_update(
+        tasks: Vec<Task>,
+        force: bool,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<(Vec<Task>, Vec<String>), AppError> {
+        let ids = tasks.iter().map(|t| t.id).collect::<Vec<uuid::Uuid>>();
+        let stored = service::get_tasks_by_ids(&ids, state.get_tasks_collection())
+            .await?
+            .into_iter()
+            .map(|t| (t.id, t))
+            .collect::<HashMap<uuid::Uuid, Task>>();
+        let mut tasks = tasks
+            .into_iter()
+            .map(|t| {
+                let stored_task = stored.get(&t.id);
+                t.with_server_fields(stored_task)
+            })
+            .collect::<Vec<Task>>();
+        if !force {
+            check_blocked_moves(&tasks, &stored, &state).await?;
+        }
+        let board_ids = tasks
+            .iter()
+            .map(|t| t.board_id)
+            .collect::<HashSet<uuid::Uuid>>();
+        let mut warnings = Vec::new();
+        for board_id in board_ids {
+            let board =
+                board_service::get_board(&board_id.to_string(), state.get_boards_collection())
+                    .await?;
+            // checks parents and WIP limits against the board as it will look after the update
+            let stored_board_tasks =
+                service::get_tasks(&board_id.to_string(), state.get_tasks_collection()).await?;
+            let mut board_tasks = stored_board_tasks
+                .iter()
+                .filter(|t| !tasks.iter().any(|u| u.id == t.id))
+                .cloned()
+                .collect::<Vec<Task>>();
+            board_tasks.extend(tasks.iter().cloned());
+            for task in tasks.iter_mut().filter(|t| t.board_id == board_id) {
+                let placed = place_in_swimlane(task.clone(), &board, &board_tasks)?;
+                if let Some(board_task) = board_tasks.iter_mut().find(|t| t.id == placed.id) {
+                    *board_task = placed.clone();
+                }
+                *task = placed;
+            }
+            let updated = tasks
+                .iter()
+                .filter(|t| t.board_id == board_id)
+                .collect::<Vec<&Task>>();
+            for task in updated.iter() {
+                if let Some(parent_id) = task.parent_task_id {
+                    validate_parent(task, parent_id, &board_tasks)?;
+                }
+            }
+            let moved = updated
+                .iter()
+                .any(|t| stored.get(&t.id).map(|s| s.column_id) != Some(t.column_id));
+            if moved || updated.iter().any(|t| !t.custom_fields.is_empty()) {
+                for task in updated.iter() {
+                    validate_values(
+                        &task.custom_fields,
+                        stored.get(&task.id).map(|t| &t.custom_fields),
+                        &board.custom_fields,
+                    )?;
+                }
+                warnings.extend(check_wip_limits(&board, &stored_board_tasks, &board_tasks)?);
+            }
+        }
+        service::update_many(&tasks, state.clone()).await?;
+        let before = stored.values().cloned().collect::<Vec<Task>>();
+        activity_service::record(
+            task_activities(&before, &tasks, actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        transition_service::record(
+            column_transitions(&before, &tasks),
+            state.get_transitions_collection(),
+        )
+        .await;
+        let notifications = tasks
+            .iter()
+            .flat_map(|t| task_notifications(stored.get(&t.id), t, actor.as_deref()))
+            .collect();
+        notification_service::notify(notifications, state.get_notifications_collection()).await;
+        for changed in automation_service::react_to_updates(&before, &tasks, &state).await {
+            if let Some(task) = tasks.iter_mut().find(|t| t.id == changed.id) {
+                *task = changed;
+            }
+        }
+        chat_service::announce(&before, &tasks, actor.as_deref(), &state).await;
+        Ok((tasks, warnings))
+    }
+
+    let force = params.force.unwrap_or(false);
+    match validate_and_update(updated_tasks, force, user.map(|u| u.0), state).await {
+        Ok((tasks, warnings)) => (
             StatusCode::OK,
             Json(Response {
                 success: true,
-                data: Some(updated_tasks.clone()),
+                data: Some(tasks),
                 error_message: None,
+                warnings: (!warnings.is_empty()).then_some(warnings),
             }),
         ),
         Err(e) => {
@@ -202,12 +538,461 @@ pub async fn task_update_handler(
                 e.to_string()
             );
             error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+/// Refuses moving a blocked task into a done column.
+async fn check_blocked_moves(
+    tasks: &[Task],
+    stored: &HashMap<uuid::Uuid, Task>,
+    state: &AppState,
+) -> Result<(), AppError> {
+    let mut done_columns = board_service::get_done_column_ids(
+        &board_ids_of(tasks.iter()),
+        state.get_boards_collection(),
+    )
+    .await?;
+    let moved_to_done = tasks
+        .iter()
+        .filter(|t| {
+            t.column_id
+                .map(|c| done_columns.contains(&c))
+                .unwrap_or(false)
+                && stored.get(&t.id).map(|s| s.column_id) != Some(t.column_id)
+        })
+        .collect::<Vec<&Task>>();
+    if moved_to_done.is_empty() {
+        return Ok(());
+    }
+    let blockers = get_blockers(
+        moved_to_done.iter().filter_map(|t| stored.get(&t.id)),
+        state,
+    )
+    .await?;
+    done_columns.extend(
+        board_service::get_done_column_ids(
+            &board_ids_of(blockers.iter()),
+            state.get_boards_collection(),
+        )
+        .await?,
+    );
+    let mut blocker_columns = blocker_columns(&blockers);
+    // blockers updated in the same request are considered in their new column
+    tasks.iter().for_each(|t| {
+        if blocker_columns.contains_key(&t.id) {
+            blocker_columns.insert(t.id, t.column_id);
+        }
+    });
+    // dependencies only change through their endpoints, the stored ones are authoritative
+    match moved_to_done.iter().find(|t| {
+        stored
+            .get(&t.id)
+            .is_some_and(|s| is_blocked(&s.blocked_by, &blocker_columns, &done_columns))
+    }) {
+        Some(blocked) => Err(AppError::Conflict(format!(
+            "task {} is blocked and cannot be moved to a done column without force=true",
+            blocked.id
+        ))),
+        None => Ok(()),
+    }
+}
+
+// Returns the dependency graph (blockers and blocked tasks) of a task
+#[axum_macros::debug_handler]
+pub async fn get_task_dependencies_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!("Getting dependencies of task with id {}", task_id);
+
+    async fn get_graph(task_id: &String, state: AppState) -> Result<DependencyGraph, AppError> {
+        let task = service::get_task(task_id, state.get_tasks_collection()).await?;
+        let linked = service::get_linked_tasks(state.get_tasks_collection()).await?;
+        let edges = dependency_edges(task.id, &linked);
+        let mut node_ids = edges
+            .iter()
+            .flat_map(|e| [e.blocker, e.blocked])
+            .collect::<HashSet<uuid::Uuid>>();
+        node_ids.insert(task.id);
+        let node_ids = node_ids.into_iter().collect::<Vec<uuid::Uuid>>();
+        let nodes = service::get_tasks_by_ids(&node_ids, state.get_tasks_collection()).await?;
+        let done_columns = board_service::get_done_column_ids(
+            &board_ids_of(nodes.iter()),
+            state.get_boards_collection(),
+        )
+        .await?;
+        let nodes = nodes
+            .into_iter()
+            .map(|t| DependencyNode {
+                id: t.id,
+                done: t
+                    .column_id
+                    .map(|c| done_columns.contains(&c))
+                    .unwrap_or(false),
+                title: t.title,
+                board_id: t.board_id,
+                column_id: t.column_id,
+            })
+            .collect();
+        Ok(DependencyGraph { nodes, edges })
+    }
+
+    match get_graph(&task_id, state).await {
+        Ok(graph) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(graph),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_task_dependencies_handler] Error getting dependencies of task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Marks a task as blocked by another one
+#[axum_macros::debug_handler]
+pub async fn task_add_dependency_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<AddDependencyRequest>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!(
+        "[task_add_dependency_handler] Task {} blocked by {}",
+        task_id, req.blocked_by
+    );
+
+    async fn validate_and_add(
+        task_id: &String,
+        blocker_id: uuid::Uuid,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Task, AppError> {
+        let task = service::get_task(task_id, state.get_tasks_collection()).await?;
+        service::get_task(&blocker_id.to_string(), state.get_tasks_collection()).await?;
+        let linked = service::get_linked_tasks(state.get_tasks_collection()).await?;
+        validate_dependency(task.id, blocker_id, &linked)?;
+        service::add_dependency(
+            task_id,
+            &blocker_id.to_string(),
+            state.get_tasks_collection(),
+        )
+        .await?;
+        record_change(task, actor, &state).await
+    }
+
+    match validate_and_add(&task_id, req.blocked_by, user.map(|u| u.0), state).await {
+        Ok(task) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(task),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[task_add_dependency_handler] Error adding dependency {} to task {}: {:?}",
+                req.blocked_by,
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Removes a dependency between two tasks
+#[axum_macros::debug_handler]
+pub async fn task_remove_dependency_handler(
+    Path((task_id, blocker_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    debug!(
+        "[task_remove_dependency_handler] Task {} no more blocked by {}",
+        task_id, blocker_id
+    );
+
+    async fn remove(
+        task_id: &String,
+        blocker_id: &String,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Task, AppError> {
+        let task = service::get_task(task_id, state.get_tasks_collection()).await?;
+        service::remove_dependency(task_id, blocker_id, state.get_tasks_collection()).await?;
+        record_change(task, actor, &state).await
+    }
+
+    match remove(&task_id, &blocker_id, user.map(|u| u.0), state).await {
+        Ok(task) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(task),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[task_remove_dependency_handler] Error removing dependency {} from task {}: {:?}",
+                blocker_id,
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Adds the current user to the watchers of a task
+#[axum_macros::debug_handler]
+pub async fn task_watch_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    set_watching(path.0, user, true, state).await
+}
+
+// Removes the current user from the watchers of a task
+#[axum_macros::debug_handler]
+pub async fn task_unwatch_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> impl IntoResponse {
+    set_watching(path.0, user, false, state).await
+}
+
+async fn set_watching(
+    task_id: String,
+    user: String,
+    watching: bool,
+    state: AppState,
+) -> (StatusCode, Json<Response<Task>>) {
+    debug!(
+        "[set_watching] User {} watching task {}: {}",
+        user, task_id, watching
+    );
+
+    async fn update(
+        task_id: &String,
+        user: &String,
+        watching: bool,
+        state: AppState,
+    ) -> Result<Task, AppError> {
+        let task = service::get_task(task_id, state.get_tasks_collection()).await?;
+        if watching {
+            service::add_watcher(task_id, user, state.get_tasks_collection()).await?;
+        } else {
+            service::remove_watcher(task_id, user, state.get_tasks_collection()).await?;
+        }
+        record_change(task, Some(user.clone()), &state).await
+    }
+
+    match update(&task_id, &user, watching, state).await {
+        Ok(task) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(task),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[set_watching] Error updating watchers of task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+/// Records the change made to a task by an update of the store, returns the
+/// updated task.
+async fn record_change(
+    before: Task,
+    actor: Option<String>,
+    state: &AppState,
+) -> Result<Task, AppError> {
+    let after = service::get_task(&before.id.to_string(), state.get_tasks_collection()).await?;
+    activity_service::record(
+        task_activities(&[before], std::slice::from_ref(&after), actor.as_deref()),
+        state.get_activity_collection(),
+    )
+    .await;
+    Ok(after)
+}
+
+// Archives a task, archived tasks are hidden from the board unless asked for
+#[axum_macros::debug_handler]
+pub async fn task_archive_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    set_archived(path.0, true, user.map(|u| u.0), state).await
+}
+
+// Restores an archived task at the bottom of its column
+#[axum_macros::debug_handler]
+pub async fn task_unarchive_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    set_archived(path.0, false, user.map(|u| u.0), state).await
+}
+
+async fn set_archived(
+    task_id: String,
+    archived: bool,
+    actor: Option<String>,
+    state: AppState,
+) -> (StatusCode, Json<Response<Task>>) {
+    debug!("[set_archived] Task {} archived: {}", task_id, archived);
+
+    async fn update(
+        task_id: &String,
+        archived: bool,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<Task, AppError> {
+        let task = service::get_task(task_id, state.get_tasks_collection()).await?;
+        if task.archived_at.is_some() == archived {
+            return Ok(task);
+        }
+        let board_tasks =
+            service::get_tasks(&task.board_id.to_string(), state.get_tasks_collection()).await?;
+        let visible = without_archived(board_tasks.clone(), &[]);
+        let updated = match archived {
+            true => unlink_from_column(&task, &visible)
+                .into_iter()
+                .map(|t| match t.id == task.id {
+                    true => Task {
+                        archived_at: Some(Utc::now()),
+                        ..t
+                    },
+                    false => t,
+                })
+                .collect::<Vec<Task>>(),
+            false => {
+                let column_tasks = visible
+                    .into_iter()
+                    .filter(|t| t.column_id == task.column_id)
+                    .collect::<Vec<Task>>();
+                let restored = Task {
+                    archived_at: None,
+                    ..task.clone()
+                };
+                move_tasks_to_column(&[restored], &column_tasks, task.column_id)
+            }
+        };
+        service::update_many(&updated, state.clone()).await?;
+        let before = board_tasks
+            .into_iter()
+            .filter(|t| updated.iter().any(|u| u.id == t.id))
+            .collect::<Vec<Task>>();
+        activity_service::record(
+            task_activities(&before, &updated, actor.as_deref()),
+            state.get_activity_collection(),
+        )
+        .await;
+        Ok(updated
+            .into_iter()
+            .find(|t| t.id == task.id)
+            .unwrap_or(task))
+    }
+
+    match update(&task_id, archived, actor, state).await {
+        Ok(task) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(task),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[set_archived] Error archiving ({}) task {}: {:?}",
+                archived,
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
             (
                 StatusCode::BAD_REQUEST,
                 Json(Response {
                     success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -218,13 +1003,30 @@ pub async fn task_update_handler(
 #[axum_macros::debug_handler]
 pub async fn task_delete_handler(
     path: Path<String>,
+    Query(params): Query<DeleteTaskParams>,
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
 ) -> impl IntoResponse {
     let task_id = path.0;
-    debug!("[task_delete_handler] Deleting task {}", task_id);
-    match service::delete(&task_id, state.get_tasks_collection()).await {
-        Ok(_) => {
-            let msg = format!("Deleted task with id {}", task_id);
+    let strategy = params.children.unwrap_or_default();
+    debug!(
+        "[task_delete_handler] Moving task {} to trash ({:?} children)",
+        task_id, strategy
+    );
+
+    async fn delete_with_children(
+        task_id: &String,
+        strategy: ChildrenStrategy,
+        deleted_by: Option<String>,
+        state: AppState,
+    ) -> Result<TrashItem, AppError> {
+        let task = service::get_task(task_id, state.get_tasks_collection()).await?;
+        trash_service::trash_task(&task, strategy, deleted_by, &state).await
+    }
+
+    match delete_with_children(&task_id, strategy, user.map(|u| u.0), state).await {
+        Ok(item) => {
+            let msg = format!("Moved task with id {} to trash item {}", task_id, item.id);
             debug!("[task_delete_handler] {}", msg);
             (
                 StatusCode::OK,
@@ -232,18 +1034,20 @@ pub async fn task_delete_handler(
                     success: true,
                     data: Some(msg),
                     error_message: None,
+                    warnings: None,
                 }),
             )
         }
         Err(e) => {
-            let msg = format!("Error in deleting task {}: {}", task_id, e.to_string());
+            let msg = format!("Error in deleting task {}: {}", task_id, e);
             error!("{}", msg);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(Response {
-                    success: true,
+                    success: false,
                     data: None,
                     error_message: Some(msg),
+                    warnings: None,
                 }),
             )
         }
@@ -1,8 +1,14 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
+use crate::custom_field::model::CustomFieldValue;
 use crate::dto::CreateTaskRequest;
+use crate::notification::utils::parse_mentions;
+use crate::task_template::model::TaskTemplate;
+use crate::task_template::utils::expand_template;
 
 #[skip_serializing_none]
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
@@ -11,23 +17,88 @@ pub struct Task {
     pub title: String,
     pub description: Option<String>,
     pub column_id: Option<uuid::Uuid>,
+    /// Row of the board the task sits in, `None` being the default lane.
+    pub swimlane_id: Option<uuid::Uuid>,
+    /// Previous task in the same swimlane and column.
     pub above_task_id: Option<uuid::Uuid>,
+    pub parent_task_id: Option<uuid::Uuid>,
+    /// Tasks (possibly on other boards) that have to be done before this one,
+    /// managed through the dependencies endpoints.
+    #[serde(default)]
+    pub blocked_by: Vec<uuid::Uuid>,
+    pub assignee: Option<String>,
+    /// Users notified when the task moves, managed through the watchers endpoints.
+    #[serde(default)]
+    pub watchers: Vec<String>,
+    /// Users mentioned in the description, computed by the server.
+    #[serde(default)]
+    pub mentions: Vec<String>,
+    /// Values of the custom fields of the board, keyed by field id.
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+    /// Effort of the task in the unit of the team, e.g. story points.
+    pub estimate: Option<f64>,
+    /// Managed through the sprints endpoints.
+    pub sprint_id: Option<uuid::Uuid>,
     pub board_id: uuid::Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// Set while the task is archived, managed through the archive endpoints.
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 impl Task {
-    pub fn from_request(r: CreateTaskRequest) -> Self {
+    /// Builds the task described by a creation request, expanded by the task
+    /// template it refers to.
+    pub fn from_request(
+        r: CreateTaskRequest,
+        template: Option<&TaskTemplate>,
+        creator: Option<String>,
+    ) -> Self {
+        let r = match template {
+            Some(template) => expand_template(template, r, Utc::now()),
+            None => r,
+        };
         Self {
             id: uuid::Uuid::new_v4(),
             title: r.title,
+            mentions: r
+                .description
+                .as_deref()
+                .map(parse_mentions)
+                .unwrap_or_default(),
             description: r.description,
             column_id: r.column_id,
+            swimlane_id: r.swimlane_id,
             above_task_id: r.above_task_id,
+            parent_task_id: r.parent_task_id,
+            blocked_by: Vec::new(),
+            assignee: r.assignee,
+            watchers: creator.into_iter().collect(),
+            custom_fields: r.custom_fields.unwrap_or_default(),
+            estimate: r.estimate,
+            sprint_id: None,
             board_id: r.board_id,
             created_at: Utc::now(),
             updated_at: None,
+            archived_at: None,
+        }
+    }
+
+    /// Overrides the fields that clients cannot set directly with their stored
+    /// (or computed) value.
+    pub fn with_server_fields(self, stored: Option<&Task>) -> Self {
+        Self {
+            blocked_by: stored.map(|t| t.blocked_by.clone()).unwrap_or_default(),
+            watchers: stored.map(|t| t.watchers.clone()).unwrap_or_default(),
+            archived_at: stored.and_then(|t| t.archived_at),
+            sprint_id: stored.and_then(|t| t.sprint_id),
+            mentions: self
+                .description
+                .as_deref()
+                .map(parse_mentions)
+                .unwrap_or_default(),
+            ..self
         }
     }
 
@@ -45,11 +116,40 @@ pub struct SortedTask {
     pub title: String,
     pub description: Option<String>,
     pub column_id: Option<uuid::Uuid>,
+    pub swimlane_id: Option<uuid::Uuid>,
     pub above_task_id: Option<uuid::Uuid>,
+    pub parent_task_id: Option<uuid::Uuid>,
+    pub blocked_by: Vec<uuid::Uuid>,
+    pub blocked: bool,
+    pub assignee: Option<String>,
+    pub watchers: Vec<String>,
+    pub mentions: Vec<String>,
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+    pub estimate: Option<f64>,
+    pub sprint_id: Option<uuid::Uuid>,
     pub position: usize,
     pub board_id: uuid::Uuid,
+    pub subtasks: Option<SubtaskRollup>,
+    pub comment_count: usize,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+/// Completion summary of the direct children of a parent task.
+#[derive(Deserialize, Debug, Clone, Serialize, PartialEq)]
+pub struct SubtaskRollup {
+    pub total: usize,
+    pub done: usize,
+    pub status: RollupStatus,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RollupStatus {
+    NotStarted,
+    InProgress,
+    Done,
 }
 
 impl Task {
@@ -59,11 +159,46 @@ impl Task {
             title: self.title.clone(),
             description: self.description.clone(),
             column_id: self.column_id,
+            swimlane_id: self.swimlane_id,
             above_task_id: self.above_task_id,
+            parent_task_id: self.parent_task_id,
+            blocked_by: self.blocked_by.clone(),
+            blocked: false,
+            assignee: self.assignee.clone(),
+            watchers: self.watchers.clone(),
+            mentions: self.mentions.clone(),
+            custom_fields: self.custom_fields.clone(),
+            estimate: self.estimate,
+            sprint_id: self.sprint_id,
             position: index,
             board_id: self.board_id,
+            subtasks: None,
+            comment_count: 0,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            archived_at: self.archived_at,
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyNode {
+    pub id: uuid::Uuid,
+    pub title: String,
+    pub board_id: uuid::Uuid,
+    pub column_id: Option<uuid::Uuid>,
+    pub done: bool,
+}
+
+/// `blocker` has to be done before `blocked`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DependencyEdge {
+    pub blocker: uuid::Uuid,
+    pub blocked: uuid::Uuid,
+}
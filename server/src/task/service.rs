@@ -1,15 +1,9 @@
-use async_recursion::async_recursion;
 use bson::{doc, Document};
-use chrono::Utc;
-use mongodb::error::{Error, TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT};
-use mongodb::options::{
-    Acknowledgment, ReadConcern, SessionOptions, TransactionOptions, WriteConcern,
-};
 use mongodb::{ClientSession, Collection};
 use tracing::debug;
-use tracing::log::error;
 
 use crate::config::AppState;
+use crate::db::run_transaction;
 use crate::error::AppError::MongoError;
 use crate::error::Result;
 use crate::error::{AppError, TaskRepoError};
@@ -17,20 +11,96 @@ use crate::task::model::Task;
 use crate::task::utils::{doc_to_task, parse_tasks, task_to_doc};
 
 pub async fn get_all_tasks(collection: Collection<Document>) -> Result<Vec<Task>> {
-    let mut cursor = collection.find(None, None).await.map_err(|_e| {
+    let cursor = collection.find(None, None).await.map_err(|_e| {
         debug!("ERROR [get_tasks] {:?}", _e);
-        return TaskRepoError::NotFound;
+        TaskRepoError::NotFound
     })?;
-    return parse_tasks(cursor).await;
+    parse_tasks(cursor).await
 }
 
 pub async fn get_tasks(board_id: &String, collection: Collection<Document>) -> Result<Vec<Task>> {
     let filter = doc! { "board_id": board_id };
-    let mut cursor = collection.find(filter, None).await.map_err(|_e| {
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
         debug!("ERROR [get_tasks] {:?}", _e);
-        return TaskRepoError::NotFound;
+        TaskRepoError::NotFound
     })?;
-    return parse_tasks(cursor).await;
+    parse_tasks(cursor).await
+}
+
+pub async fn get_children(task_id: &String, collection: Collection<Document>) -> Result<Vec<Task>> {
+    let filter = doc! { "parent_task_id": task_id };
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
+        debug!("ERROR [get_children] {:?}", _e);
+        TaskRepoError::NotFound
+    })?;
+    parse_tasks(cursor).await
+}
+
+pub async fn get_tasks_of_sprint(
+    sprint_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Task>> {
+    let filter = doc! { "sprint_id": sprint_id };
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
+        debug!("ERROR [get_tasks_of_sprint] {:?}", _e);
+        TaskRepoError::NotFound
+    })?;
+    parse_tasks(cursor).await
+}
+
+pub async fn get_tasks_of_assignee(
+    assignee: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Task>> {
+    let filter = doc! { "assignee": assignee };
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
+        debug!("ERROR [get_tasks_of_assignee] {:?}", _e);
+        TaskRepoError::NotFound
+    })?;
+    parse_tasks(cursor).await
+}
+
+pub async fn get_tasks_by_ids(
+    task_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<Vec<Task>> {
+    let ids = task_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    let filter = doc! { "id": { "$in": ids } };
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
+        debug!("ERROR [get_tasks_by_ids] {:?}", _e);
+        TaskRepoError::NotFound
+    })?;
+    parse_tasks(cursor).await
+}
+
+/// Returns all the tasks that are blocked by at least another task.
+pub async fn get_linked_tasks(collection: Collection<Document>) -> Result<Vec<Task>> {
+    let filter = doc! { "blocked_by.0": { "$exists": true } };
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
+        debug!("ERROR [get_linked_tasks] {:?}", _e);
+        TaskRepoError::NotFound
+    })?;
+    parse_tasks(cursor).await
+}
+
+/// Returns the tasks blocked by any of the given tasks.
+pub async fn get_blocked_by(
+    task_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<Vec<Task>> {
+    let ids = task_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    let filter = doc! { "blocked_by": { "$in": ids } };
+    let cursor = collection.find(filter, None).await.map_err(|_e| {
+        debug!("ERROR [get_blocked_by] {:?}", _e);
+        TaskRepoError::NotFound
+    })?;
+    parse_tasks(cursor).await
 }
 
 pub async fn get_task(task_id: &String, collection: Collection<Document>) -> Result<Task> {
@@ -59,158 +129,147 @@ pub async fn get_task(task_id: &String, collection: Collection<Document>) -> Res
 
 pub async fn create(task: &Task, collection: Collection<Document>) -> Result<&Task> {
     debug!("[create_task] Creating task with id={}", &task.id);
-    let doc = task_to_doc(&task);
+    let doc = task_to_doc(task);
     collection.insert_one(doc, None).await.map_err(|_e| {
         debug!("ERROR [create_task] {:?}", _e);
-        return TaskRepoError::InvalidTask(_e.to_string());
+        TaskRepoError::InvalidTask(_e.to_string())
     })?;
     Ok(task)
 }
 
-pub async fn update(task: &Task, collection: Collection<Document>) -> Result<Task> {
-    debug!("[update_task] Updating task with id={}", &task.id);
-    let task_id = &task.id.to_string();
-    let update_time = Utc::now();
-    let updated = Task {
-        updated_at: Some(update_time),
-        ..task.clone()
-    };
+/// Updates every task in one transaction, so that either all the writes are
+/// applied or the first failed one is reported and none of them is kept.
+pub async fn update_many(tasks: &Vec<Task>, state: AppState) -> Result<&Vec<Task>> {
+    debug!("[update_many] Updating {} tasks", tasks.len());
+    let task_docs = tasks.iter().map(task_to_doc).collect::<Vec<Document>>();
+    run_transaction(&state.client, |session| {
+        let (task_docs, collection) = (task_docs.clone(), state.get_tasks_collection());
+        Box::pin(async move { update_many_with_session(&collection, task_docs, session).await })
+    })
+    .await?;
+    Ok(tasks)
+}
 
-    let filter = doc! { "id": task_id };
-    let updates = doc! { "$set": task_to_doc(&updated) };
+/// Sets the given task documents, matched by id, within `session`.
+pub async fn update_many_with_session(
+    collection: &Collection<Document>,
+    task_docs: Vec<Document>,
+    session: &mut ClientSession,
+) -> mongodb::error::Result<()> {
+    for task_doc in task_docs {
+        let filter = doc! { "id": task_doc.get_str("id").unwrap_or_default() };
+        collection
+            .update_one_with_session(filter, doc! { "$set": task_doc }, None, session)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Removes the value of a deleted custom field from the tasks of a board.
+pub async fn unset_custom_field(
+    board_id: &String,
+    field_id: &String,
+    collection: Collection<Document>,
+) -> Result<()> {
+    debug!(
+        "[unset_custom_field] Removing custom field {} from tasks of board {}",
+        field_id, board_id
+    );
+    let filter = doc! { "board_id": board_id };
+    let updates = doc! { "$unset": { format!("custom_fields.{}", field_id): "" } };
     collection
-        .update_one(filter, updates, None)
+        .update_many(filter, updates, None)
         .await
-        .map(|res| {
-            return if res.modified_count == 1 {
-                Ok(())
-            } else {
-                Err(AppError::TaskRepo(TaskRepoError::InvalidTask(format!(
-                    "modified_count = {:?} on {}",
-                    res.modified_count, res.matched_count
-                ))))
-            };
-        })
         .map_err(|_e| {
-            debug!("ERROR [update_task] {:?}", _e);
-            return AppError::MongoError(_e);
-        })?
-        .expect(format!("Coudn't update task {}", task_id).as_str());
-
-    Ok(updated)
+            debug!("ERROR [unset_custom_field] {:?}", _e);
+            AppError::MongoError(_e)
+        })?;
+    Ok(())
 }
 
-pub async fn update_many(tasks: &Vec<Task>, state: AppState) -> Result<&Vec<Task>> {
-    let collection = state.get_tasks_collection();
-    let session = &mut state
-        .client
-        .start_session(
-            SessionOptions::builder()
-                .causal_consistency(Some(false))
-                .build(),
-        )
-        .await?;
-
-    async fn execute_transaction(
-        coll: &Collection<Document>,
-        session: &mut ClientSession,
-        tasks: &Vec<Task>,
-    ) -> mongodb::error::Result<()> {
-        // from https://stackoverflow.com/questions/50850309/how-do-i-iterate-over-a-vec-of-functions-returning-futures-in-rust
-        async fn async_action(
-            task: &Task,
-            coll: &Collection<Document>,
-            session: &mut ClientSession,
-        ) -> core::result::Result<(), Error> {
-            let filter = doc! { "id": task.id.to_string() };
-            let updates = doc! { "$set": task_to_doc(&task) };
-            debug!(
-                "Updating task with id {}: {:?}",
-                task.id.clone(),
-                task.clone()
-            );
-            coll.update_one_with_session(filter, updates, None, session)
-                .await
-                .and_then(|_| Ok(()))
-        }
-
-        async fn requests_in_sequence(
-            vals: &Vec<Task>,
-            coll: &Collection<Document>,
-            session: &mut ClientSession,
-        ) -> core::result::Result<(), AppError> {
-            let mut tasks = vals.clone().into_iter().peekable();
-            while let Some(t) = tasks.next() {
-                match async_action(&t, coll, session).await {
-                    Err(e) if tasks.peek().is_none() => return MongoError(e).into(),
-                    _ => { /* Do nothing and try the next source */ }
-                }
-            }
-            Ok(())
+/// Moves the given tasks to a sprint, or back to the backlog when `sprint_id` is `None`.
+pub async fn set_sprint(
+    task_ids: &[uuid::Uuid],
+    sprint_id: Option<uuid::Uuid>,
+    collection: Collection<Document>,
+) -> Result<()> {
+    if task_ids.is_empty() {
+        return Ok(());
+    }
+    debug!(
+        "[set_sprint] Moving {} tasks to sprint {:?}",
+        task_ids.len(),
+        sprint_id
+    );
+    let ids = task_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>();
+    let filter = doc! { "id": { "$in": ids } };
+    let updates = doc! {
+        "$set": {
+            "sprint_id": sprint_id.map(|id| id.to_string()),
+            "updated_at": bson::DateTime::now()
         }
+    };
+    collection.update_many(filter, updates, None).await?;
+    Ok(())
+}
 
-        requests_in_sequence(tasks, coll, session).await.unwrap();
-
-        // An "UnknownTransactionCommitResult" label indicates that it is unknown whether the
-        // commit has satisfied the write concern associated with the transaction. If an error
-        // with this label is returned, it is safe to retry the commit until the write concern is
-        // satisfied or an error without the label is returned.
-        #[async_recursion]
-        async fn retry_commit(
-            session: &mut ClientSession,
-            tentatives: i8,
-        ) -> mongodb::error::Result<()> {
-            debug!("Retring to commit on tentative {}", tentatives);
-            if let Err(err) = session.commit_transaction().await {
-                if err.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) {
-                    if tentatives <= 3 {
-                        retry_commit(session, tentatives + 1).await
-                    } else {
-                        debug!("Failed to commit after {} tentatives", tentatives);
-                        Err(mongodb::error::Error::from(err))
-                    }
-                } else {
-                    error!("Commit got error {}", err.to_string());
-                    Err(mongodb::error::Error::from(err))
-                }
-            } else {
-                debug!("Successfully Committed transaction!");
-                mongodb::error::Result::Ok(())
-            }
-        }
-        retry_commit(session, 1).await
-    }
+pub async fn add_dependency(
+    task_id: &String,
+    blocker_id: &String,
+    collection: Collection<Document>,
+) -> Result<()> {
+    debug!(
+        "[add_dependency] Task id={} blocked by {}",
+        task_id, blocker_id
+    );
+    let filter = doc! { "id": task_id };
+    let updates = doc! {
+        "$addToSet": { "blocked_by": blocker_id },
+        "$set": { "updated_at": bson::DateTime::now() }
+    };
+    collection.update_one(filter, updates, None).await?;
+    Ok(())
+}
 
-    let options = TransactionOptions::builder()
-        .read_concern(ReadConcern::majority())
-        .write_concern(WriteConcern::builder().w(Acknowledgment::Majority).build())
-        .build();
-    session.start_transaction(options).await?;
-
-    // A "TransientTransactionError" label indicates that the entire transaction can be retried
-    // with a reasonable expectation that it will succeed.
-    while let Err(error) = execute_transaction(&collection, session, tasks).await {
-        debug!("Got transaction error {:?}", error);
-        if !error.contains_label(TRANSIENT_TRANSACTION_ERROR) {
-            break;
-        }
-    }
-    Ok(tasks)
+pub async fn add_watcher(
+    task_id: &String,
+    user: &String,
+    collection: Collection<Document>,
+) -> Result<()> {
+    let filter = doc! { "id": task_id };
+    let updates = doc! { "$addToSet": { "watchers": user } };
+    collection.update_one(filter, updates, None).await?;
+    Ok(())
 }
 
-pub async fn delete_tasks_of_column(
-    column_id: &String,
+pub async fn remove_watcher(
+    task_id: &String,
+    user: &String,
     collection: Collection<Document>,
 ) -> Result<()> {
-    debug!("[delete_task] Deleting task for column id={}", column_id);
-    let filter = doc! { "column_id": column_id };
-    collection.delete_many(filter, None).await?;
+    let filter = doc! { "id": task_id };
+    let updates = doc! { "$pull": { "watchers": user } };
+    collection.update_one(filter, updates, None).await?;
     Ok(())
 }
 
-pub async fn delete(task_id: &String, collection: Collection<Document>) -> Result<()> {
-    debug!("[delete_task] Deleting task with id={}", task_id);
+pub async fn remove_dependency(
+    task_id: &String,
+    blocker_id: &String,
+    collection: Collection<Document>,
+) -> Result<()> {
+    debug!(
+        "[remove_dependency] Task id={} no more blocked by {}",
+        task_id, blocker_id
+    );
     let filter = doc! { "id": task_id };
-    collection.delete_one(filter, None).await?;
+    let updates = doc! {
+        "$pull": { "blocked_by": blocker_id },
+        "$set": { "updated_at": bson::DateTime::now() }
+    };
+    collection.update_one(filter, updates, None).await?;
     Ok(())
 }
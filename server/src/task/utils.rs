@@ -1,4 +1,4 @@
-use std::collections::{HashMap, LinkedList};
+use std::collections::{HashMap, HashSet, LinkedList};
 use std::str::FromStr;
 
 use axum::extract::FromRef;
@@ -8,13 +8,16 @@ use mongodb::bson::doc;
 use tokio_stream::StreamExt;
 use uuid::Uuid;
 
+use crate::board::model::Board;
+use crate::custom_field::mapper::{custom_values_to_doc, get_custom_values};
 use crate::error::Result;
 use crate::error::{AppError, TaskRepoError};
-use crate::task::model::{SortedTask, Task};
-use crate::util::get_optional_uuid;
+use crate::task::model::{DependencyEdge, RollupStatus, SortedTask, SubtaskRollup, Task};
+use crate::util::{get_optional_uuid, get_string_list, get_uuid_list};
 
-pub fn map_task_db_to_linked(elems: Vec<Task>) -> LinkedList<SortedTask> {
-    grouped_by_column(&elems)
+/// Sorts the tasks of `boards`, cell by cell.
+pub fn map_task_db_to_linked(elems: Vec<Task>, boards: &[Board]) -> LinkedList<SortedTask> {
+    grouped_by_cell(&elems, boards)
         .into_iter()
         .flat_map(|(_, tasks)| {
             build_hierarchy_set(tasks.clone())
@@ -25,6 +28,206 @@ pub fn map_task_db_to_linked(elems: Vec<Task>) -> LinkedList<SortedTask> {
         .collect::<LinkedList<SortedTask>>()
 }
 
+/// Attaches the completion roll-up of their children to every parent task in the list.
+/// A child counts as done when it sits in one of `done_columns`.
+pub fn with_subtask_rollups(
+    sorted: LinkedList<SortedTask>,
+    done_columns: &HashSet<Uuid>,
+) -> LinkedList<SortedTask> {
+    let mut children: HashMap<Uuid, Vec<Option<Uuid>>> = HashMap::new();
+    for task in sorted.iter() {
+        if let Some(parent_id) = task.parent_task_id {
+            children.entry(parent_id).or_default().push(task.column_id);
+        }
+    }
+    sorted
+        .into_iter()
+        .map(|t| SortedTask {
+            subtasks: children
+                .get(&t.id)
+                .map(|columns| compute_rollup(columns, done_columns)),
+            ..t
+        })
+        .collect()
+}
+
+pub fn compute_rollup(
+    child_columns: &[Option<Uuid>],
+    done_columns: &HashSet<Uuid>,
+) -> SubtaskRollup {
+    let total = child_columns.len();
+    let done = child_columns
+        .iter()
+        .filter(|c| c.map(|id| done_columns.contains(&id)).unwrap_or(false))
+        .count();
+    let status = if total > 0 && done == total {
+        RollupStatus::Done
+    } else if done > 0 {
+        RollupStatus::InProgress
+    } else {
+        RollupStatus::NotStarted
+    };
+    SubtaskRollup {
+        total,
+        done,
+        status,
+    }
+}
+
+/// Checks that `parent_id` can be used as parent of `task`: it must exist in `board_tasks`,
+/// belong to the same board and must not be the task itself or one of its descendants.
+pub fn validate_parent(task: &Task, parent_id: Uuid, board_tasks: &[Task]) -> Result<()> {
+    let by_id: HashMap<Uuid, &Task> = board_tasks.iter().map(|t| (t.id, t)).collect();
+    let parent = by_id.get(&parent_id).ok_or_else(|| {
+        TaskRepoError::InvalidTask(format!("parent task {} not found", parent_id))
+    })?;
+    if parent.board_id != task.board_id {
+        return Err(TaskRepoError::InvalidTask(format!(
+            "parent task {} belongs to another board",
+            parent_id
+        ))
+        .into());
+    }
+    let mut visited = HashSet::new();
+    let mut current = Some(parent_id);
+    while let Some(id) = current {
+        if id == task.id || !visited.insert(id) {
+            return Err(TaskRepoError::InvalidTask(format!(
+                "setting {} as parent of {} would create a cycle",
+                parent_id, task.id
+            ))
+            .into());
+        }
+        current = by_id.get(&id).and_then(|t| t.parent_task_id);
+    }
+    Ok(())
+}
+
+/// Returns the ids of all the descendants of `task_id`, children first.
+pub fn collect_descendants(task_id: Uuid, tasks: &[Task]) -> Vec<Uuid> {
+    let mut result = Vec::new();
+    let mut queue = vec![task_id];
+    while let Some(current) = queue.pop() {
+        for task in tasks.iter() {
+            if task.parent_task_id == Some(current) && !result.contains(&task.id) {
+                result.push(task.id);
+                queue.push(task.id);
+            }
+        }
+    }
+    result
+}
+
+/// Flags as blocked the tasks having at least one blocker outside of `done_columns`.
+/// `blocker_columns` maps each known blocker to the column it currently sits in.
+pub fn with_blocked_flags(
+    sorted: LinkedList<SortedTask>,
+    blocker_columns: &HashMap<Uuid, Option<Uuid>>,
+    done_columns: &HashSet<Uuid>,
+) -> LinkedList<SortedTask> {
+    sorted
+        .into_iter()
+        .map(|t| SortedTask {
+            blocked: is_blocked(&t.blocked_by, blocker_columns, done_columns),
+            ..t
+        })
+        .collect()
+}
+
+pub fn with_comment_counts(
+    sorted: LinkedList<SortedTask>,
+    counts: &HashMap<Uuid, usize>,
+) -> LinkedList<SortedTask> {
+    sorted
+        .into_iter()
+        .map(|t| SortedTask {
+            comment_count: counts.get(&t.id).copied().unwrap_or(0),
+            ..t
+        })
+        .collect()
+}
+
+pub fn is_blocked(
+    blocked_by: &[Uuid],
+    blocker_columns: &HashMap<Uuid, Option<Uuid>>,
+    done_columns: &HashSet<Uuid>,
+) -> bool {
+    blocked_by
+        .iter()
+        .any(|blocker_id| match blocker_columns.get(blocker_id) {
+            // blockers that were deleted in the meantime don't block anymore
+            None => false,
+            Some(column_id) => !column_id
+                .map(|id| done_columns.contains(&id))
+                .unwrap_or(false),
+        })
+}
+
+/// Checks that `task_id` can be marked as blocked by `blocker_id` without creating a cycle,
+/// given the tasks that currently have dependencies.
+pub fn validate_dependency(task_id: Uuid, blocker_id: Uuid, linked_tasks: &[Task]) -> Result<()> {
+    let blockers_of: HashMap<Uuid, &Vec<Uuid>> =
+        linked_tasks.iter().map(|t| (t.id, &t.blocked_by)).collect();
+    let mut visited = HashSet::new();
+    let mut queue = vec![blocker_id];
+    while let Some(current) = queue.pop() {
+        if current == task_id {
+            return Err(TaskRepoError::InvalidTask(format!(
+                "making {} blocked by {} would create a dependency cycle",
+                task_id, blocker_id
+            ))
+            .into());
+        }
+        if visited.insert(current) {
+            if let Some(blockers) = blockers_of.get(&current) {
+                queue.extend(blockers.iter());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the edges reachable from `task_id`, following both its blockers (upstream)
+/// and the tasks it blocks (downstream).
+pub fn dependency_edges(task_id: Uuid, linked_tasks: &[Task]) -> Vec<DependencyEdge> {
+    let all_edges = linked_tasks
+        .iter()
+        .flat_map(|t| {
+            t.blocked_by.iter().map(|blocker| DependencyEdge {
+                blocker: *blocker,
+                blocked: t.id,
+            })
+        })
+        .collect::<Vec<DependencyEdge>>();
+
+    let mut result: Vec<DependencyEdge> = Vec::new();
+    let walk = |upstream: bool, result: &mut Vec<DependencyEdge>| {
+        let mut visited = HashSet::new();
+        let mut queue = vec![task_id];
+        while let Some(current) = queue.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            for edge in all_edges.iter() {
+                let (from, to) = if upstream {
+                    (edge.blocked, edge.blocker)
+                } else {
+                    (edge.blocker, edge.blocked)
+                };
+                if from == current {
+                    if !result.contains(edge) {
+                        result.push(edge.clone());
+                    }
+                    queue.push(to);
+                }
+            }
+        }
+    };
+    walk(true, &mut result);
+    walk(false, &mut result);
+    result
+}
+
 pub fn build_hierarchy_set(tasks: Vec<Task>) -> LinkedList<Task> {
     // Create a hash map to map task IDs to their corresponding task objects.
     let mut task_map = HashMap::new();
@@ -36,7 +239,7 @@ pub fn build_hierarchy_set(tasks: Vec<Task>) -> LinkedList<Task> {
     let mut hierarchy_set = LinkedList::new();
 
     // Traverse the task hierarchy starting from each root task, and add the tasks to the hierarchy set in sorted order.
-    for (task_id, _) in &task_map {
+    for task_id in task_map.keys() {
         let task = task_map.get(task_id).unwrap();
         if task.above_task_id.is_none() {
             add_task_and_children_to_set(&task_map, &mut hierarchy_set, &task.id.to_string());
@@ -56,24 +259,199 @@ pub fn build_hierarchy_set(tasks: Vec<Task>) -> LinkedList<Task> {
     hierarchy_set
 }
 
-pub fn grouped_by_column(tasks: &Vec<Task>) -> Vec<(Option<Uuid>, Vec<Task>)> {
-    let mut elems: HashMap<Option<Uuid>, Vec<Task>> = HashMap::new();
-    for task in tasks.iter() {
-        let mut mapped_for_key = elems
-            .get(&task.column_id)
-            .map(|v| v.clone())
-            .unwrap_or(Vec::new());
-        if mapped_for_key.clone().len() > 0 {
-            mapped_for_key.push(task.clone());
-            elems.insert(task.column_id, mapped_for_key.clone());
-        } else {
-            elems.insert(task.column_id, vec![task.clone()]);
+/// Appends the given tasks, in their current order, below the last task of
+/// their swimlane in the `target` column.
+pub fn move_tasks_to_column(
+    moved: &[Task],
+    target_tasks: &[Task],
+    target: Option<Uuid>,
+) -> Vec<Task> {
+    let mut last_of_lanes: HashMap<Option<Uuid>, Option<Uuid>> = HashMap::new();
+    build_hierarchy_set(moved.to_vec())
+        .into_iter()
+        .map(|t| {
+            let above_task_id = *last_of_lanes.entry(t.swimlane_id).or_insert_with(|| {
+                build_hierarchy_set(
+                    target_tasks
+                        .iter()
+                        .filter(|target_task| target_task.swimlane_id == t.swimlane_id)
+                        .cloned()
+                        .collect(),
+                )
+                .back()
+                .map(|last| last.id)
+            });
+            let task = Task {
+                column_id: target,
+                above_task_id,
+                ..t
+            };
+            last_of_lanes.insert(task.swimlane_id, Some(task.id));
+            task
+        })
+        .collect()
+}
+
+/// Takes a task out of the order of its column, the task below it takes its
+/// place. Returns the tasks to update.
+pub fn unlink_from_column(task: &Task, board_tasks: &[Task]) -> Vec<Task> {
+    let mut updated = vec![task.with_above_task(None)];
+    if let Some(below) = board_tasks.iter().find(|t| {
+        t.id != task.id
+            && t.column_id == task.column_id
+            && t.swimlane_id == task.swimlane_id
+            && t.above_task_id == Some(task.id)
+    }) {
+        updated.push(below.with_above_task(task.above_task_id));
+    }
+    updated
+}
+
+/// Closes the gaps left in the order of their columns by removed tasks: a task
+/// that was below a removed one moves up below the first remaining task above.
+/// Returns the remaining tasks that changed.
+pub fn relink_without(removed: &[Uuid], board_tasks: &[Task]) -> Vec<Task> {
+    let by_id = board_tasks
+        .iter()
+        .map(|t| (t.id, t))
+        .collect::<HashMap<Uuid, &Task>>();
+    board_tasks
+        .iter()
+        .filter(|t| !removed.contains(&t.id))
+        .filter_map(|t| {
+            let mut above_task_id = t.above_task_id;
+            while let Some(above) = above_task_id.filter(|id| removed.contains(id)) {
+                above_task_id = by_id.get(&above).and_then(|a| a.above_task_id);
+            }
+            (above_task_id != t.above_task_id).then(|| t.with_above_task(above_task_id))
+        })
+        .collect()
+}
+
+/// Drops the dependencies and parents of removed tasks that don't exist
+/// anymore, `existing` holding the ids of the tasks that do.
+pub fn without_missing_links(tasks: &[Task], existing: &[uuid::Uuid]) -> Vec<Task> {
+    tasks
+        .iter()
+        .map(|t| Task {
+            blocked_by: t
+                .blocked_by
+                .iter()
+                .filter(|b| existing.contains(b))
+                .cloned()
+                .collect(),
+            parent_task_id: t.parent_task_id.filter(|p| existing.contains(p)),
+            ..t.clone()
+        })
+        .collect()
+}
+
+/// Puts removed tasks back in the order of their columns: below the task that
+/// was above them if it is still in the column, at the top if they were at the
+/// top, at the bottom otherwise. Tasks whose column doesn't exist anymore are
+/// put back with the unassigned tasks, the ones whose swimlane doesn't exist
+/// anymore in the default lane. Returns the restored tasks and the existing
+/// tasks that changed.
+pub fn reinsert_tasks(
+    restored: &[Task],
+    board_tasks: &[Task],
+    column_ids: &[Uuid],
+    swimlane_ids: &[Uuid],
+) -> (Vec<Task>, Vec<Task>) {
+    let mut current = board_tasks
+        .iter()
+        .filter(|t| t.archived_at.is_none())
+        .cloned()
+        .collect::<Vec<Task>>();
+    let mut changed = HashSet::new();
+    // a task goes back after the restored task that was above it
+    let mut pending = restored.to_vec();
+    let mut ordered = Vec::new();
+    while !pending.is_empty() {
+        let (ready, rest): (Vec<Task>, Vec<Task>) = pending.iter().cloned().partition(|t| {
+            t.above_task_id
+                .is_none_or(|above| pending.iter().all(|p| p.id != above))
+        });
+        if ready.is_empty() {
+            ordered.extend(rest);
+            break;
         }
+        ordered.extend(ready);
+        pending = rest;
     }
-    elems
+    for task in ordered.iter() {
+        let column_id = task.column_id.filter(|c| column_ids.contains(c));
+        let swimlane_id = task.swimlane_id.filter(|l| swimlane_ids.contains(l));
+        let in_column = |t: &&Task| t.column_id == column_id && t.swimlane_id == swimlane_id;
+        let above_task_id = match task.above_task_id {
+            None => None,
+            Some(above) if current.iter().filter(in_column).any(|t| t.id == above) => Some(above),
+            Some(_) => build_hierarchy_set(current.iter().filter(in_column).cloned().collect())
+                .back()
+                .map(|t| t.id),
+        };
+        if let Some(below) = current.iter_mut().find(|t| {
+            t.column_id == column_id
+                && t.swimlane_id == swimlane_id
+                && t.above_task_id == above_task_id
+        }) {
+            below.above_task_id = Some(task.id);
+            changed.insert(below.id);
+        }
+        current.push(Task {
+            column_id,
+            swimlane_id,
+            above_task_id,
+            archived_at: None,
+            ..task.clone()
+        });
+    }
+    let restored_ids = restored.iter().map(|t| t.id).collect::<HashSet<Uuid>>();
+    current
+        .into_iter()
+        .filter(|t| restored_ids.contains(&t.id) || changed.contains(&t.id))
+        .partition(|t| restored_ids.contains(&t.id))
+}
+
+/// Tasks that are not archived and not in an archived column.
+pub fn without_archived(tasks: Vec<Task>, archived_columns: &[Uuid]) -> Vec<Task> {
+    tasks
         .into_iter()
-        .map(|(key, values)| (key, values))
-        .collect::<Vec<(Option<Uuid>, Vec<Task>)>>()
+        .filter(|t| {
+            t.archived_at.is_none()
+                && !t
+                    .column_id
+                    .map(|c| archived_columns.contains(&c))
+                    .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// A swimlane and a column of a board, tasks are ordered within their cell.
+pub type Cell = (Option<Uuid>, Option<Uuid>);
+
+/// Groups the tasks by cell, following the order of the boards, then of their
+/// swimlanes and then of their columns. Tasks outside of a lane or a column
+/// come after the others.
+pub fn grouped_by_cell(tasks: &[Task], boards: &[Board]) -> Vec<(Cell, Vec<Task>)> {
+    let mut elems: HashMap<Cell, Vec<Task>> = HashMap::new();
+    for task in tasks.iter() {
+        elems
+            .entry((task.swimlane_id, task.column_id))
+            .or_default()
+            .push(task.clone());
+    }
+    let mut cells = elems.into_iter().collect::<Vec<(Cell, Vec<Task>)>>();
+    cells.sort_by_cached_key(|((swimlane_id, column_id), tasks)| {
+        let board_index = boards.iter().position(|b| b.id == tasks[0].board_id);
+        let board = board_index.map(|i| &boards[i]);
+        let lane_index =
+            board.and_then(|b| b.swimlanes.iter().position(|l| Some(l.id) == *swimlane_id));
+        let column_index =
+            board.and_then(|b| b.columns.iter().position(|c| Some(c.id) == *column_id));
+        [board_index, lane_index, column_index].map(|i| i.unwrap_or(usize::MAX))
+    });
+    cells
 }
 
 fn add_task_and_children_to_set(
@@ -85,9 +463,9 @@ fn add_task_and_children_to_set(
     hierarchy_set.push_back(task.clone());
 
     // Traverse the child tasks recursively and add them to the hierarchy set in sorted order.
-    for (child_task_id, _) in task_map {
+    for child_task_id in task_map.keys() {
         let child_task = task_map.get(child_task_id).unwrap();
-        if child_task.above_task_id == Some(task.id.clone()) {
+        if child_task.above_task_id == Some(task.id) {
             add_task_and_children_to_set(task_map, hierarchy_set, child_task_id);
         }
     }
@@ -108,7 +486,16 @@ pub fn doc_to_task(doc: &bson::document::Document) -> Result<Task> {
     let title = doc.get_str("title")?;
     let description = doc.get_str("description").ok();
     let column_id = get_optional_uuid(doc, "column_id");
+    let swimlane_id = get_optional_uuid(doc, "swimlane_id");
     let above_task_id = get_optional_uuid(doc, "above_task_id");
+    let parent_task_id = get_optional_uuid(doc, "parent_task_id");
+    let blocked_by = get_uuid_list(doc, "blocked_by");
+    let assignee = doc.get_str("assignee").ok();
+    let watchers = get_string_list(doc, "watchers");
+    let mentions = get_string_list(doc, "mentions");
+    let custom_fields = get_custom_values(doc);
+    let estimate = doc.get_f64("estimate").ok();
+    let sprint_id = get_optional_uuid(doc, "sprint_id");
     let board_id = doc.get_str("board_id")?;
     let created_at = bson::DateTime::from_ref(doc.get_datetime("created_at")?);
     let updated_at = doc
@@ -121,10 +508,23 @@ pub fn doc_to_task(doc: &bson::document::Document) -> Result<Task> {
             title: title.to_owned(),
             description: description.map(|d| d.to_string()),
             column_id,
+            swimlane_id,
             above_task_id,
+            parent_task_id,
+            blocked_by,
+            assignee: assignee.map(|a| a.to_owned()),
+            watchers,
+            mentions,
+            custom_fields,
+            estimate,
+            sprint_id,
             board_id: board_uuid,
             created_at: chrono::DateTime::from(created_at),
             updated_at,
+            archived_at: doc
+                .get_datetime("archived_at")
+                .ok()
+                .map(|v| chrono::DateTime::from(*v)),
         }),
         _ => Err(AppError::TaskRepo(TaskRepoError::DecodeError(format!(
             "Task doesnt have id or board_id {} {}",
@@ -139,10 +539,294 @@ pub fn task_to_doc(task: &Task) -> bson::document::Document {
         "title" : task.title.clone(),
         "description" : task.description.clone(),
         "column_id" : task.column_id.map(|v| v.to_string()),
+        "swimlane_id" : task.swimlane_id.map(|v| v.to_string()),
         "above_task_id" : task.above_task_id.map(|v| v.to_string()),
+        "parent_task_id" : task.parent_task_id.map(|v| v.to_string()),
+        "blocked_by" : task.blocked_by.iter().map(|v| v.to_string()).collect::<Vec<String>>(),
+        "assignee" : task.assignee.clone(),
+        "watchers" : task.watchers.clone(),
+        "mentions" : task.mentions.clone(),
+        "custom_fields" : custom_values_to_doc(&task.custom_fields),
+        "estimate" : task.estimate,
+        "sprint_id" : task.sprint_id.map(|v| v.to_string()),
         "board_id" : task.board_id.to_string(),
         "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(task.created_at),
-        "updated_at": task.updated_at.map(|v| <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(v)).clone()
+        "updated_at": task.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+        "archived_at": task.archived_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into)
     };
-    return doc;
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use uuid::Uuid;
+
+    use crate::fixtures::{mock_board, mock_column, mock_task};
+    use crate::swimlane::model::Swimlane;
+    use crate::task::model::{RollupStatus, Task};
+    use crate::task::utils::{
+        collect_descendants, compute_rollup, dependency_edges, grouped_by_cell,
+        map_task_db_to_linked, move_tasks_to_column, reinsert_tasks, relink_without,
+        unlink_from_column, validate_dependency, validate_parent, without_missing_links,
+    };
+
+    #[test]
+    fn it_rejects_parent_cycles() {
+        let board_id = Uuid::new_v4();
+        let root = mock_task(board_id, None);
+        let child = Task {
+            parent_task_id: Some(root.id),
+            ..mock_task(board_id, None)
+        };
+        let grandchild = Task {
+            parent_task_id: Some(child.id),
+            ..mock_task(board_id, None)
+        };
+        let tasks = vec![root.clone(), child.clone(), grandchild.clone()];
+
+        assert!(validate_parent(&root, grandchild.id, &tasks).is_err());
+        assert!(validate_parent(&root, root.id, &tasks).is_err());
+        assert!(validate_parent(&grandchild, root.id, &tasks).is_ok());
+
+        let other_board = mock_task(Uuid::new_v4(), None);
+        let mut all = tasks.clone();
+        all.push(other_board.clone());
+        assert!(validate_parent(&root, other_board.id, &all).is_err());
+        assert_eq!(
+            collect_descendants(root.id, &tasks),
+            vec![child.id, grandchild.id]
+        );
+    }
+
+    #[test]
+    fn it_rolls_up_children_completion() {
+        let done_column = Uuid::new_v4();
+        let done_columns = HashSet::from([done_column]);
+        let todo = Some(Uuid::new_v4());
+
+        let rollup = compute_rollup(&[todo, None], &done_columns);
+        assert_eq!(rollup.status, RollupStatus::NotStarted);
+        let rollup = compute_rollup(&[todo, Some(done_column)], &done_columns);
+        assert_eq!((rollup.done, rollup.status), (1, RollupStatus::InProgress));
+        let rollup = compute_rollup(&[Some(done_column)], &done_columns);
+        assert_eq!(rollup.status, RollupStatus::Done);
+    }
+
+    #[test]
+    fn it_rejects_dependency_cycles() {
+        let a = mock_task(Uuid::new_v4(), None);
+        let b = Task {
+            blocked_by: vec![a.id],
+            ..mock_task(Uuid::new_v4(), None)
+        };
+        let c = Task {
+            blocked_by: vec![b.id],
+            ..mock_task(b.board_id, None)
+        };
+        let linked = vec![b.clone(), c.clone()];
+
+        assert!(validate_dependency(a.id, c.id, &linked).is_err());
+        assert!(validate_dependency(a.id, a.id, &linked).is_err());
+        assert!(validate_dependency(c.id, a.id, &linked).is_ok());
+        assert_eq!(dependency_edges(b.id, &linked).len(), 2);
+        assert_eq!(dependency_edges(a.id, &linked).len(), 2);
+    }
+
+    #[test]
+    fn it_moves_tasks_below_the_last_task_of_the_target_column() {
+        let board_id = Uuid::new_v4();
+        let (from, to) = (Some(Uuid::new_v4()), Some(Uuid::new_v4()));
+        let in_column = |column_id: Option<Uuid>, above: Option<&Task>| Task {
+            column_id,
+            above_task_id: above.map(|t| t.id),
+            ..mock_task(board_id, None)
+        };
+        let target_first = in_column(to, None);
+        let target_last = in_column(to, Some(&target_first));
+        let moved_first = in_column(from, None);
+        let moved_last = in_column(from, Some(&moved_first));
+
+        let moved = move_tasks_to_column(
+            &[moved_last.clone(), moved_first.clone()],
+            &[target_first.clone(), target_last.clone()],
+            to,
+        );
+        let mut all = vec![target_first.clone(), target_last.clone()];
+        all.extend(moved);
+        let order = map_task_db_to_linked(all, &[])
+            .into_iter()
+            .map(|t| (t.id, t.column_id, t.position))
+            .collect::<Vec<(Uuid, Option<Uuid>, usize)>>();
+        assert_eq!(
+            order,
+            vec![
+                (target_first.id, to, 0),
+                (target_last.id, to, 1),
+                (moved_first.id, to, 2),
+                (moved_last.id, to, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_orders_cells_by_swimlane_then_column() {
+        let mut board = mock_board(vec![mock_column("todo", false), mock_column("done", true)]);
+        board.swimlanes = vec![Swimlane::new("top", None), Swimlane::new("bottom", None)];
+        let cell = |lane: usize, column: usize| Task {
+            swimlane_id: Some(board.swimlanes[lane].id),
+            ..mock_task(board.id, Some(board.columns[column].id))
+        };
+        let outside = mock_task(board.id, None);
+        let tasks = vec![
+            outside.clone(),
+            cell(1, 1),
+            cell(1, 0),
+            cell(0, 1),
+            cell(0, 0),
+        ];
+        let order = grouped_by_cell(&tasks, &[board.clone()])
+            .into_iter()
+            .map(|(_, tasks)| tasks[0].id)
+            .collect::<Vec<Uuid>>();
+        assert_eq!(
+            order,
+            vec![
+                tasks[4].id,
+                tasks[3].id,
+                tasks[2].id,
+                tasks[1].id,
+                outside.id
+            ]
+        );
+    }
+
+    #[test]
+    fn it_unlinks_tasks_from_their_column() {
+        let board_id = Uuid::new_v4();
+        let column_id = Some(Uuid::new_v4());
+        let first = Task {
+            column_id,
+            ..mock_task(board_id, None)
+        };
+        let second = Task {
+            column_id,
+            above_task_id: Some(first.id),
+            ..mock_task(board_id, None)
+        };
+        let third = Task {
+            column_id,
+            above_task_id: Some(second.id),
+            ..mock_task(board_id, None)
+        };
+        let tasks = vec![first.clone(), second.clone(), third.clone()];
+
+        let updated = unlink_from_column(&second, &tasks);
+        assert_eq!(updated.len(), 2);
+        assert_eq!(updated[0].above_task_id, None);
+        assert_eq!(updated[1].id, third.id);
+        assert_eq!(updated[1].above_task_id, Some(first.id));
+        assert_eq!(unlink_from_column(&third, &tasks).len(), 1);
+    }
+
+    #[test]
+    fn it_relinks_tasks_below_removed_ones() {
+        let board_id = Uuid::new_v4();
+        let column_id = Some(Uuid::new_v4());
+        let first = Task {
+            column_id,
+            ..mock_task(board_id, None)
+        };
+        let second = Task {
+            column_id,
+            above_task_id: Some(first.id),
+            ..mock_task(board_id, None)
+        };
+        let third = Task {
+            column_id,
+            above_task_id: Some(second.id),
+            ..mock_task(board_id, None)
+        };
+        let fourth = Task {
+            column_id,
+            above_task_id: Some(third.id),
+            ..mock_task(board_id, None)
+        };
+        let tasks = vec![first.clone(), second.clone(), third.clone(), fourth.clone()];
+
+        let updated = relink_without(&[second.id, third.id], &tasks);
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].id, fourth.id);
+        assert_eq!(updated[0].above_task_id, Some(first.id));
+
+        let updated = relink_without(&[first.id], &tasks);
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].id, second.id);
+        assert_eq!(updated[0].above_task_id, None);
+    }
+
+    #[test]
+    fn it_drops_links_to_tasks_that_are_gone() {
+        let board_id = Uuid::new_v4();
+        let (parent, blocker) = (mock_task(board_id, None), mock_task(board_id, None));
+        let purged = Uuid::new_v4();
+        let child = Task {
+            parent_task_id: Some(parent.id),
+            blocked_by: vec![blocker.id, purged],
+            ..mock_task(board_id, None)
+        };
+        let orphan = Task {
+            parent_task_id: Some(purged),
+            ..mock_task(board_id, None)
+        };
+
+        let restored =
+            without_missing_links(&[child.clone(), orphan], &[parent.id, blocker.id, child.id]);
+        assert_eq!(restored[0].parent_task_id, Some(parent.id));
+        assert_eq!(restored[0].blocked_by, vec![blocker.id]);
+        assert_eq!(restored[1].parent_task_id, None);
+    }
+
+    #[test]
+    fn it_reinserts_tasks_at_their_previous_place() {
+        let board_id = Uuid::new_v4();
+        let column_id = Uuid::new_v4();
+        let first = Task {
+            column_id: Some(column_id),
+            ..mock_task(board_id, None)
+        };
+        let second = Task {
+            column_id: Some(column_id),
+            above_task_id: Some(first.id),
+            ..mock_task(board_id, None)
+        };
+        let third = Task {
+            column_id: Some(column_id),
+            above_task_id: Some(second.id),
+            ..mock_task(board_id, None)
+        };
+        let remaining = vec![
+            first.clone(),
+            Task {
+                above_task_id: Some(first.id),
+                ..third.clone()
+            },
+        ];
+
+        let (restored, changed) =
+            reinsert_tasks(std::slice::from_ref(&second), &remaining, &[column_id], &[]);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].above_task_id, Some(first.id));
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].id, third.id);
+        assert_eq!(changed[0].above_task_id, Some(second.id));
+
+        // the column is gone, the task joins the unassigned ones
+        let (restored, changed) =
+            reinsert_tasks(std::slice::from_ref(&second), &remaining, &[], &[]);
+        assert_eq!(restored[0].column_id, None);
+        assert_eq!(restored[0].above_task_id, None);
+        assert!(changed.is_empty());
+    }
 }
@@ -0,0 +1,260 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::dto::{CreateTaskTemplateRequest, Response, UpdateTaskTemplateRequest};
+use crate::error::AppError;
+use crate::task_template::model::TaskTemplate;
+use crate::task_template::service;
+use crate::task_template::utils::validate_task_template;
+
+// Returns the task templates of a board, by name
+#[axum_macros::debug_handler]
+pub async fn get_task_templates_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting task templates of board {}", board_id);
+    match service::get_task_templates(&board_id, state.get_task_templates_collection()).await {
+        Ok(templates) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(templates),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_task_templates_handler] Error getting task templates of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+#[axum_macros::debug_handler]
+pub async fn get_task_template_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let template_id = path.0;
+    debug!("Getting task template {}", template_id);
+    match service::get_task_template(&template_id, state.get_task_templates_collection()).await {
+        Ok(template) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(template),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_task_template_handler] Error getting task template {}: {:?}",
+                template_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Creates a new task template on a board
+#[axum_macros::debug_handler]
+pub async fn task_template_create_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    Json(req): Json<CreateTaskTemplateRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[task_template_create_handler] Creating task template {} on board {}",
+        req.name, board_id
+    );
+
+    async fn validate_and_create(
+        board_id: &String,
+        req: CreateTaskTemplateRequest,
+        state: AppState,
+    ) -> Result<TaskTemplate, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let template = TaskTemplate::from_request(board.id, req);
+        validate_task_template(&template, &board)?;
+        service::create(&template, state.get_task_templates_collection()).await?;
+        Ok(template)
+    }
+
+    match validate_and_create(&board_id, req, state).await {
+        Ok(template) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(template),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[task_template_create_handler] Error creating task template on board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Updates the fields a task template pre-fills
+#[axum_macros::debug_handler]
+pub async fn task_template_update_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    Json(req): Json<UpdateTaskTemplateRequest>,
+) -> impl IntoResponse {
+    let template_id = path.0;
+    debug!(
+        "[task_template_update_handler] Updating task template {}",
+        template_id
+    );
+
+    async fn validate_and_update(
+        template_id: &String,
+        req: UpdateTaskTemplateRequest,
+        state: AppState,
+    ) -> Result<TaskTemplate, AppError> {
+        let stored =
+            service::get_task_template(template_id, state.get_task_templates_collection()).await?;
+        let reset_runs = req
+            .recurrence
+            .as_ref()
+            .is_some_and(|r| *r != stored.recurrence);
+        let template = stored.with_update_request(req);
+        let board = board_service::get_board(
+            &template.board_id.to_string(),
+            state.get_boards_collection(),
+        )
+        .await?;
+        validate_task_template(&template, &board)?;
+        service::update(&template, reset_runs, state.get_task_templates_collection()).await?;
+        Ok(template)
+    }
+
+    match validate_and_update(&template_id, req, state).await {
+        Ok(template) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(template),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[task_template_update_handler] Error updating task template {}: {:?}",
+                template_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes a task template, the tasks created from it are left untouched
+#[axum_macros::debug_handler]
+pub async fn task_template_delete_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let template_id = path.0;
+    debug!(
+        "[task_template_delete_handler] Deleting task template {}",
+        template_id
+    );
+
+    async fn delete(template_id: &String, state: AppState) -> Result<(), AppError> {
+        let template =
+            service::get_task_template(template_id, state.get_task_templates_collection()).await?;
+        service::delete(
+            &template.id.to_string(),
+            state.get_task_templates_collection(),
+        )
+        .await
+    }
+
+    match delete(&template_id, state).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(template_id),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[task_template_delete_handler] Error deleting task template {}: {:?}",
+                template_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+use crate::config::AppState;
+use crate::task_template::service;
+
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Creates the tasks of the recurring task templates that are due, once at
+/// startup and every minute after.
+pub fn spawn_scheduler(state: AppState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match service::run_due(Utc::now(), &state).await {
+                Ok(created) => debug!("[spawn_scheduler] Created {} recurring tasks", created),
+                Err(e) => error!(
+                    "ERROR [spawn_scheduler] Couldn't create the recurring tasks: {:?}",
+                    e
+                ),
+            }
+        }
+    })
+}
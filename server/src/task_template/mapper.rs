@@ -0,0 +1,76 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::custom_field::mapper::{custom_values_to_doc, get_custom_values};
+use crate::error::{AppError, Result, TemplateRepoError};
+use crate::task_template::model::TaskTemplate;
+use crate::util::{get_optional_uuid, get_string_list};
+
+pub async fn parse_task_templates(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<TaskTemplate>> {
+    let mut result: Vec<TaskTemplate> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_task_template(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_task_template(doc: &bson::document::Document) -> Result<TaskTemplate> {
+    let id = doc.get_str("id")?;
+    let board_id = doc.get_str("board_id")?;
+    let name = doc.get_str("name")?;
+    let title_pattern = doc.get_str("title_pattern")?;
+    let created_at = doc.get_datetime("created_at")?;
+    match (Uuid::from_str(id), Uuid::from_str(board_id)) {
+        (Ok(template_uuid), Ok(board_uuid)) => Ok(TaskTemplate {
+            id: template_uuid,
+            board_id: board_uuid,
+            name: name.to_owned(),
+            title_pattern: title_pattern.to_owned(),
+            description: doc.get_str("description").ok().map(|d| d.to_owned()),
+            custom_fields: get_custom_values(doc),
+            checklist: get_string_list(doc, "checklist"),
+            column_id: get_optional_uuid(doc, "column_id"),
+            recurrence: doc
+                .get_document("recurrence")
+                .ok()
+                .and_then(|r| bson::from_document(r.clone()).ok()),
+            last_run_at: doc
+                .get_datetime("last_run_at")
+                .ok()
+                .map(|v| chrono::DateTime::from(*v)),
+            last_task_id: get_optional_uuid(doc, "last_task_id"),
+            created_at: chrono::DateTime::from(*created_at),
+            updated_at: doc
+                .get_datetime("updated_at")
+                .ok()
+                .map(|v| chrono::DateTime::from(*v)),
+        }),
+        _ => Err(AppError::TemplateRepo(TemplateRepoError::InvalidTemplate(
+            format!("Task template doesnt have id or board_id {}", id),
+        ))),
+    }
+}
+
+pub fn task_template_to_doc(template: &TaskTemplate) -> bson::document::Document {
+    doc! {
+        "id" : template.id.to_string(),
+        "board_id" : template.board_id.to_string(),
+        "name" : template.name.clone(),
+        "title_pattern" : template.title_pattern.clone(),
+        "description" : template.description.clone(),
+        "custom_fields" : custom_values_to_doc(&template.custom_fields),
+        "checklist" : template.checklist.clone(),
+        "column_id" : template.column_id.map(|c| c.to_string()),
+        "recurrence" : template.recurrence.as_ref().and_then(|r| bson::to_document(r).ok()),
+        "last_run_at" : template.last_run_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+        "last_task_id" : template.last_task_id.map(|t| t.to_string()),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(template.created_at),
+        "updated_at" : template.updated_at.map(<chrono::DateTime<Utc> as Into<bson::DateTime>>::into),
+    }
+}
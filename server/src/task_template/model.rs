@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::custom_field::model::CustomFieldValue;
+use crate::dto::{CreateTaskTemplateRequest, UpdateTaskTemplateRequest};
+
+/// Skeleton of the tasks of a board that always start the same way.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct TaskTemplate {
+    pub id: uuid::Uuid,
+    pub board_id: uuid::Uuid,
+    pub name: String,
+    /// Title of the created tasks, `{title}` is replaced by the title given on
+    /// creation and `{date}` by the day of creation.
+    pub title_pattern: String,
+    pub description: Option<String>,
+    /// Values of the custom fields of the board the created tasks start with.
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+    /// Items appended to the description of the created tasks as a task list.
+    #[serde(default)]
+    pub checklist: Vec<String>,
+    /// Column of the created tasks when none is given.
+    pub column_id: Option<uuid::Uuid>,
+    /// Schedule the template creates tasks on by itself, in its column.
+    pub recurrence: Option<Recurrence>,
+    /// Scheduled time of the last task created by the recurrence, managed by
+    /// the scheduler.
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Task created by the last run of the recurrence.
+    pub last_task_id: Option<uuid::Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// When a task template creates tasks, times are in UTC.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Recurrence {
+    #[serde(flatten)]
+    pub rule: RecurrenceRule,
+    /// Time of day of the daily, weekly and monthly rules, midnight by default.
+    #[serde(default)]
+    pub hour: u32,
+    #[serde(default)]
+    pub minute: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "every", rename_all = "snake_case")]
+pub enum RecurrenceRule {
+    Day,
+    /// On the given weekdays, e.g. `["mon", "thu"]`.
+    Week {
+        weekdays: Vec<Weekday>,
+    },
+    /// On the given day of the month, the last day of the months that are
+    /// shorter.
+    Month {
+        day: u32,
+    },
+    /// Cron expression of five fields: minute, hour, day of month, month and
+    /// day of week.
+    Cron {
+        expression: String,
+    },
+}
+
+impl TaskTemplate {
+    pub fn from_request(board_id: uuid::Uuid, r: CreateTaskTemplateRequest) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            board_id,
+            name: r.name.trim().to_owned(),
+            title_pattern: r.title_pattern,
+            description: r.description,
+            custom_fields: r.custom_fields.unwrap_or_default(),
+            checklist: r.checklist.unwrap_or_default(),
+            column_id: r.column_id,
+            recurrence: r.recurrence,
+            last_run_at: None,
+            last_task_id: None,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    /// A new recurrence starts from the time of the update.
+    pub fn with_update_request(self, r: UpdateTaskTemplateRequest) -> Self {
+        let (recurrence, last_run_at, last_task_id) = match r.recurrence {
+            Some(recurrence) if recurrence != self.recurrence => (recurrence, None, None),
+            _ => (self.recurrence, self.last_run_at, self.last_task_id),
+        };
+        Self {
+            recurrence,
+            last_run_at,
+            last_task_id,
+            name: r.name.map(|n| n.trim().to_owned()).unwrap_or(self.name),
+            title_pattern: r.title_pattern.unwrap_or(self.title_pattern),
+            description: r.description.or(self.description),
+            custom_fields: r.custom_fields.unwrap_or(self.custom_fields),
+            checklist: r.checklist.unwrap_or(self.checklist),
+            column_id: r.column_id.or(self.column_id),
+            updated_at: Some(Utc::now()),
+            ..self
+        }
+    }
+}
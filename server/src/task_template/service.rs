@@ -0,0 +1,230 @@
+use bson::{doc, Bson, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::activity::service as activity_service;
+use crate::activity::utils::task_activities;
+use crate::automation::service as automation_service;
+use crate::automation::utils::TaskEvent;
+use crate::board::service as board_service;
+use crate::board::utils::check_wip_limits;
+use crate::config::AppState;
+use crate::dto::CreateTaskRequest;
+use crate::error::{AppError, Result, TaskRepoError, TemplateRepoError};
+use crate::notification::service as notification_service;
+use crate::notification::utils::task_notifications;
+use crate::swimlane::utils::place_in_swimlane;
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::task::utils::{move_tasks_to_column, without_archived};
+use crate::task_template::mapper::{
+    doc_to_task_template, parse_task_templates, task_template_to_doc,
+};
+use crate::task_template::model::TaskTemplate;
+use crate::task_template::utils::due_run;
+
+/// Returns the task templates of a board, by name.
+pub async fn get_task_templates(
+    board_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<TaskTemplate>> {
+    let options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+    let cursor = collection
+        .find(doc! { "board_id": board_id }, options)
+        .await?;
+    parse_task_templates(cursor).await
+}
+
+pub async fn get_task_template(
+    template_id: &String,
+    collection: Collection<Document>,
+) -> Result<TaskTemplate> {
+    let template_opt = collection
+        .find_one(doc! { "id": template_id }, None)
+        .await
+        .map_err(|e| {
+            debug!(
+                "Error while getting task template with id {}: {}",
+                template_id, e
+            );
+            AppError::MongoError(e)
+        })?;
+    match template_opt {
+        Some(doc) => doc_to_task_template(&doc),
+        None => Err(AppError::TemplateRepo(TemplateRepoError::NotFound)),
+    }
+}
+
+pub async fn create(
+    template: &TaskTemplate,
+    collection: Collection<Document>,
+) -> Result<&TaskTemplate> {
+    debug!(
+        "[create_task_template] Creating task template with id={}",
+        &template.id
+    );
+    collection
+        .insert_one(task_template_to_doc(template), None)
+        .await?;
+    Ok(template)
+}
+
+/// Saves the edited fields of a template. The runs of its recurrence are left
+/// to the scheduler, which may claim one meanwhile, unless `reset_runs` when
+/// the recurrence changed.
+pub async fn update(
+    template: &TaskTemplate,
+    reset_runs: bool,
+    collection: Collection<Document>,
+) -> Result<&TaskTemplate> {
+    debug!(
+        "[update_task_template] Updating task template with id={}",
+        &template.id
+    );
+    let filter = doc! { "id": template.id.to_string() };
+    let mut fields = task_template_to_doc(template);
+    fields.remove("last_run_at");
+    fields.remove("last_task_id");
+    if reset_runs {
+        fields.insert("last_run_at", Bson::Null);
+        fields.insert("last_task_id", Bson::Null);
+    }
+    collection
+        .update_one(filter, doc! { "$set": fields }, None)
+        .await?;
+    Ok(template)
+}
+
+pub async fn delete(template_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!(
+        "[delete_task_template] Deleting task template with id={}",
+        template_id
+    );
+    collection
+        .delete_one(doc! { "id": template_id }, None)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_of_board(board_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!(
+        "[delete_of_board] Deleting task templates of board {}",
+        board_id
+    );
+    collection
+        .delete_many(doc! { "board_id": board_id }, None)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_recurring(collection: Collection<Document>) -> Result<Vec<TaskTemplate>> {
+    let cursor = collection
+        .find(doc! { "recurrence": { "$ne": null } }, None)
+        .await?;
+    parse_task_templates(cursor).await
+}
+
+/// Records a run of the recurrence of a template unless another run got
+/// recorded since the template was read, returns whether it was recorded.
+async fn claim_run(
+    template: &TaskTemplate,
+    run_at: DateTime<Utc>,
+    collection: Collection<Document>,
+) -> Result<bool> {
+    let filter = doc! {
+        "id": template.id.to_string(),
+        "last_run_at": template.last_run_at.map(<DateTime<Utc> as Into<bson::DateTime>>::into),
+    };
+    let updates = doc! {
+        "$set": { "last_run_at": <DateTime<Utc> as Into<bson::DateTime>>::into(run_at) },
+    };
+    let result = collection.update_one(filter, updates, None).await?;
+    Ok(result.modified_count == 1)
+}
+
+/// Creates the task of a run of a template at the bottom of its column.
+async fn create_recurring_task(template: &TaskTemplate, state: &AppState) -> Result<Task> {
+    let board_id = template.board_id.to_string();
+    let board = board_service::get_board(&board_id, state.get_boards_collection()).await?;
+    let request = CreateTaskRequest {
+        title: String::new(),
+        description: None,
+        column_id: None,
+        swimlane_id: None,
+        above_task_id: None,
+        parent_task_id: None,
+        assignee: None,
+        board_id: template.board_id,
+        custom_fields: None,
+        estimate: None,
+        template_id: Some(template.id),
+    };
+    let task = Task::from_request(request, Some(template), None);
+    if task.column_id.is_some_and(|c| board.column(c).is_none()) {
+        return Err(TaskRepoError::InvalidTask(String::from("unknown column")).into());
+    }
+    let board_tasks = without_archived(
+        task_service::get_tasks(&board_id, state.get_tasks_collection()).await?,
+        &board.archived_column_ids(),
+    );
+    let task = place_in_swimlane(task, &board, &board_tasks)?;
+    let column_tasks = board_tasks
+        .iter()
+        .filter(|t| t.column_id == task.column_id)
+        .cloned()
+        .collect::<Vec<Task>>();
+    let task = move_tasks_to_column(&[task], &column_tasks, template.column_id).remove(0);
+    let mut after = board_tasks.clone();
+    after.push(task.clone());
+    for warning in check_wip_limits(&board, &board_tasks, &after)? {
+        debug!("[create_recurring_task] {}", warning);
+    }
+    task_service::create(&task, state.get_tasks_collection()).await?;
+    activity_service::record(
+        task_activities(&[], std::slice::from_ref(&task), None),
+        state.get_activity_collection(),
+    )
+    .await;
+    notification_service::notify(
+        task_notifications(None, &task, None),
+        state.get_notifications_collection(),
+    )
+    .await;
+    automation_service::react(task.board_id, vec![TaskEvent::Created(task.clone())], state).await;
+    Ok(task)
+}
+
+/// Creates the tasks of the recurring templates whose scheduled time has come,
+/// returns how many. A run is recorded before its task gets created so that it
+/// happens once across restarts and running instances.
+pub async fn run_due(now: DateTime<Utc>, state: &AppState) -> Result<usize> {
+    let collection = state.get_task_templates_collection();
+    let mut created = 0;
+    for template in get_recurring(collection.clone()).await? {
+        let Some(run_at) = due_run(&template, now) else {
+            continue;
+        };
+        if !claim_run(&template, run_at, collection.clone()).await? {
+            continue;
+        }
+        match create_recurring_task(&template, state).await {
+            Ok(task) => {
+                created += 1;
+                collection
+                    .update_one(
+                        doc! { "id": template.id.to_string() },
+                        doc! { "$set": { "last_task_id": task.id.to_string() } },
+                        None,
+                    )
+                    .await?;
+            }
+            Err(e) => error!(
+                "ERROR [run_due] Couldn't create the task of template {} due at {}: {:?}",
+                template.id, run_at, e
+            ),
+        }
+    }
+    Ok(created)
+}
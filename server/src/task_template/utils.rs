@@ -0,0 +1,405 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::board::model::Board;
+use crate::custom_field::utils::validate_values;
+use crate::dto::CreateTaskRequest;
+use crate::error::{Result, TemplateRepoError};
+use crate::task_template::model::{Recurrence, RecurrenceRule, TaskTemplate};
+
+pub fn validate_task_template(template: &TaskTemplate, board: &Board) -> Result<()> {
+    let invalid = |msg: &str| -> Result<()> {
+        Err(TemplateRepoError::InvalidTemplate(String::from(msg)).into())
+    };
+    if template.name.is_empty() {
+        return invalid("name cannot be empty");
+    }
+    if template.title_pattern.trim().is_empty() {
+        return invalid("title pattern cannot be empty");
+    }
+    if template.checklist.iter().any(|item| item.trim().is_empty()) {
+        return invalid("checklist items cannot be empty");
+    }
+    if template
+        .column_id
+        .is_some_and(|c| board.column(c).is_none())
+    {
+        return invalid("unknown column");
+    }
+    if let Some(recurrence) = &template.recurrence {
+        if template.column_id.is_none() {
+            return invalid("recurring templates need a column");
+        }
+        Schedule::of(recurrence)?;
+    }
+    validate_values(&template.custom_fields, None, &board.custom_fields)
+}
+
+/// Fills a task creation request from a template. The values given in the
+/// request win over the ones of the template, the checklist is appended to the
+/// description.
+pub fn expand_template(
+    template: &TaskTemplate,
+    r: CreateTaskRequest,
+    now: DateTime<Utc>,
+) -> CreateTaskRequest {
+    let title = template
+        .title_pattern
+        .replace("{title}", r.title.trim())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .trim()
+        .to_owned();
+    let checklist = template
+        .checklist
+        .iter()
+        .map(|item| format!("- [ ] {}", item.trim()))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let description = match (
+        r.description.or_else(|| template.description.clone()),
+        checklist.is_empty(),
+    ) {
+        (description, true) => description,
+        (Some(description), false) => Some(format!("{}\n\n{}", description.trim_end(), checklist)),
+        (None, false) => Some(checklist),
+    };
+    let mut custom_fields = template.custom_fields.clone();
+    custom_fields.extend(r.custom_fields.unwrap_or_default());
+    CreateTaskRequest {
+        title,
+        description,
+        column_id: r.column_id.or(template.column_id),
+        custom_fields: Some(custom_fields),
+        ..r
+    }
+}
+
+/// Days a recurrence is looked for in, the rarest rules fire every four years.
+const MAX_SCHEDULE_DAYS: i64 = 4 * 366;
+
+/// Days and times of day a recurrence fires on.
+struct Schedule {
+    days: Box<dyn Fn(NaiveDate) -> bool>,
+    times: Vec<NaiveTime>,
+}
+
+impl Schedule {
+    fn of(recurrence: &Recurrence) -> Result<Schedule> {
+        let invalid = |msg: String| -> crate::error::AppError {
+            TemplateRepoError::InvalidTemplate(msg).into()
+        };
+        let time =
+            NaiveTime::from_hms_opt(recurrence.hour, recurrence.minute, 0).ok_or_else(|| {
+                invalid(format!(
+                    "invalid time of day {}:{}",
+                    recurrence.hour, recurrence.minute
+                ))
+            })?;
+        let days: Box<dyn Fn(NaiveDate) -> bool> = match &recurrence.rule {
+            RecurrenceRule::Day => Box::new(|_| true),
+            RecurrenceRule::Week { weekdays } => {
+                if weekdays.is_empty() {
+                    return Err(invalid(String::from("weekly recurrences need weekdays")));
+                }
+                let weekdays = weekdays.clone();
+                Box::new(move |day| weekdays.contains(&day.weekday()))
+            }
+            RecurrenceRule::Month { day } => {
+                if !(1..=31).contains(day) {
+                    return Err(invalid(format!("invalid day of month {}", day)));
+                }
+                let day = *day;
+                Box::new(move |date| date.day() == day.min(days_in_month(date)))
+            }
+            RecurrenceRule::Cron { expression } => {
+                return parse_cron(expression)
+                    .ok_or_else(|| invalid(format!("invalid cron expression {}", expression)))
+            }
+        };
+        Ok(Schedule {
+            days,
+            times: vec![time],
+        })
+    }
+
+    /// Latest time the schedule fires at after `after` and up to `until`.
+    fn latest_between(&self, after: DateTime<Utc>, until: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let first_day = after.date_naive();
+        let mut day = until.date_naive();
+        for _ in 0..=MAX_SCHEDULE_DAYS {
+            if day < first_day {
+                break;
+            }
+            if (self.days)(day) {
+                let latest = self
+                    .times
+                    .iter()
+                    .rev()
+                    .map(|time| Utc.from_utc_datetime(&day.and_time(*time)))
+                    .find(|at| *at > after && *at <= until);
+                if latest.is_some() {
+                    return latest;
+                }
+            }
+            day = day.pred_opt()?;
+        }
+        None
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let next_month = match date.month() {
+        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+    };
+    next_month
+        .and_then(|d| d.pred_opt())
+        .map_or(31, |d| d.day())
+}
+
+/// Values of a cron field, made of comma separated values, ranges (`1-5`),
+/// wildcards and steps (`*/15`, `0-30/10`).
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Option<Vec<u32>> {
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<usize>().ok().filter(|s| *s > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = match (range, range.split_once('-')) {
+            ("*", _) => (min, max),
+            (_, Some((start, end))) => (start.parse().ok()?, end.parse().ok()?),
+            (value, None) => {
+                let value = value.parse().ok()?;
+                (value, if step > 1 { max } else { value })
+            }
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        values.extend((start..=end).step_by(step));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Some(values)
+}
+
+/// Schedule of a cron expression. Like cron, a day matches either its day of
+/// month or its day of week when both are restricted.
+fn parse_cron(expression: &str) -> Option<Schedule> {
+    let fields = expression.split_whitespace().collect::<Vec<&str>>();
+    let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+        return None;
+    };
+    let minutes = parse_cron_field(minute, 0, 59)?;
+    let hours = parse_cron_field(hour, 0, 23)?;
+    let days_of_month = parse_cron_field(day_of_month, 1, 31)?;
+    let months = parse_cron_field(month, 1, 12)?;
+    // 0 and 7 are both sunday
+    let days_of_week = parse_cron_field(day_of_week, 0, 7)?
+        .into_iter()
+        .map(|d| d % 7)
+        .collect::<Vec<u32>>();
+    let (any_day_of_month, any_day_of_week) =
+        (day_of_month.starts_with('*'), day_of_week.starts_with('*'));
+    let days = move |date: NaiveDate| {
+        let by_month = days_of_month.contains(&date.day());
+        let by_week = days_of_week.contains(&date.weekday().num_days_from_sunday());
+        let by_day = match (any_day_of_month, any_day_of_week) {
+            (false, false) => by_month || by_week,
+            _ => by_month && by_week,
+        };
+        months.contains(&date.month()) && by_day
+    };
+    let times = hours
+        .iter()
+        .flat_map(|h| {
+            minutes
+                .iter()
+                .filter_map(|m| NaiveTime::from_hms_opt(*h, *m, 0))
+        })
+        .collect();
+    Some(Schedule {
+        days: Box::new(days),
+        times,
+    })
+}
+
+/// Latest scheduled time of the recurrence of a template that has come and has
+/// not run yet. The recurrence starts when it was set on the template, the
+/// times missed while the server was down are merged into the latest one.
+pub fn due_run(template: &TaskTemplate, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let schedule = Schedule::of(template.recurrence.as_ref()?).ok()?;
+    let since = template
+        .last_run_at
+        .or(template.updated_at)
+        .unwrap_or(template.created_at);
+    schedule.latest_between(since, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::{TimeZone, Utc, Weekday};
+    use uuid::Uuid;
+
+    use crate::custom_field::model::CustomFieldValue;
+    use crate::dto::CreateTaskRequest;
+    use crate::task_template::model::{Recurrence, RecurrenceRule, TaskTemplate};
+    use crate::task_template::utils::{due_run, expand_template, Schedule};
+
+    #[test]
+    fn it_expands_task_templates() {
+        let (column_id, severity, team) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let template = TaskTemplate {
+            id: Uuid::new_v4(),
+            board_id: Uuid::new_v4(),
+            name: String::from("bug report"),
+            title_pattern: String::from("Bug: {title} ({date})"),
+            description: Some(String::from("## Steps to reproduce\n")),
+            custom_fields: BTreeMap::from([
+                (
+                    severity.to_string(),
+                    CustomFieldValue::Text(String::from("Minor")),
+                ),
+                (
+                    team.to_string(),
+                    CustomFieldValue::Text(String::from("web")),
+                ),
+            ]),
+            checklist: vec![String::from("Reproduced"), String::from("Test added")],
+            column_id: Some(column_id),
+            recurrence: None,
+            last_run_at: None,
+            last_task_id: None,
+            created_at: Utc::now(),
+            updated_at: None,
+        };
+        let request = CreateTaskRequest {
+            title: String::from(" login fails "),
+            description: None,
+            column_id: None,
+            swimlane_id: None,
+            above_task_id: None,
+            parent_task_id: None,
+            assignee: None,
+            board_id: template.board_id,
+            custom_fields: Some(BTreeMap::from([(
+                severity.to_string(),
+                CustomFieldValue::Text(String::from("Critical")),
+            )])),
+            estimate: None,
+            template_id: Some(template.id),
+        };
+        let now = Utc.with_ymd_and_hms(2023, 3, 6, 9, 0, 0).unwrap();
+
+        let expanded = expand_template(&template, request.clone(), now);
+        assert_eq!(expanded.title, "Bug: login fails (2023-03-06)");
+        assert_eq!(
+            expanded.description.as_deref(),
+            Some("## Steps to reproduce\n\n- [ ] Reproduced\n- [ ] Test added")
+        );
+        assert_eq!(expanded.column_id, Some(column_id));
+        let custom_fields = expanded.custom_fields.unwrap();
+        assert_eq!(
+            custom_fields.get(&severity.to_string()),
+            Some(&CustomFieldValue::Text(String::from("Critical")))
+        );
+        assert_eq!(custom_fields.len(), 2);
+
+        let given_column = Uuid::new_v4();
+        let expanded = expand_template(
+            &TaskTemplate {
+                checklist: Vec::new(),
+                ..template
+            },
+            CreateTaskRequest {
+                description: Some(String::from("as described")),
+                column_id: Some(given_column),
+                ..request
+            },
+            now,
+        );
+        assert_eq!(expanded.description.as_deref(), Some("as described"));
+        assert_eq!(expanded.column_id, Some(given_column));
+    }
+
+    #[test]
+    fn it_finds_the_due_runs_of_recurrences() {
+        let at = |d: u32, h: u32, m: u32| Utc.with_ymd_and_hms(2023, 3, d, h, m, 0).unwrap();
+        let recurrence = |rule: RecurrenceRule| Recurrence {
+            rule,
+            hour: 9,
+            minute: 30,
+        };
+        let template = TaskTemplate {
+            id: Uuid::new_v4(),
+            board_id: Uuid::new_v4(),
+            name: String::from("dependency review"),
+            title_pattern: String::from("Dependency review {date}"),
+            description: None,
+            custom_fields: BTreeMap::new(),
+            checklist: Vec::new(),
+            column_id: Some(Uuid::new_v4()),
+            // monday 2023-03-06
+            recurrence: Some(recurrence(RecurrenceRule::Week {
+                weekdays: vec![Weekday::Mon, Weekday::Thu],
+            })),
+            last_run_at: None,
+            last_task_id: None,
+            created_at: at(1, 12, 0),
+            updated_at: None,
+        };
+
+        assert_eq!(due_run(&template, at(6, 9, 0)), Some(at(2, 9, 30)));
+        assert_eq!(due_run(&template, at(6, 10, 0)), Some(at(6, 9, 30)));
+        let ran = TaskTemplate {
+            last_run_at: Some(at(6, 9, 30)),
+            ..template.clone()
+        };
+        assert_eq!(due_run(&ran, at(9, 9, 29)), None);
+        assert_eq!(due_run(&ran, at(9, 9, 30)), Some(at(9, 9, 30)));
+
+        let monthly = TaskTemplate {
+            recurrence: Some(recurrence(RecurrenceRule::Month { day: 31 })),
+            created_at: Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap(),
+            ..template.clone()
+        };
+        let end_of_february = Utc.with_ymd_and_hms(2023, 2, 28, 9, 30, 0).unwrap();
+        assert_eq!(due_run(&monthly, at(1, 0, 0)), Some(end_of_february));
+
+        // every 15 minutes of the working hours on weekdays
+        let cron = Recurrence {
+            rule: RecurrenceRule::Cron {
+                expression: String::from("*/15 9-17 * * 1-5"),
+            },
+            hour: 0,
+            minute: 0,
+        };
+        let cron_template = TaskTemplate {
+            recurrence: Some(cron.clone()),
+            ..template
+        };
+        assert_eq!(due_run(&cron_template, at(6, 10, 14)), Some(at(6, 10, 0)));
+        // saturday and sunday are skipped
+        assert_eq!(due_run(&cron_template, at(13, 8, 0)), Some(at(10, 17, 45)));
+
+        for expression in ["* * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *"] {
+            let rule = RecurrenceRule::Cron {
+                expression: String::from(expression),
+            };
+            assert!(Schedule::of(&Recurrence {
+                rule,
+                ..cron.clone()
+            })
+            .is_err());
+        }
+        let weekly = r#"{"every": "week", "weekdays": ["mon", "thu"], "hour": 9, "minute": 30}"#;
+        assert_eq!(
+            serde_json::from_str::<Recurrence>(weekly).unwrap(),
+            ran.recurrence.unwrap()
+        );
+        let stored = bson::to_document(&cron).unwrap();
+        assert_eq!(bson::from_document::<Recurrence>(stored).unwrap(), cron);
+    }
+}
@@ -0,0 +1,162 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::board::service as board_service;
+use crate::config::AppState;
+use crate::dto::{Response, SaveTemplateRequest};
+use crate::error::{AppError, TemplateRepoError};
+use crate::template::model::BoardTemplate;
+use crate::template::service;
+use crate::template::utils::validate_template;
+use crate::user::extractor::CurrentUser;
+
+// Returns the built-in and the saved board templates
+#[axum_macros::debug_handler]
+pub async fn get_templates_handler(State(state): State<AppState>) -> impl IntoResponse {
+    debug!("Getting board templates");
+    match service::get_templates(state.get_templates_collection()).await {
+        Ok(templates) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(templates),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_templates_handler] Error getting board templates: {:?}",
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Saves the layout of a board as a template new boards can start from
+#[axum_macros::debug_handler]
+pub async fn template_save_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<SaveTemplateRequest>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!(
+        "[template_save_handler] Saving board {} as template {}",
+        board_id, req.name
+    );
+
+    async fn validate_and_save(
+        board_id: &String,
+        req: SaveTemplateRequest,
+        actor: Option<String>,
+        state: AppState,
+    ) -> Result<BoardTemplate, AppError> {
+        let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+        let template = BoardTemplate::from_board(&board, req, actor);
+        validate_template(&template)?;
+        service::create(&template, state.get_templates_collection()).await?;
+        Ok(template)
+    }
+
+    match validate_and_save(&board_id, req, user.map(|u| u.0), state).await {
+        Ok(template) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(template),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[template_save_handler] Error saving board {} as template: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes a saved template, the built-in ones cannot be deleted
+#[axum_macros::debug_handler]
+pub async fn template_delete_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let template_id = path.0;
+    debug!(
+        "[template_delete_handler] Deleting template {}",
+        template_id
+    );
+
+    async fn delete(template_id: &String, state: AppState) -> Result<(), AppError> {
+        let template = service::get_template(template_id, state.get_templates_collection()).await?;
+        if template.built_in {
+            return Err(TemplateRepoError::InvalidTemplate(format!(
+                "built-in template {} cannot be deleted",
+                template_id
+            ))
+            .into());
+        }
+        service::delete(template_id, state.get_templates_collection()).await
+    }
+
+    match delete(&template_id, state).await {
+        Ok(_) => {
+            let msg = format!("Deleted template with id {}", template_id);
+            debug!("[template_delete_handler] {}", msg);
+            (
+                StatusCode::OK,
+                Json(Response {
+                    success: true,
+                    data: Some(msg),
+                    error_message: None,
+                    warnings: None,
+                }),
+            )
+        }
+        Err(e) => {
+            let msg = format!(
+                "[template_delete_handler] Error deleting template {}: {:?}",
+                template_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
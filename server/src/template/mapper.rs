@@ -0,0 +1,51 @@
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+
+use crate::board::mapper::{
+    get_board_columns, get_board_settings, map_columns_to_docs, settings_to_doc,
+};
+use crate::custom_field::mapper::{custom_field_to_doc, get_custom_fields};
+use crate::error::Result;
+use crate::swimlane::mapper::{get_swimlanes, swimlane_to_doc};
+use crate::template::model::BoardTemplate;
+
+pub async fn parse_templates(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<BoardTemplate>> {
+    let mut result: Vec<BoardTemplate> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_template(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_template(doc: &bson::document::Document) -> Result<BoardTemplate> {
+    let created_at = doc.get_datetime("created_at")?;
+    Ok(BoardTemplate {
+        id: doc.get_str("id")?.to_owned(),
+        name: doc.get_str("name")?.to_owned(),
+        description: doc.get_str("description").ok().map(|d| d.to_owned()),
+        built_in: false,
+        columns: get_board_columns(doc),
+        custom_fields: get_custom_fields(doc),
+        swimlanes: get_swimlanes(doc),
+        settings: get_board_settings(doc),
+        created_by: doc.get_str("created_by").ok().map(|u| u.to_owned()),
+        created_at: chrono::DateTime::from(*created_at),
+    })
+}
+
+pub fn template_to_doc(template: &BoardTemplate) -> bson::document::Document {
+    doc! {
+        "id" : template.id.clone(),
+        "name" : template.name.clone(),
+        "description" : template.description.clone(),
+        "columns" : map_columns_to_docs(&template.columns),
+        "custom_fields" : template.custom_fields.iter().map(custom_field_to_doc).collect::<Vec<bson::document::Document>>(),
+        "swimlanes" : template.swimlanes.iter().map(swimlane_to_doc).collect::<Vec<bson::document::Document>>(),
+        "settings" : settings_to_doc(&template.settings),
+        "created_by" : template.created_by.clone(),
+        "created_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(template.created_at),
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::board::model::{Board, BoardColumn, BoardSettings};
+use crate::custom_field::model::CustomField;
+use crate::dto::SaveTemplateRequest;
+use crate::swimlane::model::Swimlane;
+
+/// Layout new boards can start from, copied under new ids.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BoardTemplate {
+    /// Slug of the built-in templates, e.g. `kanban`, id of the saved ones.
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub built_in: bool,
+    pub columns: Vec<BoardColumn>,
+    pub custom_fields: Vec<CustomField>,
+    pub swimlanes: Vec<Swimlane>,
+    pub settings: BoardSettings,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BoardTemplate {
+    /// Saves the layout of a board, without its archived columns.
+    pub fn from_board(board: &Board, r: SaveTemplateRequest, actor: Option<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: r.name.trim().to_owned(),
+            description: r.description,
+            built_in: false,
+            columns: board
+                .columns
+                .iter()
+                .filter(|c| c.archived_at.is_none())
+                .cloned()
+                .collect(),
+            custom_fields: board.custom_fields.clone(),
+            swimlanes: board.swimlanes.clone(),
+            settings: board.settings.clone(),
+            created_by: actor,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Board holding the layout of the template, to be cloned into new boards.
+    pub fn layout(&self) -> Board {
+        Board {
+            id: uuid::Uuid::nil(),
+            title: self.name.clone(),
+            description: self.description.clone(),
+            columns: self.columns.clone(),
+            custom_fields: self.custom_fields.clone(),
+            swimlanes: self.swimlanes.clone(),
+            settings: self.settings.clone(),
+            created_at: self.created_at,
+            updated_at: None,
+            archived_at: None,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use tracing::debug;
+
+use crate::error::{AppError, Result, TemplateRepoError};
+use crate::template::mapper::{doc_to_template, parse_templates, template_to_doc};
+use crate::template::model::BoardTemplate;
+use crate::template::utils::built_in_templates;
+
+/// Returns the built-in templates followed by the saved ones, by name.
+pub async fn get_templates(collection: Collection<Document>) -> Result<Vec<BoardTemplate>> {
+    let options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+    let cursor = collection.find(None, options).await?;
+    let mut templates = built_in_templates();
+    templates.extend(parse_templates(cursor).await?);
+    Ok(templates)
+}
+
+pub async fn get_template(
+    template_id: &String,
+    collection: Collection<Document>,
+) -> Result<BoardTemplate> {
+    if let Some(template) = built_in_templates()
+        .into_iter()
+        .find(|t| &t.id == template_id)
+    {
+        return Ok(template);
+    }
+    let template_opt = collection
+        .find_one(doc! { "id": template_id }, None)
+        .await
+        .map_err(|e| {
+            debug!(
+                "Error while getting template with id {}: {}",
+                template_id, e
+            );
+            AppError::MongoError(e)
+        })?;
+    match template_opt {
+        Some(doc) => doc_to_template(&doc),
+        None => Err(AppError::TemplateRepo(TemplateRepoError::NotFound)),
+    }
+}
+
+pub async fn create(
+    template: &BoardTemplate,
+    collection: Collection<Document>,
+) -> Result<&BoardTemplate> {
+    debug!(
+        "[create_template] Creating template with id={}",
+        &template.id
+    );
+    collection
+        .insert_one(template_to_doc(template), None)
+        .await?;
+    Ok(template)
+}
+
+pub async fn delete(template_id: &String, collection: Collection<Document>) -> Result<()> {
+    debug!(
+        "[delete_template] Deleting template with id={}",
+        template_id
+    );
+    collection
+        .delete_one(doc! { "id": template_id }, None)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+
+use crate::board::model::{BoardColumn, BoardSettings};
+use crate::custom_field::model::{CustomField, CustomFieldKind};
+use crate::error::{Result, TemplateRepoError};
+use crate::swimlane::model::{Swimlane, SwimlaneSource};
+use crate::template::model::BoardTemplate;
+
+pub fn validate_template(template: &BoardTemplate) -> Result<()> {
+    if template.name.is_empty() {
+        return Err(
+            TemplateRepoError::InvalidTemplate(String::from("name cannot be empty")).into(),
+        );
+    }
+    Ok(())
+}
+
+fn column(name: &str, done: bool, wip_limit: Option<u32>) -> BoardColumn {
+    BoardColumn {
+        id: uuid::Uuid::new_v4(),
+        name: String::from(name),
+        colour: None,
+        description: None,
+        done,
+        wip_limit,
+        created_at: DateTime::<Utc>::default(),
+        updated_at: None,
+        archived_at: None,
+    }
+}
+
+fn built_in(id: &str, name: &str, description: &str, columns: Vec<BoardColumn>) -> BoardTemplate {
+    BoardTemplate {
+        id: String::from(id),
+        name: String::from(name),
+        description: Some(String::from(description)),
+        built_in: true,
+        settings: BoardSettings {
+            started_column_id: columns.get(2).map(|c| c.id),
+            ..Default::default()
+        },
+        columns,
+        custom_fields: Vec::new(),
+        swimlanes: Vec::new(),
+        created_by: None,
+        created_at: DateTime::<Utc>::default(),
+    }
+}
+
+/// Templates every installation offers, they cannot be deleted.
+pub fn built_in_templates() -> Vec<BoardTemplate> {
+    let kanban = built_in(
+        "kanban",
+        "Kanban",
+        "Continuous flow with a WIP limit on the work in progress.",
+        vec![
+            column("Backlog", false, None),
+            column("To do", false, None),
+            column("In progress", false, Some(3)),
+            column("Done", true, None),
+        ],
+    );
+    let scrum = built_in(
+        "scrum",
+        "Scrum",
+        "Sprints pulling stories from the product backlog.",
+        vec![
+            column("Product backlog", false, None),
+            column("Sprint backlog", false, None),
+            column("In progress", false, None),
+            column("Review", false, None),
+            column("Done", true, None),
+        ],
+    );
+
+    let mut bug_triage = built_in(
+        "bug_triage",
+        "Bug triage",
+        "Incoming bugs sorted into one swimlane per severity.",
+        vec![
+            column("New", false, None),
+            column("Triaged", false, None),
+            column("In progress", false, None),
+            column("Fixed", true, None),
+            column("Won't fix", true, None),
+        ],
+    );
+    let severity = CustomField {
+        id: uuid::Uuid::new_v4(),
+        name: String::from("Severity"),
+        kind: CustomFieldKind::SingleSelect,
+        options: ["Critical", "Major", "Minor"].map(String::from).to_vec(),
+        created_at: DateTime::<Utc>::default(),
+        updated_at: None,
+    };
+    bug_triage.swimlanes = severity
+        .options
+        .iter()
+        .map(|option| Swimlane {
+            created_at: DateTime::<Utc>::default(),
+            ..Swimlane::new(option, Some(option.clone()))
+        })
+        .collect();
+    bug_triage.settings.done_column_id = bug_triage.columns.get(3).map(|c| c.id);
+    bug_triage.settings.swimlanes_by = Some(SwimlaneSource::CustomField {
+        field_id: severity.id,
+    });
+    bug_triage.custom_fields = vec![severity];
+
+    vec![kanban, scrum, bug_triage]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::model::Board;
+    use crate::board::utils::{clone_board, CloneOptions};
+    use crate::fixtures::mock_board;
+    use crate::swimlane::model::SwimlaneSource;
+    use crate::template::utils::built_in_templates;
+
+    #[test]
+    fn it_creates_boards_from_built_in_templates() {
+        let template = built_in_templates()
+            .into_iter()
+            .find(|t| t.id == "bug_triage")
+            .unwrap();
+        let target = Board {
+            title: String::from("bugs"),
+            ..mock_board(Vec::new())
+        };
+        let options = CloneOptions {
+            custom_fields: true,
+            settings: true,
+            ..Default::default()
+        };
+
+        let (board, tasks) = clone_board(&template.layout(), &[], target.clone(), options);
+        assert!(tasks.is_empty());
+        assert_eq!(board.id, target.id);
+        assert_eq!(board.title, "bugs");
+        assert_eq!(board.columns.len(), 5);
+        assert!(board
+            .columns
+            .iter()
+            .all(|c| template.columns.iter().all(|t| t.id != c.id)));
+        assert_eq!(board.settings.done_column_id, Some(board.columns[3].id));
+        assert_eq!(board.settings.started_column_id, Some(board.columns[2].id));
+        assert_eq!(
+            board.settings.swimlanes_by,
+            Some(SwimlaneSource::CustomField {
+                field_id: board.custom_fields[0].id
+            })
+        );
+        assert_eq!(board.swimlanes.len(), 3);
+    }
+}
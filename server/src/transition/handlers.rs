@@ -0,0 +1,60 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::Utc;
+use tracing::{debug, error};
+
+use crate::config::AppState;
+use crate::dto::Response;
+use crate::error::AppError;
+use crate::task::service as task_service;
+use crate::transition::model::TaskTimeline;
+use crate::transition::service;
+use crate::transition::utils::task_timeline;
+
+// Returns the column changes of a task and the time it spent in each column
+#[axum_macros::debug_handler]
+pub async fn get_task_timeline_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let task_id = path.0;
+    debug!("Getting timeline of task {}", task_id);
+
+    async fn timeline(task_id: &String, state: AppState) -> Result<TaskTimeline, AppError> {
+        let task = task_service::get_task(task_id, state.get_tasks_collection()).await?;
+        let transitions =
+            service::get_transitions(task_id, state.get_transitions_collection()).await?;
+        Ok(task_timeline(&task, transitions, Utc::now()))
+    }
+
+    match timeline(&task_id, state).await {
+        Ok(timeline) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(timeline),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_task_timeline_handler] Error getting timeline of task {}: {:?}",
+                task_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result, TransitionRepoError};
+use crate::transition::model::Transition;
+use crate::util::get_optional_uuid;
+
+pub async fn parse_transitions(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<Transition>> {
+    let mut result: Vec<Transition> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_transition(&doc?)?);
+    }
+    Ok(result)
+}
+
+pub fn doc_to_transition(doc: &bson::document::Document) -> Result<Transition> {
+    let id = doc.get_str("id")?;
+    let task_id = doc.get_str("task_id")?;
+    let board_id = doc.get_str("board_id")?;
+    let at = doc.get_datetime("at")?;
+    match (
+        Uuid::from_str(id),
+        Uuid::from_str(task_id),
+        Uuid::from_str(board_id),
+    ) {
+        (Ok(transition_uuid), Ok(task_uuid), Ok(board_uuid)) => Ok(Transition {
+            id: transition_uuid,
+            task_id: task_uuid,
+            board_id: board_uuid,
+            from_column_id: get_optional_uuid(doc, "from_column_id"),
+            to_column_id: get_optional_uuid(doc, "to_column_id"),
+            at: chrono::DateTime::from(*at),
+        }),
+        _ => Err(AppError::TransitionRepo(TransitionRepoError::DecodeError(
+            format!("Transition doesnt have id, task_id or board_id {}", id),
+        ))),
+    }
+}
+
+pub fn transition_to_doc(transition: &Transition) -> bson::document::Document {
+    doc! {
+        "id" : transition.id.to_string(),
+        "task_id" : transition.task_id.to_string(),
+        "board_id" : transition.board_id.to_string(),
+        "from_column_id" : transition.from_column_id.map(|c| c.to_string()),
+        "to_column_id" : transition.to_column_id.map(|c| c.to_string()),
+        "at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(transition.at)
+    }
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A task moving from a column to another, `None` being the unassigned tasks.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Transition {
+    pub id: uuid::Uuid,
+    pub task_id: uuid::Uuid,
+    pub board_id: uuid::Uuid,
+    pub from_column_id: Option<uuid::Uuid>,
+    pub to_column_id: Option<uuid::Uuid>,
+    pub at: DateTime<Utc>,
+}
+
+/// A stay of a task in a column, still going on when `left_at` is missing.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct ColumnPeriod {
+    pub column_id: Option<uuid::Uuid>,
+    pub entered_at: DateTime<Utc>,
+    pub left_at: Option<DateTime<Utc>>,
+    pub seconds: i64,
+}
+
+/// Total time a task spent in a column over all its stays.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct ColumnTime {
+    pub column_id: Option<uuid::Uuid>,
+    pub seconds: i64,
+    pub visits: u32,
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct TaskTimeline {
+    pub task_id: uuid::Uuid,
+    pub transitions: Vec<Transition>,
+    pub periods: Vec<ColumnPeriod>,
+    /// Ordered by first entry in the column.
+    pub time_in_columns: Vec<ColumnTime>,
+}
@@ -0,0 +1,48 @@
+use bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use tracing::{debug, error};
+
+use crate::error::Result;
+use crate::transition::mapper::{parse_transitions, transition_to_doc};
+use crate::transition::model::Transition;
+
+/// Returns the transitions of a task, oldest first.
+pub async fn get_transitions(
+    task_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Transition>> {
+    let options = FindOptions::builder().sort(doc! { "at": 1 }).build();
+    let cursor = collection
+        .find(doc! { "task_id": task_id }, options)
+        .await?;
+    parse_transitions(cursor).await
+}
+
+/// Returns the transitions of all the tasks of a board, oldest first.
+pub async fn get_transitions_of_board(
+    board_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<Transition>> {
+    let options = FindOptions::builder().sort(doc! { "at": 1 }).build();
+    let cursor = collection
+        .find(doc! { "board_id": board_id }, options)
+        .await?;
+    parse_transitions(cursor).await
+}
+
+/// Stores the given transitions. Failures are only logged, as transitions are
+/// recorded after the moves they describe succeeded.
+pub async fn record(transitions: Vec<Transition>, collection: Collection<Document>) {
+    if transitions.is_empty() {
+        return;
+    }
+    debug!("[record] Recording {} transitions", transitions.len());
+    let docs = transitions
+        .iter()
+        .map(transition_to_doc)
+        .collect::<Vec<Document>>();
+    if let Err(e) = collection.insert_many(docs, None).await {
+        error!("ERROR [record] Couldn't record transitions: {:?}", e);
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::task::model::Task;
+use crate::transition::model::{ColumnPeriod, ColumnTime, TaskTimeline, Transition};
+
+/// Transitions of the tasks whose column differs between `before` and `after`.
+pub fn column_transitions(before: &[Task], after: &[Task]) -> Vec<Transition> {
+    let stored = before
+        .iter()
+        .map(|t| (t.id, t.column_id))
+        .collect::<HashMap<uuid::Uuid, Option<uuid::Uuid>>>();
+    let at = Utc::now();
+    after
+        .iter()
+        .filter_map(|t| {
+            let from_column_id = *stored.get(&t.id)?;
+            (from_column_id != t.column_id).then(|| Transition {
+                id: uuid::Uuid::new_v4(),
+                task_id: t.id,
+                board_id: t.board_id,
+                from_column_id,
+                to_column_id: t.column_id,
+                at,
+            })
+        })
+        .collect()
+}
+
+/// Splits the life of a task into its stays in columns, from its creation to
+/// `now`. `transitions` are expected oldest first.
+pub fn column_periods(
+    task: &Task,
+    transitions: &[Transition],
+    now: DateTime<Utc>,
+) -> Vec<ColumnPeriod> {
+    let mut column_id = transitions
+        .first()
+        .map_or(task.column_id, |t| t.from_column_id);
+    let mut entered_at = task.created_at;
+    let mut periods = Vec::new();
+    for transition in transitions {
+        periods.push(ColumnPeriod {
+            column_id,
+            entered_at,
+            left_at: Some(transition.at),
+            seconds: (transition.at - entered_at).num_seconds(),
+        });
+        column_id = transition.to_column_id;
+        entered_at = transition.at;
+    }
+    periods.push(ColumnPeriod {
+        column_id,
+        entered_at,
+        left_at: None,
+        seconds: (now - entered_at).num_seconds(),
+    });
+    periods
+}
+
+/// Column a task was in at `at`, `None` when the task didn't exist yet.
+pub fn column_at(periods: &[ColumnPeriod], at: DateTime<Utc>) -> Option<Option<uuid::Uuid>> {
+    periods
+        .iter()
+        .find(|p| p.entered_at <= at && p.left_at.is_none_or(|left_at| left_at > at))
+        .map(|p| p.column_id)
+}
+
+pub fn task_timeline(
+    task: &Task,
+    transitions: Vec<Transition>,
+    now: DateTime<Utc>,
+) -> TaskTimeline {
+    let periods = column_periods(task, &transitions, now);
+    let mut time_in_columns: Vec<ColumnTime> = Vec::new();
+    for period in periods.iter() {
+        match time_in_columns
+            .iter_mut()
+            .find(|c| c.column_id == period.column_id)
+        {
+            Some(time) => {
+                time.seconds += period.seconds;
+                time.visits += 1;
+            }
+            None => time_in_columns.push(ColumnTime {
+                column_id: period.column_id,
+                seconds: period.seconds,
+                visits: 1,
+            }),
+        }
+    }
+    TaskTimeline {
+        task_id: task.id,
+        transitions,
+        periods,
+        time_in_columns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    use crate::fixtures::mock_task;
+    use crate::task::model::Task;
+    use crate::transition::model::Transition;
+    use crate::transition::utils::{column_transitions, task_timeline};
+
+    #[test]
+    fn it_records_transitions_of_moved_tasks_only() {
+        let todo = Some(Uuid::new_v4());
+        let doing = Some(Uuid::new_v4());
+        let moved = mock_task(Uuid::new_v4(), todo);
+        let renamed = mock_task(Uuid::new_v4(), todo);
+        let after = vec![
+            Task {
+                column_id: doing,
+                ..moved.clone()
+            },
+            Task {
+                title: String::from("renamed"),
+                ..renamed.clone()
+            },
+        ];
+
+        let transitions = column_transitions(&[moved.clone(), renamed], &after);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].task_id, moved.id);
+        assert_eq!(transitions[0].from_column_id, todo);
+        assert_eq!(transitions[0].to_column_id, doing);
+    }
+
+    #[test]
+    fn it_sums_the_time_spent_in_each_column() {
+        let todo = Some(Uuid::new_v4());
+        let doing = Some(Uuid::new_v4());
+        let task = mock_task(Uuid::new_v4(), doing);
+        let transition = |hours: i64, from_column_id, to_column_id| Transition {
+            id: Uuid::new_v4(),
+            task_id: task.id,
+            board_id: task.board_id,
+            from_column_id,
+            to_column_id,
+            at: task.created_at + Duration::hours(hours),
+        };
+        let transitions = vec![
+            transition(1, todo, doing),
+            transition(3, doing, todo),
+            transition(4, todo, doing),
+        ];
+
+        let timeline = task_timeline(&task, transitions, task.created_at + Duration::hours(10));
+        assert_eq!(timeline.periods.len(), 4);
+        assert_eq!(timeline.periods[3].left_at, None);
+        assert_eq!(timeline.time_in_columns.len(), 2);
+        assert_eq!(timeline.time_in_columns[0].column_id, todo);
+        assert_eq!(timeline.time_in_columns[0].seconds, 2 * 3600);
+        assert_eq!(timeline.time_in_columns[0].visits, 2);
+        assert_eq!(timeline.time_in_columns[1].seconds, 8 * 3600);
+    }
+}
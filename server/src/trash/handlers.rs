@@ -0,0 +1,144 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::{debug, error};
+
+use crate::config::AppState;
+use crate::dto::Response;
+use crate::error::AppError;
+use crate::trash::model::TrashItem;
+use crate::trash::service;
+use crate::user::extractor::CurrentUser;
+
+// Returns the deleted tasks, columns and boards of a board, most recent first
+#[axum_macros::debug_handler]
+pub async fn get_trash_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let board_id = path.0;
+    debug!("Getting trash of board {}", board_id);
+    match service::get_trash(&board_id, state.get_trash_collection()).await {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(items),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[get_trash_handler] Error getting trash of board {}: {:?}",
+                board_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Puts a deleted task, column or board back where it was
+#[axum_macros::debug_handler]
+pub async fn trash_restore_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> impl IntoResponse {
+    let item_id = path.0;
+    debug!("[trash_restore_handler] Restoring trash item {}", item_id);
+
+    async fn restore(
+        item_id: &String,
+        restored_by: Option<String>,
+        state: AppState,
+    ) -> Result<TrashItem, AppError> {
+        let item = service::get_item(item_id, state.get_trash_collection()).await?;
+        service::restore(&item, restored_by, &state).await?;
+        Ok(item)
+    }
+
+    match restore(&item_id, user.map(|u| u.0), state).await {
+        Ok(item) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(item),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[trash_restore_handler] Error restoring trash item {}: {:?}",
+                item_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::BAD_REQUEST),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
+
+// Deletes a trash item for good
+#[axum_macros::debug_handler]
+pub async fn trash_purge_handler(
+    path: Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let item_id = path.0;
+    debug!("[trash_purge_handler] Purging trash item {}", item_id);
+
+    async fn purge(item_id: &String, state: AppState) -> Result<(), AppError> {
+        let item = service::get_item(item_id, state.get_trash_collection()).await?;
+        service::purge(&item, &state).await
+    }
+
+    match purge(&item_id, state).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(Response {
+                success: true,
+                data: Some(format!("Purged trash item with id {}", item_id)),
+                error_message: None,
+                warnings: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = format!(
+                "[trash_purge_handler] Error purging trash item {}: {:?}",
+                item_id,
+                e.to_string()
+            );
+            error!("{}", msg);
+            (
+                e.status_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(msg),
+                    warnings: None,
+                }),
+            )
+        }
+    }
+}
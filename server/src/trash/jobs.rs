@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+use crate::config::AppState;
+use crate::trash::service;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Purges the items that stayed in the trash longer than `retention`, once at
+/// startup and every hour after.
+pub fn spawn_purge(state: AppState, retention: chrono::Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match service::purge_expired(retention, &state).await {
+                Ok(purged) => debug!("[spawn_purge] Purged {} trash items", purged),
+                Err(e) => error!("ERROR [spawn_purge] Couldn't purge the trash: {:?}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::attachment::mapper::{attachment_to_doc, doc_to_attachment};
+use crate::board::mapper::{board_to_doc, doc_to_board, doc_to_board_column, map_columns_to_docs};
+use crate::comment::mapper::{comment_to_doc, doc_to_comment};
+use crate::error::{AppError, Result, TrashRepoError};
+use crate::task::utils::{doc_to_task, task_to_doc};
+use crate::trash::model::{TrashContent, TrashItem, TrashKind};
+
+pub async fn parse_trash_items(
+    mut cursor: mongodb::Cursor<bson::document::Document>,
+) -> Result<Vec<TrashItem>> {
+    let mut result: Vec<TrashItem> = Vec::new();
+    while let Some(doc) = cursor.next().await {
+        result.push(doc_to_trash_item(&doc?)?);
+    }
+    Ok(result)
+}
+
+fn get_docs(doc: &bson::document::Document, field_name: &str) -> Vec<bson::document::Document> {
+    doc.get_array(field_name)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_document().cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn doc_to_content(doc: &bson::document::Document) -> Result<TrashContent> {
+    Ok(TrashContent {
+        board: doc
+            .get_document("board")
+            .ok()
+            .map(doc_to_board)
+            .transpose()?,
+        column: doc
+            .get_document("column")
+            .ok()
+            .map(doc_to_board_column)
+            .transpose()?,
+        position: doc.get_i64("position").ok().map(|p| p as usize),
+        tasks: get_docs(doc, "tasks")
+            .iter()
+            .map(doc_to_task)
+            .collect::<Result<_>>()?,
+        comments: get_docs(doc, "comments")
+            .iter()
+            .map(doc_to_comment)
+            .collect::<Result<_>>()?,
+        attachments: get_docs(doc, "attachments")
+            .iter()
+            .map(doc_to_attachment)
+            .collect::<Result<_>>()?,
+        dependency_links: get_docs(doc, "dependency_links")
+            .iter()
+            .filter_map(|link| {
+                match (
+                    Uuid::from_str(link.get_str("task_id").ok()?),
+                    Uuid::from_str(link.get_str("blocker_id").ok()?),
+                ) {
+                    (Ok(task_id), Ok(blocker_id)) => Some((task_id, blocker_id)),
+                    _ => None,
+                }
+            })
+            .collect(),
+    })
+}
+
+pub fn doc_to_trash_item(doc: &bson::document::Document) -> Result<TrashItem> {
+    let id = doc.get_str("id")?;
+    let kind = doc.get_str("kind")?;
+    let board_id = doc.get_str("board_id")?;
+    let entity_id = doc.get_str("entity_id")?;
+    let name = doc.get_str("name")?;
+    let task_count = doc.get_i64("task_count").unwrap_or_default();
+    let deleted_by = doc.get_str("deleted_by").ok();
+    let deleted_at = doc.get_datetime("deleted_at")?;
+    match (
+        Uuid::from_str(id),
+        TrashKind::from_str(kind),
+        Uuid::from_str(board_id),
+        Uuid::from_str(entity_id),
+    ) {
+        (Ok(item_uuid), Ok(kind), Ok(board_uuid), Ok(entity_uuid)) => Ok(TrashItem {
+            id: item_uuid,
+            kind,
+            board_id: board_uuid,
+            entity_id: entity_uuid,
+            name: name.to_owned(),
+            task_count: task_count as usize,
+            deleted_by: deleted_by.map(|d| d.to_owned()),
+            deleted_at: chrono::DateTime::from(*deleted_at),
+            content: doc_to_content(doc)?,
+        }),
+        _ => Err(AppError::TrashRepo(TrashRepoError::DecodeError(format!(
+            "Trash item doesnt have id, kind, board_id or entity_id {}",
+            id
+        )))),
+    }
+}
+
+pub fn trash_item_to_doc(item: &TrashItem) -> bson::document::Document {
+    let content = &item.content;
+    doc! {
+        "id" : item.id.to_string(),
+        "kind" : item.kind.to_string(),
+        "board_id" : item.board_id.to_string(),
+        "entity_id" : item.entity_id.to_string(),
+        "name" : item.name.clone(),
+        "task_count" : item.task_count as i64,
+        "deleted_by" : item.deleted_by.clone(),
+        "deleted_at" : <chrono::DateTime<Utc> as Into<bson::DateTime>>::into(item.deleted_at),
+        "board" : content.board.as_ref().map(board_to_doc),
+        "column" : content.column.as_ref().and_then(|c| map_columns_to_docs(std::slice::from_ref(c)).pop()),
+        "position" : content.position.map(|p| p as i64),
+        "tasks" : content.tasks.iter().map(task_to_doc).collect::<Vec<bson::document::Document>>(),
+        "comments" : content.comments.iter().map(comment_to_doc).collect::<Vec<bson::document::Document>>(),
+        "attachments" : content.attachments.iter().map(attachment_to_doc).collect::<Vec<bson::document::Document>>(),
+        "dependency_links" : content.dependency_links.iter().map(|(task_id, blocker_id)| doc! {
+            "task_id" : task_id.to_string(),
+            "blocker_id" : blocker_id.to_string(),
+        }).collect::<Vec<bson::document::Document>>(),
+    }
+}
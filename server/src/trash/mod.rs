@@ -0,0 +1,5 @@
+pub mod handlers;
+pub mod jobs;
+pub mod mapper;
+pub mod model;
+pub mod service;
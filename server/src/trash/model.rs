@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum_macros::{Display, EnumString};
+
+use crate::attachment::model::Attachment;
+use crate::board::model::{Board, BoardColumn};
+use crate::comment::model::Comment;
+use crate::task::model::Task;
+
+/// Something deleted by a user, kept until it gets restored or purged.
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Debug)]
+pub struct TrashItem {
+    pub id: uuid::Uuid,
+    pub kind: TrashKind,
+    pub board_id: uuid::Uuid,
+    /// Id of the deleted task, column or board.
+    pub entity_id: uuid::Uuid,
+    /// Title of the deleted task or board, name of the deleted column.
+    pub name: String,
+    /// Number of tasks that come back on restore.
+    pub task_count: usize,
+    pub deleted_by: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub content: TrashContent,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TrashKind {
+    Task,
+    Column,
+    Board,
+}
+
+/// Everything needed to put a deleted entity back as it was.
+#[derive(Clone, Debug, Default)]
+pub struct TrashContent {
+    pub board: Option<Board>,
+    pub column: Option<BoardColumn>,
+    /// Index of the column in the board.
+    pub position: Option<usize>,
+    /// Deleted tasks, or for a column the tasks it held before they were moved.
+    pub tasks: Vec<Task>,
+    pub comments: Vec<Comment>,
+    pub attachments: Vec<Attachment>,
+    /// (task, blocker) pairs of tasks that were blocked by deleted tasks.
+    pub dependency_links: Vec<(uuid::Uuid, uuid::Uuid)>,
+}
+
+impl TrashItem {
+    pub fn new(
+        kind: TrashKind,
+        board_id: uuid::Uuid,
+        entity_id: uuid::Uuid,
+        name: String,
+        deleted_by: Option<String>,
+        content: TrashContent,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            kind,
+            board_id,
+            entity_id,
+            name,
+            task_count: content.tasks.len(),
+            deleted_by,
+            deleted_at: Utc::now(),
+            content,
+        }
+    }
+}
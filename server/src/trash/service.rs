@@ -0,0 +1,701 @@
+use std::collections::HashMap;
+
+use bson::{doc, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::FindOptions;
+use mongodb::{ClientSession, Collection};
+use tracing::{debug, error};
+
+use crate::activity::service as activity_service;
+use crate::activity::utils::{as_restored, board_activities, task_activities};
+use crate::attachment::service as attachment_service;
+use crate::automation::service as automation_service;
+use crate::board::mapper::board_to_doc;
+use crate::board::model::Board;
+use crate::board::service as board_service;
+use crate::board::utils::filter_column_from;
+use crate::chat::service as chat_service;
+use crate::comment::service as comment_service;
+use crate::config::AppState;
+use crate::db::run_transaction;
+use crate::dto::ChildrenStrategy;
+use crate::error::{AppError, Result, TrashRepoError};
+use crate::sprint::service as sprint_service;
+use crate::task::model::Task;
+use crate::task::service as task_service;
+use crate::task::utils::{
+    collect_descendants, reinsert_tasks, relink_without, task_to_doc, without_missing_links,
+};
+use crate::task_template::service as task_template_service;
+use crate::transition::service as transition_service;
+use crate::transition::utils::column_transitions;
+use crate::trash::mapper::{doc_to_trash_item, parse_trash_items, trash_item_to_doc};
+use crate::trash::model::{TrashContent, TrashItem, TrashKind};
+
+pub async fn get_trash(
+    board_id: &String,
+    collection: Collection<Document>,
+) -> Result<Vec<TrashItem>> {
+    let filter = doc! { "board_id": board_id };
+    let options = FindOptions::builder()
+        .sort(doc! { "deleted_at": -1 })
+        .build();
+    let cursor = collection.find(filter, options).await.map_err(|_e| {
+        debug!("ERROR [get_trash] {:?}", _e);
+        TrashRepoError::NotFound
+    })?;
+    parse_trash_items(cursor).await
+}
+
+pub async fn get_item(item_id: &String, collection: Collection<Document>) -> Result<TrashItem> {
+    let filter = doc! { "id": item_id };
+    match collection.find_one(filter, None).await? {
+        Some(doc) => doc_to_trash_item(&doc),
+        None => Err(AppError::TrashRepo(TrashRepoError::NotFound)),
+    }
+}
+
+/// Returns the items deleted before the given date.
+pub async fn get_expired(
+    before: DateTime<Utc>,
+    collection: Collection<Document>,
+) -> Result<Vec<TrashItem>> {
+    let filter = doc! { "deleted_at": { "$lt": bson::DateTime::from(before) } };
+    let cursor = collection.find(filter, None).await?;
+    parse_trash_items(cursor).await
+}
+
+/// Returns the (task, blocker) pairs of the tasks blocked by the given ones.
+async fn get_dependency_links(
+    task_ids: &[uuid::Uuid],
+    collection: Collection<Document>,
+) -> Result<Vec<(uuid::Uuid, uuid::Uuid)>> {
+    Ok(task_service::get_blocked_by(task_ids, collection)
+        .await?
+        .iter()
+        .filter(|t| !task_ids.contains(&t.id))
+        .flat_map(|t| {
+            t.blocked_by
+                .iter()
+                .filter(|b| task_ids.contains(b))
+                .map(|b| (t.id, *b))
+        })
+        .collect())
+}
+
+fn to_strings(ids: &[uuid::Uuid]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+/// Removes the tasks with their comments and attachment records, and drops the
+/// dependencies pointing at them. Attachment contents stay in the blob store
+/// until the trash item is purged.
+async fn delete_tasks_with_session(
+    state: &AppState,
+    task_ids: Vec<String>,
+    session: &mut ClientSession,
+) -> mongodb::error::Result<()> {
+    let by_task = doc! { "task_id": { "$in": task_ids.clone() } };
+    state
+        .get_tasks_collection()
+        .delete_many_with_session(doc! { "id": { "$in": task_ids.clone() } }, None, session)
+        .await?;
+    state
+        .get_tasks_collection()
+        .update_many_with_session(
+            doc! { "blocked_by": { "$in": task_ids.clone() } },
+            doc! { "$pull": { "blocked_by": { "$in": task_ids } } },
+            None,
+            session,
+        )
+        .await?;
+    for collection in [
+        state.get_comments_collection(),
+        state.get_attachments_collection(),
+    ] {
+        collection
+            .delete_many_with_session(by_task.clone(), None, session)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Puts back the comments, attachment records and dependencies of restored
+/// tasks and removes the item from the trash.
+async fn restore_content_with_session(
+    state: &AppState,
+    item_doc: &Document,
+    session: &mut ClientSession,
+) -> mongodb::error::Result<()> {
+    for (collection, field) in [
+        (state.get_comments_collection(), "comments"),
+        (state.get_attachments_collection(), "attachments"),
+    ] {
+        let docs = item_doc
+            .get_array(field)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_document().cloned())
+                    .collect::<Vec<Document>>()
+            })
+            .unwrap_or_default();
+        if !docs.is_empty() {
+            collection
+                .insert_many_with_session(docs, None, session)
+                .await?;
+        }
+    }
+    for link in item_doc.get_array("dependency_links").unwrap_or(&vec![]) {
+        if let Some(link) = link.as_document() {
+            state
+                .get_tasks_collection()
+                .update_one_with_session(
+                    doc! { "id": link.get_str("task_id").unwrap_or_default() },
+                    doc! { "$addToSet": { "blocked_by": link.get_str("blocker_id").unwrap_or_default() } },
+                    None,
+                    session,
+                )
+                .await?;
+        }
+    }
+    state
+        .get_trash_collection()
+        .delete_one_with_session(
+            doc! { "id": item_doc.get_str("id").unwrap_or_default() },
+            None,
+            session,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Moves a task to the trash, along with its descendants when `strategy` is
+/// `Delete`. Children are detached otherwise.
+pub async fn trash_task(
+    task: &Task,
+    strategy: ChildrenStrategy,
+    deleted_by: Option<String>,
+    state: &AppState,
+) -> Result<TrashItem> {
+    debug!("[trash_task] Moving task with id={} to trash", task.id);
+    let board_tasks =
+        task_service::get_tasks(&task.board_id.to_string(), state.get_tasks_collection()).await?;
+    let mut task_ids = vec![task.id];
+    if let ChildrenStrategy::Delete = strategy {
+        task_ids.extend(collect_descendants(task.id, &board_tasks));
+    }
+    let item = TrashItem::new(
+        TrashKind::Task,
+        task.board_id,
+        task.id,
+        task.title.clone(),
+        deleted_by,
+        TrashContent {
+            tasks: board_tasks
+                .iter()
+                .filter(|t| task_ids.contains(&t.id))
+                .cloned()
+                .collect(),
+            comments: comment_service::get_comments_of_tasks(
+                &task_ids,
+                state.get_comments_collection(),
+            )
+            .await?,
+            attachments: attachment_service::get_attachments_of_tasks(
+                &task_ids,
+                state.get_attachments_collection(),
+            )
+            .await?,
+            dependency_links: get_dependency_links(&task_ids, state.get_tasks_collection()).await?,
+            ..Default::default()
+        },
+    );
+    let item_doc = trash_item_to_doc(&item);
+    let mut updated = relink_without(&task_ids, &board_tasks)
+        .into_iter()
+        .map(|t| (t.id, t))
+        .collect::<HashMap<uuid::Uuid, Task>>();
+    for child in board_tasks
+        .iter()
+        .filter(|t| t.parent_task_id == Some(task.id) && !task_ids.contains(&t.id))
+    {
+        let child = updated.remove(&child.id).unwrap_or_else(|| child.clone());
+        updated.insert(
+            child.id,
+            Task {
+                parent_task_id: None,
+                updated_at: Some(Utc::now()),
+                ..child
+            },
+        );
+    }
+    let updated = updated.into_values().collect::<Vec<Task>>();
+    let updated_docs = updated.iter().map(task_to_doc).collect::<Vec<Document>>();
+    let deleted_ids = to_strings(&task_ids);
+    run_transaction(&state.client, |session| {
+        let (item_doc, updated_docs, deleted_ids, state) = (
+            item_doc.clone(),
+            updated_docs.clone(),
+            deleted_ids.clone(),
+            state.clone(),
+        );
+        Box::pin(async move {
+            state
+                .get_trash_collection()
+                .insert_one_with_session(item_doc, None, session)
+                .await?;
+            delete_tasks_with_session(&state, deleted_ids, session).await?;
+            task_service::update_many_with_session(
+                &state.get_tasks_collection(),
+                updated_docs,
+                session,
+            )
+            .await
+        })
+    })
+    .await?;
+    let before = board_tasks
+        .into_iter()
+        .filter(|t| task_ids.contains(&t.id) || updated.iter().any(|u| u.id == t.id))
+        .collect::<Vec<Task>>();
+    activity_service::record(
+        task_activities(&before, &updated, item.deleted_by.as_deref()),
+        state.get_activity_collection(),
+    )
+    .await;
+    Ok(item)
+}
+
+/// Removes a column from its board and moves it to the trash. `column_tasks`
+/// are the tasks it held, kept to bring them back on restore, `moved` the same
+/// tasks in the column they are moved to.
+pub async fn trash_column(
+    board: &Board,
+    column_id: uuid::Uuid,
+    column_tasks: &[Task],
+    moved: &[Task],
+    deleted_by: Option<String>,
+    state: &AppState,
+) -> Result<Board> {
+    debug!(
+        "[trash_column] Moving column with id={} of board {} to trash",
+        column_id, board.id
+    );
+    let position = board.columns.iter().position(|c| c.id == column_id);
+    let column = board.column(column_id).cloned();
+    let item = TrashItem::new(
+        TrashKind::Column,
+        board.id,
+        column_id,
+        column.as_ref().map(|c| c.name.clone()).unwrap_or_default(),
+        deleted_by,
+        TrashContent {
+            column,
+            position,
+            tasks: column_tasks.to_vec(),
+            ..Default::default()
+        },
+    );
+    let updated = Board {
+        updated_at: Some(Utc::now()),
+        ..filter_column_from(board.clone(), column_id)
+    };
+    let item_doc = trash_item_to_doc(&item);
+    let board_doc = board_to_doc(&updated);
+    let task_docs = moved.iter().map(task_to_doc).collect::<Vec<Document>>();
+    run_transaction(&state.client, |session| {
+        let (item_doc, board_doc, task_docs, state) = (
+            item_doc.clone(),
+            board_doc.clone(),
+            task_docs.clone(),
+            state.clone(),
+        );
+        Box::pin(async move {
+            state
+                .get_trash_collection()
+                .insert_one_with_session(item_doc, None, session)
+                .await?;
+            let filter = doc! { "id": board_doc.get_str("id").unwrap_or_default() };
+            state
+                .get_boards_collection()
+                .update_one_with_session(filter, doc! { "$set": board_doc }, None, session)
+                .await?;
+            task_service::update_many_with_session(
+                &state.get_tasks_collection(),
+                task_docs,
+                session,
+            )
+            .await
+        })
+    })
+    .await?;
+    let mut activities = board_activities(Some(board), Some(&updated), item.deleted_by.as_deref());
+    activities.extend(task_activities(
+        column_tasks,
+        moved,
+        item.deleted_by.as_deref(),
+    ));
+    activity_service::record(activities, state.get_activity_collection()).await;
+    transition_service::record(
+        column_transitions(column_tasks, moved),
+        state.get_transitions_collection(),
+    )
+    .await;
+    Ok(updated)
+}
+
+/// Moves a board to the trash along with its tasks and everything attached to
+/// them. Notifications about the board are dropped.
+pub async fn trash_board(
+    board_id: &String,
+    deleted_by: Option<String>,
+    state: &AppState,
+) -> Result<TrashItem> {
+    debug!("[trash_board] Moving board with id={} to trash", board_id);
+    let board = board_service::get_board(board_id, state.get_boards_collection()).await?;
+    let tasks = task_service::get_tasks(board_id, state.get_tasks_collection()).await?;
+    let task_ids = tasks.iter().map(|t| t.id).collect::<Vec<uuid::Uuid>>();
+    let item = TrashItem::new(
+        TrashKind::Board,
+        board.id,
+        board.id,
+        board.title.clone(),
+        deleted_by,
+        TrashContent {
+            comments: comment_service::get_comments_of_tasks(
+                &task_ids,
+                state.get_comments_collection(),
+            )
+            .await?,
+            attachments: attachment_service::get_attachments_of_board(
+                board_id,
+                state.get_attachments_collection(),
+            )
+            .await?,
+            dependency_links: get_dependency_links(&task_ids, state.get_tasks_collection()).await?,
+            board: Some(board),
+            tasks,
+            ..Default::default()
+        },
+    );
+    let item_doc = trash_item_to_doc(&item);
+    let task_ids = to_strings(&task_ids);
+    run_transaction(&state.client, |session| {
+        let (item_doc, board_id, task_ids, state) = (
+            item_doc.clone(),
+            board_id.clone(),
+            task_ids.clone(),
+            state.clone(),
+        );
+        Box::pin(async move {
+            state
+                .get_trash_collection()
+                .insert_one_with_session(item_doc, None, session)
+                .await?;
+            state
+                .get_boards_collection()
+                .delete_one_with_session(doc! { "id": &board_id }, None, session)
+                .await?;
+            delete_tasks_with_session(&state, task_ids, session).await?;
+            state
+                .get_notifications_collection()
+                .delete_many_with_session(doc! { "board_id": &board_id }, None, session)
+                .await?;
+            Ok(())
+        })
+    })
+    .await?;
+    let content = &item.content;
+    let mut activities = board_activities(content.board.as_ref(), None, item.deleted_by.as_deref());
+    activities.extend(task_activities(
+        &content.tasks,
+        &[],
+        item.deleted_by.as_deref(),
+    ));
+    activity_service::record(activities, state.get_activity_collection()).await;
+    Ok(item)
+}
+
+/// Puts the content of a trash item back and removes it from the trash.
+pub async fn restore(
+    item: &TrashItem,
+    restored_by: Option<String>,
+    state: &AppState,
+) -> Result<()> {
+    debug!(
+        "[restore] Restoring {} with id={} from trash",
+        item.kind, item.entity_id
+    );
+    let board = board_service::get_board(&item.board_id.to_string(), state.get_boards_collection())
+        .await
+        .ok();
+    let stored_board = board.clone();
+    let (board, tasks) = match (item.kind, board) {
+        (TrashKind::Board, Some(_)) => {
+            return Err(AppError::Conflict(format!(
+                "board {} exists already",
+                item.board_id
+            )))
+        }
+        (TrashKind::Board, None) => (item.content.board.clone(), item.content.tasks.clone()),
+        (_, None) => {
+            return Err(AppError::Conflict(format!(
+                "board {} doesn't exist anymore, restore it first",
+                item.board_id
+            )))
+        }
+        (TrashKind::Task, Some(board)) => (None, restored_tasks(item, &board, state).await?),
+        (TrashKind::Column, Some(board)) => restored_column(item, board, state).await?,
+    };
+    let (new_tasks, updated_tasks): (Vec<Task>, Vec<Task>) = match item.kind {
+        TrashKind::Column => (vec![], tasks),
+        _ => tasks.into_iter().partition(|t| {
+            item.content
+                .tasks
+                .iter()
+                .any(|restored| restored.id == t.id)
+        }),
+    };
+    let updated_ids = updated_tasks
+        .iter()
+        .map(|t| t.id)
+        .collect::<Vec<uuid::Uuid>>();
+    let stored_tasks =
+        task_service::get_tasks_by_ids(&updated_ids, state.get_tasks_collection()).await?;
+    let item_doc = trash_item_to_doc(item);
+    let board_doc = board.as_ref().map(board_to_doc);
+    let new_docs = new_tasks.iter().map(task_to_doc).collect::<Vec<Document>>();
+    let updated_docs = updated_tasks
+        .iter()
+        .map(task_to_doc)
+        .collect::<Vec<Document>>();
+    let kind = item.kind;
+    run_transaction(&state.client, |session| {
+        let (item_doc, board_doc, new_docs, updated_docs, state) = (
+            item_doc.clone(),
+            board_doc.clone(),
+            new_docs.clone(),
+            updated_docs.clone(),
+            state.clone(),
+        );
+        Box::pin(async move {
+            match (kind, board_doc) {
+                (TrashKind::Board, Some(board_doc)) => {
+                    state
+                        .get_boards_collection()
+                        .insert_one_with_session(board_doc, None, session)
+                        .await?;
+                }
+                (_, Some(board_doc)) => {
+                    let filter = doc! { "id": board_doc.get_str("id").unwrap_or_default() };
+                    state
+                        .get_boards_collection()
+                        .update_one_with_session(filter, doc! { "$set": board_doc }, None, session)
+                        .await?;
+                }
+                _ => {}
+            }
+            if !new_docs.is_empty() {
+                state
+                    .get_tasks_collection()
+                    .insert_many_with_session(new_docs, None, session)
+                    .await?;
+            }
+            task_service::update_many_with_session(
+                &state.get_tasks_collection(),
+                updated_docs,
+                session,
+            )
+            .await?;
+            restore_content_with_session(&state, &item_doc, session).await
+        })
+    })
+    .await?;
+    let mut activities = match board {
+        Some(board) => {
+            board_activities(stored_board.as_ref(), Some(&board), restored_by.as_deref())
+        }
+        None => vec![],
+    };
+    let tasks = new_tasks
+        .into_iter()
+        .chain(updated_tasks)
+        .collect::<Vec<Task>>();
+    activities.extend(task_activities(
+        &stored_tasks,
+        &tasks,
+        restored_by.as_deref(),
+    ));
+    activity_service::record(as_restored(activities), state.get_activity_collection()).await;
+    transition_service::record(
+        column_transitions(&stored_tasks, &tasks),
+        state.get_transitions_collection(),
+    )
+    .await;
+    Ok(())
+}
+
+/// Returns the deleted tasks at their place in the board, along with the tasks
+/// of the board that move to make room for them.
+async fn restored_tasks(item: &TrashItem, board: &Board, state: &AppState) -> Result<Vec<Task>> {
+    let board_tasks =
+        task_service::get_tasks(&item.board_id.to_string(), state.get_tasks_collection()).await?;
+    let column_ids = board
+        .columns
+        .iter()
+        .map(|c| c.id)
+        .collect::<Vec<uuid::Uuid>>();
+    let swimlane_ids = board
+        .swimlanes
+        .iter()
+        .map(|l| l.id)
+        .collect::<Vec<uuid::Uuid>>();
+    let linked_ids = item
+        .content
+        .tasks
+        .iter()
+        .flat_map(|t| t.blocked_by.iter().cloned().chain(t.parent_task_id))
+        .collect::<Vec<uuid::Uuid>>();
+    let existing = task_service::get_tasks_by_ids(&linked_ids, state.get_tasks_collection())
+        .await?
+        .iter()
+        .chain(item.content.tasks.iter())
+        .map(|t| t.id)
+        .collect::<Vec<uuid::Uuid>>();
+    let tasks = without_missing_links(&item.content.tasks, &existing)
+        .into_iter()
+        .map(|t| Task {
+            updated_at: Some(Utc::now()),
+            ..t
+        })
+        .collect::<Vec<Task>>();
+    let (restored, changed) = reinsert_tasks(&tasks, &board_tasks, &column_ids, &swimlane_ids);
+    Ok(restored.into_iter().chain(changed).collect())
+}
+
+/// Returns the board with the deleted column back at its position, along with
+/// the tasks it held that still exist moved back into it.
+async fn restored_column(
+    item: &TrashItem,
+    board: Board,
+    state: &AppState,
+) -> Result<(Option<Board>, Vec<Task>)> {
+    let column = item
+        .content
+        .column
+        .clone()
+        .ok_or(TrashRepoError::DecodeError(format!(
+            "Trash item {} doesnt have a column",
+            item.id
+        )))?;
+    if board.column(column.id).is_some() {
+        return Err(AppError::Conflict(format!(
+            "column {} exists already",
+            column.id
+        )));
+    }
+    let mut columns = board.columns.clone();
+    let position = item
+        .content
+        .position
+        .unwrap_or(columns.len())
+        .min(columns.len());
+    columns.insert(position, column);
+    let board = Board {
+        columns,
+        updated_at: Some(Utc::now()),
+        ..board
+    };
+    let board_tasks =
+        task_service::get_tasks(&item.board_id.to_string(), state.get_tasks_collection()).await?;
+    let current = board_tasks
+        .iter()
+        .filter(|t| t.archived_at.is_none())
+        .map(|t| (t.id, t))
+        .collect::<HashMap<uuid::Uuid, &Task>>();
+    let moved_back = item
+        .content
+        .tasks
+        .iter()
+        .filter_map(|t| {
+            current.get(&t.id).map(|c| Task {
+                column_id: t.column_id,
+                above_task_id: t.above_task_id,
+                updated_at: Some(Utc::now()),
+                ..(*c).clone()
+            })
+        })
+        .collect::<Vec<Task>>();
+    let moved_ids = moved_back.iter().map(|t| t.id).collect::<Vec<uuid::Uuid>>();
+    let mut updated = relink_without(&moved_ids, &board_tasks)
+        .into_iter()
+        .map(|t| (t.id, t))
+        .collect::<HashMap<uuid::Uuid, Task>>();
+    let remaining = board_tasks
+        .iter()
+        .filter(|t| !moved_ids.contains(&t.id))
+        .map(|t| updated.get(&t.id).unwrap_or(t).clone())
+        .collect::<Vec<Task>>();
+    let column_ids = board
+        .columns
+        .iter()
+        .map(|c| c.id)
+        .collect::<Vec<uuid::Uuid>>();
+    let swimlane_ids = board
+        .swimlanes
+        .iter()
+        .map(|l| l.id)
+        .collect::<Vec<uuid::Uuid>>();
+    let (restored, changed) = reinsert_tasks(&moved_back, &remaining, &column_ids, &swimlane_ids);
+    updated.extend(restored.into_iter().chain(changed).map(|t| (t.id, t)));
+    Ok((Some(board), updated.into_values().collect()))
+}
+
+/// Removes a trash item for good, along with the content of its attachments.
+pub async fn purge(item: &TrashItem, state: &AppState) -> Result<()> {
+    debug!("[purge] Purging trash item with id={}", item.id);
+    state
+        .get_trash_collection()
+        .delete_one(doc! { "id": item.id.to_string() }, None)
+        .await?;
+    attachment_service::delete_contents(&item.content.attachments, state.blob_store.as_ref()).await;
+    if item.kind == TrashKind::Board {
+        sprint_service::delete_of_board(
+            &item.entity_id.to_string(),
+            state.get_sprints_collection(),
+        )
+        .await?;
+        task_template_service::delete_of_board(
+            &item.entity_id.to_string(),
+            state.get_task_templates_collection(),
+        )
+        .await?;
+        for collection in [
+            state.get_automations_collection(),
+            state.get_automation_runs_collection(),
+        ] {
+            automation_service::delete_of_board(&item.entity_id.to_string(), collection).await?;
+        }
+        chat_service::delete_of_board(
+            &item.entity_id.to_string(),
+            state.get_chat_webhooks_collection(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Purges the items deleted more than `retention` ago, returns how many.
+pub async fn purge_expired(retention: chrono::Duration, state: &AppState) -> Result<usize> {
+    let expired = get_expired(Utc::now() - retention, state.get_trash_collection()).await?;
+    for item in expired.iter() {
+        if let Err(e) = purge(item, state).await {
+            error!(
+                "ERROR [purge_expired] Couldn't purge trash item {}: {:?}",
+                item.id, e
+            );
+        }
+    }
+    Ok(expired.len())
+}
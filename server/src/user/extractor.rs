@@ -0,0 +1,38 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::Json;
+
+use crate::dto::Response;
+
+/// Header carrying the username of the caller.
+/// There is no authentication in place, the frontend sends the name picked by the user.
+pub const USER_HEADER: &str = "x-user";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurrentUser(pub String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = (StatusCode, Json<Response<()>>);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .headers
+            .get(USER_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(|v| CurrentUser(v.to_owned()))
+            .ok_or((
+                StatusCode::UNAUTHORIZED,
+                Json(Response {
+                    success: false,
+                    data: None,
+                    error_message: Some(format!("Missing {} header", USER_HEADER)),
+                    warnings: None,
+                }),
+            ))
+    }
+}
@@ -7,3 +7,25 @@ pub fn get_optional_uuid(doc: &Document, field_name: &str) -> Option<uuid::Uuid>
         .ok()
         .and_then(|v| uuid::Uuid::from_str(v).ok())
 }
+
+pub fn get_uuid_list(doc: &Document, field_name: &str) -> Vec<uuid::Uuid> {
+    doc.get_array(field_name)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str().and_then(|v| uuid::Uuid::from_str(v).ok()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn get_string_list(doc: &Document, field_name: &str) -> Vec<String> {
+    doc.get_array(field_name)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str().map(|v| v.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default()
+}